@@ -0,0 +1,16 @@
+-- Add up migration script here
+CREATE TABLE IF NOT EXISTS audit_log (
+    id BIGINT PRIMARY KEY NOT NULL AUTO_INCREMENT,
+    actor_id INT(11) NULL,
+    actor VARCHAR(255) NULL,
+    action VARCHAR(50) NOT NULL,
+    target_user_id INT(11) NULL,
+    changes JSON NULL,
+    ip VARCHAR(45) NULL,
+    user_agent VARCHAR(512) NULL,
+    created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
+    INDEX idx_audit_log_actor_id (actor_id),
+    INDEX idx_audit_log_target_user_id (target_user_id),
+    INDEX idx_audit_log_action (action),
+    INDEX idx_audit_log_created_at (created_at)
+);
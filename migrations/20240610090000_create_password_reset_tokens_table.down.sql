@@ -0,0 +1,4 @@
+-- Add down migration script here
+DROP TABLE IF EXISTS password_reset_tokens;
+
+ALTER TABLE users DROP COLUMN token_version;
@@ -0,0 +1,4 @@
+-- Add down migration script here
+DROP TABLE IF EXISTS verification_codes;
+
+ALTER TABLE users DROP COLUMN phone_verified_at, DROP COLUMN email_verified_at;
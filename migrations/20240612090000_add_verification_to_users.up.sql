@@ -0,0 +1,19 @@
+-- Add up migration script here
+ALTER TABLE users
+    ADD COLUMN email_verified_at TIMESTAMP NULL AFTER token_version,
+    ADD COLUMN phone_verified_at TIMESTAMP NULL AFTER email_verified_at;
+
+CREATE TABLE IF NOT EXISTS verification_codes (
+    id BIGINT PRIMARY KEY NOT NULL AUTO_INCREMENT,
+    user_id INT(11) NOT NULL,
+    channel VARCHAR(10) NOT NULL,
+    target VARCHAR(255) NOT NULL,
+    code_hash CHAR(64) NOT NULL,
+    attempts INT(11) NOT NULL DEFAULT 0,
+    expires_at TIMESTAMP NOT NULL,
+    used_at TIMESTAMP NULL,
+    created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
+    INDEX idx_verification_codes_user_channel (user_id, channel, created_at),
+    INDEX idx_verification_codes_code_hash (code_hash),
+    FOREIGN KEY (user_id) REFERENCES users(id) ON DELETE CASCADE
+);
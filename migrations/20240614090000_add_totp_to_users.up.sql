@@ -0,0 +1,15 @@
+-- Add up migration script here
+ALTER TABLE users
+    ADD COLUMN totp_secret VARCHAR(255) NULL AFTER phone_verified_at,
+    ADD COLUMN totp_enabled_at TIMESTAMP NULL AFTER totp_secret,
+    ADD COLUMN totp_last_step BIGINT NULL AFTER totp_enabled_at;
+
+CREATE TABLE IF NOT EXISTS recovery_codes (
+    id BIGINT PRIMARY KEY NOT NULL AUTO_INCREMENT,
+    user_id INT(11) NOT NULL,
+    code_hash CHAR(64) NOT NULL UNIQUE,
+    used_at TIMESTAMP NULL,
+    created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
+    INDEX idx_recovery_codes_user_id (user_id),
+    FOREIGN KEY (user_id) REFERENCES users(id) ON DELETE CASCADE
+);
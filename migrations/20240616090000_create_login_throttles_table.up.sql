@@ -0,0 +1,11 @@
+-- Add up migration script here
+CREATE TABLE IF NOT EXISTS login_throttles (
+    id BIGINT PRIMARY KEY NOT NULL AUTO_INCREMENT,
+    scope VARCHAR(10) NOT NULL,
+    throttle_key VARCHAR(255) NOT NULL,
+    failures INT(11) NOT NULL DEFAULT 0,
+    last_failure_at TIMESTAMP NULL,
+    locked_until TIMESTAMP NULL,
+    updated_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP ON UPDATE CURRENT_TIMESTAMP,
+    UNIQUE KEY uq_login_throttles_scope_key (scope, throttle_key)
+);
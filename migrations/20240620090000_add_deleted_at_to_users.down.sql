@@ -0,0 +1,12 @@
+-- Add down migration script here
+DELETE FROM users WHERE deleted_at IS NOT NULL;
+
+ALTER TABLE users
+    DROP INDEX uq_users_username_alive,
+    DROP INDEX uq_users_email_alive,
+    DROP INDEX uq_users_phone_alive,
+    ADD UNIQUE INDEX username (username),
+    ADD UNIQUE INDEX email (email),
+    ADD UNIQUE INDEX phone (phone);
+
+ALTER TABLE users DROP INDEX idx_users_deleted_at, DROP COLUMN alive, DROP COLUMN deleted_at;
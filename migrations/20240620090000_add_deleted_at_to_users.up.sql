@@ -0,0 +1,15 @@
+-- Add up migration script here
+-- alive bernilai NULL untuk baris yang sudah dihapus, sehingga UNIQUE hanya
+-- berlaku di antara pengguna aktif dan email/username/phone dapat dipakai ulang
+ALTER TABLE users
+    ADD COLUMN deleted_at TIMESTAMP NULL AFTER totp_last_step,
+    ADD COLUMN alive TINYINT(1) GENERATED ALWAYS AS (IF(deleted_at IS NULL, 1, NULL)) STORED AFTER deleted_at,
+    ADD INDEX idx_users_deleted_at (deleted_at);
+
+ALTER TABLE users
+    DROP INDEX username,
+    DROP INDEX email,
+    DROP INDEX phone,
+    ADD UNIQUE INDEX uq_users_username_alive (username, alive),
+    ADD UNIQUE INDEX uq_users_email_alive (email, alive),
+    ADD UNIQUE INDEX uq_users_phone_alive (phone, alive);
@@ -0,0 +1,3 @@
+-- Add up migration script here
+-- Dinaikkan setiap kali data pengguna diubah, dipakai sebagai ETag
+ALTER TABLE users ADD COLUMN version INT(11) NOT NULL DEFAULT 1 AFTER token_version;
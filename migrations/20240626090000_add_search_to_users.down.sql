@@ -0,0 +1,5 @@
+-- Add down migration script here
+ALTER TABLE users
+    DROP INDEX ft_users_username_email,
+    DROP INDEX idx_users_phone_digits,
+    DROP COLUMN phone_digits;
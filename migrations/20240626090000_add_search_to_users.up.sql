@@ -0,0 +1,8 @@
+-- Add up migration script here
+-- phone_digits hanya berisi angka agar "0812-345" dan "+62 812 345" sama-sama ditemukan
+ALTER TABLE users
+    ADD COLUMN phone_digits VARCHAR(20) GENERATED ALWAYS AS (
+        REPLACE(REPLACE(REPLACE(REPLACE(REPLACE(REPLACE(phone, '+', ''), '-', ''), ' ', ''), '(', ''), ')', ''), '.', '')
+    ) STORED AFTER phone,
+    ADD INDEX idx_users_phone_digits (phone_digits),
+    ADD FULLTEXT INDEX ft_users_username_email (username, email);
@@ -0,0 +1,3 @@
+-- Add up migration script here
+-- Request yang macet (mis. proses mati) melepas key setelah locked_until lewat
+ALTER TABLE idempotency_keys ADD COLUMN locked_until TIMESTAMP NULL AFTER response;
@@ -0,0 +1,6 @@
+-- Add down migration script here
+ALTER TABLE users
+    DROP INDEX idx_users_created_at_id,
+    DROP INDEX idx_users_updated_at_id,
+    DROP INDEX idx_users_username_id,
+    DROP INDEX idx_users_email_id;
@@ -0,0 +1,7 @@
+-- Add up migration script here
+-- Keyset pagination (GET /api/user?cursor=) memakai ORDER BY (kolom sort, id)
+ALTER TABLE users
+    ADD INDEX idx_users_created_at_id (created_at, id),
+    ADD INDEX idx_users_updated_at_id (updated_at, id),
+    ADD INDEX idx_users_username_id (username, id),
+    ADD INDEX idx_users_email_id (email, id);
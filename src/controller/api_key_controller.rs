@@ -0,0 +1,115 @@
+use axum::{extract::Path, http::StatusCode, response::IntoResponse, Json};
+
+use crate::{
+    model::{
+        response::{ApiDataResponse, ApiMessage},
+        schema::CreateApiKeySchema,
+    },
+    service::api_key::api_key_service::{
+        create_api_key_service, get_api_keys_service, revoke_api_key_service,
+    },
+    utils::auth::AuthUser,
+};
+
+#[utoipa::path(
+    post,
+    path = "/keys",
+    tag = "api-key",
+    request_body = CreateApiKeySchema,
+    responses(
+        (status = 201, description = "API key created, the key is only shown once", body = ApiDataResponse),
+        (status = 400, description = "Invalid name, scopes or expiry", body = ApiMessage)
+    ),
+    security(("bearerAuth" = []), ("apiKeyAuth" = []))
+)]
+pub async fn create_api_key_controller(
+    user: AuthUser,
+    Json(body): Json<CreateApiKeySchema>,
+) -> impl IntoResponse {
+    let res = create_api_key_service(&user, axum::Json(body)).await;
+    match res {
+        Ok((api_key, key)) => {
+            let json_response = serde_json::json!({
+                "status": "ok",
+                "message": "API key created, store it safely because it will not be shown again",
+                "data": {
+                    "key": key,
+                    "api_key": api_key
+                }
+            });
+
+            (StatusCode::CREATED, Json(json_response))
+        }
+        Err(e) => {
+            let json_response = serde_json::json!({
+                "status": "error",
+                "message": e
+            });
+
+            (StatusCode::BAD_REQUEST, Json(json_response))
+        }
+    }
+}
+
+#[utoipa::path(
+    get,
+    path = "/keys",
+    tag = "api-key",
+    responses((status = 200, description = "API keys of the current user", body = ApiDataResponse)),
+    security(("bearerAuth" = []), ("apiKeyAuth" = []))
+)]
+pub async fn get_api_keys_controller(user: AuthUser) -> impl IntoResponse {
+    let res = get_api_keys_service(&user).await;
+    match res {
+        Ok(api_keys) => {
+            let json_response = serde_json::json!({
+                "status": "ok",
+                "message": "API keys fetched successfully",
+                "data": api_keys
+            });
+
+            (StatusCode::OK, Json(json_response))
+        }
+        Err(e) => {
+            let json_response = serde_json::json!({
+                "status": "error",
+                "message": e
+            });
+
+            (StatusCode::INTERNAL_SERVER_ERROR, Json(json_response))
+        }
+    }
+}
+
+#[utoipa::path(
+    delete,
+    path = "/keys/{id}",
+    tag = "api-key",
+    params(("id" = i64, Path, description = "API key id")),
+    responses(
+        (status = 200, description = "API key revoked", body = ApiMessage),
+        (status = 404, description = "API key does not exist", body = ApiMessage)
+    ),
+    security(("bearerAuth" = []), ("apiKeyAuth" = []))
+)]
+pub async fn revoke_api_key_controller(user: AuthUser, Path(id): Path<i64>) -> impl IntoResponse {
+    let res = revoke_api_key_service(&user, id).await;
+    match res {
+        Ok(_) => {
+            let json_response = serde_json::json!({
+                "status": "ok",
+                "message": "API key revoked successfully"
+            });
+
+            (StatusCode::OK, Json(json_response))
+        }
+        Err(e) => {
+            let json_response = serde_json::json!({
+                "status": "error",
+                "message": e
+            });
+
+            (StatusCode::NOT_FOUND, Json(json_response))
+        }
+    }
+}
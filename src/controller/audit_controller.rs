@@ -0,0 +1,53 @@
+use axum::{extract::Query, http::StatusCode, response::IntoResponse, Json};
+
+use crate::{
+    model::{
+        response::{ApiDataResponse, ApiMessage},
+        schema::AuditFilterOptions,
+    },
+    service::audit::audit_service::get_audit_logs_service,
+    utils::auth::{forbidden, VerifiedUser},
+};
+
+#[utoipa::path(
+    get,
+    path = "/audit",
+    tag = "audit",
+    params(AuditFilterOptions),
+    responses(
+        (status = 200, description = "Audit log entries", body = ApiDataResponse),
+        (status = 403, description = "Requires admin or the audit:read scope", body = ApiMessage)
+    ),
+    security(("bearerAuth" = []), ("apiKeyAuth" = []))
+)]
+pub async fn get_audit_logs_controller(
+    VerifiedUser(user): VerifiedUser,
+    opts: Option<Query<AuditFilterOptions>>,
+) -> impl IntoResponse {
+    if !user.is_admin_for("audit:read") {
+        return forbidden();
+    }
+
+    let Query(opts) = opts.unwrap_or_default();
+
+    let res = get_audit_logs_service(opts).await;
+    match res {
+        Ok(logs) => {
+            let json_response = serde_json::json!({
+                "status": "ok",
+                "message": "Audit logs fetched successfully",
+                "data": logs
+            });
+
+            (StatusCode::OK, Json(json_response))
+        }
+        Err(e) => {
+            let json_response = serde_json::json!({
+                "status": "error",
+                "message": e
+            });
+
+            (StatusCode::INTERNAL_SERVER_ERROR, Json(json_response))
+        }
+    }
+}
@@ -0,0 +1,156 @@
+use axum::{
+    http::{header::RETRY_AFTER, HeaderMap, HeaderValue, StatusCode},
+    response::IntoResponse,
+    Json,
+};
+
+use crate::{
+    model::{
+        response::{ApiMessage, ApiResponse, LoginData},
+        schema::{ForgotPasswordSchema, LoginSchema, ResetPasswordSchema},
+    },
+    service::auth::auth_service::{
+        forgot_password_service, login_service, reset_password_service, LoginOutcome,
+    },
+};
+
+#[utoipa::path(
+    post,
+    path = "/auth/login",
+    tag = "auth",
+    request_body = LoginSchema,
+    responses(
+        (status = 200, description = "Login token, or an MFA token when 2FA is enabled", body = ApiResponse<LoginData>),
+        (status = 401, description = "Invalid username or password", body = ApiMessage),
+        (status = 429, description = "Too many failed attempts", body = ApiMessage,
+            headers(("Retry-After" = u64, description = "Seconds"))),
+        (status = 500, description = "Database or password hashing failure", body = ApiMessage)
+    )
+)]
+pub async fn login_controller(Json(body): Json<LoginSchema>) -> impl IntoResponse {
+    let mut headers = HeaderMap::new();
+    let res = login_service(axum::Json(body)).await;
+    match res {
+        Ok(LoginOutcome::Token(token, expires_in)) => {
+            let json_response = serde_json::json!({
+                "status": "ok",
+                "message": "Login successful",
+                "data": {
+                    "token": token,
+                    "token_type": "Bearer",
+                    "expires_in": expires_in
+                }
+            });
+
+            (StatusCode::OK, headers, Json(json_response))
+        }
+        Ok(LoginOutcome::MfaRequired(mfa_token, expires_in)) => {
+            let json_response = serde_json::json!({
+                "status": "ok",
+                "message": "Two-factor authentication required",
+                "data": {
+                    "mfa_required": true,
+                    "mfa_token": mfa_token,
+                    "expires_in": expires_in
+                }
+            });
+
+            (StatusCode::OK, headers, Json(json_response))
+        }
+        Ok(LoginOutcome::Throttled(retry_after)) => {
+            headers.insert(RETRY_AFTER, HeaderValue::from(retry_after));
+            let json_response = serde_json::json!({
+                "status": "error",
+                "message": "Too many failed login attempts, please try again later"
+            });
+
+            (StatusCode::TOO_MANY_REQUESTS, headers, Json(json_response))
+        }
+        Ok(LoginOutcome::Rejected(message)) => {
+            let json_response = serde_json::json!({
+                "status": "error",
+                "message": message
+            });
+
+            (StatusCode::UNAUTHORIZED, headers, Json(json_response))
+        }
+        Err(e) => {
+            let json_response = serde_json::json!({
+                "status": "error",
+                "message": e
+            });
+
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                headers,
+                Json(json_response),
+            )
+        }
+    }
+}
+
+#[utoipa::path(
+    post,
+    path = "/auth/forgot-password",
+    tag = "auth",
+    request_body = ForgotPasswordSchema,
+    responses(
+        (status = 200, description = "Reset link sent if the email is registered", body = ApiMessage),
+        (status = 500, description = "Database failure", body = ApiMessage)
+    )
+)]
+pub async fn forgot_password_controller(
+    Json(body): Json<ForgotPasswordSchema>,
+) -> impl IntoResponse {
+    let res = forgot_password_service(axum::Json(body)).await;
+    match res {
+        Ok(_) => {
+            let json_response = serde_json::json!({
+                "status": "ok",
+                "message": "If the email is registered, a password reset link has been sent"
+            });
+
+            (StatusCode::OK, Json(json_response))
+        }
+        Err(e) => {
+            let json_response = serde_json::json!({
+                "status": "error",
+                "message": e
+            });
+
+            (StatusCode::INTERNAL_SERVER_ERROR, Json(json_response))
+        }
+    }
+}
+
+#[utoipa::path(
+    post,
+    path = "/auth/reset-password",
+    tag = "auth",
+    request_body = ResetPasswordSchema,
+    responses(
+        (status = 200, description = "Password reset", body = ApiMessage),
+        (status = 400, description = "Invalid or expired token, or weak password", body = ApiMessage)
+    )
+)]
+pub async fn reset_password_controller(Json(body): Json<ResetPasswordSchema>) -> impl IntoResponse {
+    let res = reset_password_service(axum::Json(body)).await;
+    match res {
+        Ok(_) => {
+            let json_response = serde_json::json!({
+                "status": "ok",
+                "message": "Password has been reset successfully"
+            });
+
+            (StatusCode::OK, Json(json_response))
+        }
+        Err(e) => {
+            let json_response = serde_json::json!({
+                "status": "error",
+                "message": e
+            });
+
+            (StatusCode::BAD_REQUEST, Json(json_response))
+        }
+    }
+}
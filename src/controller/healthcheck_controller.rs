@@ -1,7 +1,13 @@
 use axum::{response::IntoResponse, Json};
 
-use crate::service::healthcheck_service::healthcheck_service;
+use crate::{model::response::ApiMessage, service::healthcheck_service::healthcheck_service};
 
+#[utoipa::path(
+    get,
+    path = "/healthcheck",
+    tag = "system",
+    responses((status = 200, description = "Service is up", body = ApiMessage))
+)]
 pub async fn healthcheck_controller() -> impl IntoResponse {
     let message = healthcheck_service().await;
 
@@ -0,0 +1,37 @@
+use axum::{
+    http::{header::CONTENT_TYPE, StatusCode},
+    response::IntoResponse,
+};
+use std::sync::Arc;
+use tokio::sync::OnceCell;
+
+use crate::utils::{
+    metrics::metrics,
+    singleton::{init_app_state, AppState},
+};
+
+static APP_STATE: OnceCell<Arc<AppState>> = OnceCell::const_new();
+
+#[utoipa::path(
+    get,
+    path = "/metrics",
+    tag = "system",
+    responses((status = 200, description = "Prometheus metrics", content_type = "text/plain", body = String))
+)]
+pub async fn metrics_controller() -> impl IntoResponse {
+    let app_state: Arc<AppState> = APP_STATE.get_or_init(init_app_state).await.clone();
+    metrics().observe_pool(&app_state.db);
+
+    match metrics().render() {
+        Ok(body) => (
+            StatusCode::OK,
+            [(CONTENT_TYPE, "text/plain; version=0.0.4")],
+            body,
+        ),
+        Err(e) => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            [(CONTENT_TYPE, "text/plain; charset=utf-8")],
+            e,
+        ),
+    }
+}
@@ -0,0 +1,194 @@
+use axum::{http::StatusCode, response::IntoResponse, Json};
+
+use crate::{
+    model::{
+        response::{ApiDataResponse, ApiMessage, ApiResponse, TokenData},
+        schema::{ConfirmMfaSchema, DisableMfaSchema, MfaChallengeSchema},
+    },
+    service::auth::mfa_service::{
+        confirm_mfa_service, disable_mfa_service, enroll_mfa_service, mfa_challenge_service,
+        mfa_status_service,
+    },
+    utils::auth::AuthUser,
+};
+
+#[utoipa::path(
+    get,
+    path = "/auth/2fa",
+    tag = "2fa",
+    responses((status = 200, description = "Whether 2FA is enabled and recovery codes left", body = ApiDataResponse)),
+    security(("bearerAuth" = []), ("apiKeyAuth" = []))
+)]
+pub async fn mfa_status_controller(user: AuthUser) -> impl IntoResponse {
+    let res = mfa_status_service(user.id).await;
+    match res {
+        Ok((enabled, recovery_codes_remaining)) => {
+            let json_response = serde_json::json!({
+                "status": "ok",
+                "message": "Two-factor authentication status fetched successfully",
+                "data": {
+                    "enabled": enabled,
+                    "recovery_codes_remaining": recovery_codes_remaining
+                }
+            });
+
+            (StatusCode::OK, Json(json_response))
+        }
+        Err(e) => {
+            let json_response = serde_json::json!({
+                "status": "error",
+                "message": e
+            });
+
+            (StatusCode::BAD_REQUEST, Json(json_response))
+        }
+    }
+}
+
+#[utoipa::path(
+    post,
+    path = "/auth/2fa/enroll",
+    tag = "2fa",
+    responses(
+        (status = 200, description = "TOTP secret and otpauth URI", body = ApiDataResponse),
+        (status = 400, description = "2FA already enabled", body = ApiMessage)
+    ),
+    security(("bearerAuth" = []), ("apiKeyAuth" = []))
+)]
+pub async fn enroll_mfa_controller(user: AuthUser) -> impl IntoResponse {
+    let res = enroll_mfa_service(user.id).await;
+    match res {
+        Ok((secret, otpauth_uri)) => {
+            let json_response = serde_json::json!({
+                "status": "ok",
+                "message": "Scan the URI with an authenticator app, then confirm with a code",
+                "data": {
+                    "secret": secret,
+                    "otpauth_uri": otpauth_uri
+                }
+            });
+
+            (StatusCode::OK, Json(json_response))
+        }
+        Err(e) => {
+            let json_response = serde_json::json!({
+                "status": "error",
+                "message": e
+            });
+
+            (StatusCode::BAD_REQUEST, Json(json_response))
+        }
+    }
+}
+
+#[utoipa::path(
+    post,
+    path = "/auth/2fa/confirm",
+    tag = "2fa",
+    request_body = ConfirmMfaSchema,
+    responses(
+        (status = 200, description = "2FA enabled, recovery codes returned once", body = ApiDataResponse),
+        (status = 400, description = "Invalid code", body = ApiMessage)
+    ),
+    security(("bearerAuth" = []), ("apiKeyAuth" = []))
+)]
+pub async fn confirm_mfa_controller(
+    user: AuthUser,
+    Json(body): Json<ConfirmMfaSchema>,
+) -> impl IntoResponse {
+    let res = confirm_mfa_service(user.id, axum::Json(body)).await;
+    match res {
+        Ok(recovery_codes) => {
+            let json_response = serde_json::json!({
+                "status": "ok",
+                "message": "Two-factor authentication enabled, store the recovery codes safely",
+                "data": {
+                    "recovery_codes": recovery_codes
+                }
+            });
+
+            (StatusCode::OK, Json(json_response))
+        }
+        Err(e) => {
+            let json_response = serde_json::json!({
+                "status": "error",
+                "message": e
+            });
+
+            (StatusCode::BAD_REQUEST, Json(json_response))
+        }
+    }
+}
+
+#[utoipa::path(
+    post,
+    path = "/auth/2fa/challenge",
+    tag = "2fa",
+    request_body = MfaChallengeSchema,
+    responses(
+        (status = 200, description = "Login token", body = ApiResponse<TokenData>),
+        (status = 401, description = "Invalid MFA token or code", body = ApiMessage)
+    )
+)]
+pub async fn mfa_challenge_controller(Json(body): Json<MfaChallengeSchema>) -> impl IntoResponse {
+    let res = mfa_challenge_service(axum::Json(body)).await;
+    match res {
+        Ok((token, expires_in)) => {
+            let json_response = serde_json::json!({
+                "status": "ok",
+                "message": "Login successful",
+                "data": {
+                    "token": token,
+                    "token_type": "Bearer",
+                    "expires_in": expires_in
+                }
+            });
+
+            (StatusCode::OK, Json(json_response))
+        }
+        Err(e) => {
+            let json_response = serde_json::json!({
+                "status": "error",
+                "message": e
+            });
+
+            (StatusCode::UNAUTHORIZED, Json(json_response))
+        }
+    }
+}
+
+#[utoipa::path(
+    post,
+    path = "/auth/2fa/disable",
+    tag = "2fa",
+    request_body = DisableMfaSchema,
+    responses(
+        (status = 200, description = "2FA disabled", body = ApiMessage),
+        (status = 400, description = "Invalid password or code", body = ApiMessage)
+    ),
+    security(("bearerAuth" = []), ("apiKeyAuth" = []))
+)]
+pub async fn disable_mfa_controller(
+    user: AuthUser,
+    Json(body): Json<DisableMfaSchema>,
+) -> impl IntoResponse {
+    let res = disable_mfa_service(user.id, axum::Json(body)).await;
+    match res {
+        Ok(_) => {
+            let json_response = serde_json::json!({
+                "status": "ok",
+                "message": "Two-factor authentication disabled"
+            });
+
+            (StatusCode::OK, Json(json_response))
+        }
+        Err(e) => {
+            let json_response = serde_json::json!({
+                "status": "error",
+                "message": e
+            });
+
+            (StatusCode::BAD_REQUEST, Json(json_response))
+        }
+    }
+}
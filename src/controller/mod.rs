@@ -1,2 +1,15 @@
+pub mod api_key_controller;
+pub mod audit_controller;
+pub mod auth_controller;
 pub mod healthcheck_controller;
-pub mod user_controller;
\ No newline at end of file
+pub mod metrics_controller;
+pub mod mfa_controller;
+pub mod openapi_controller;
+pub mod replay_controller;
+pub mod resource_controller;
+pub mod simulation_controller;
+pub mod user_controller;
+pub mod user_import_controller;
+pub mod user_v2_controller;
+pub mod verification_controller;
+pub mod ws_controller;
\ No newline at end of file
@@ -0,0 +1,14 @@
+use axum::{response::IntoResponse, Json};
+
+use crate::service::openapi_service::openapi_document;
+
+// UI Swagger di /api/docs membaca dokumen ini
+#[utoipa::path(
+    get,
+    path = "/api/openapi.json",
+    tag = "system",
+    responses((status = 200, description = "This OpenAPI 3.1 document", body = Object))
+)]
+pub async fn openapi_controller() -> impl IntoResponse {
+    Json(openapi_document())
+}
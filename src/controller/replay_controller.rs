@@ -0,0 +1,45 @@
+use axum::{
+    body::to_bytes,
+    extract::Request,
+    http::{HeaderName, HeaderValue, StatusCode},
+    response::{IntoResponse, Response},
+    Json,
+};
+
+use crate::utils::recording::{body_value, query_map, replay, replay_response};
+
+// Penanda apakah response berasal dari rekaman
+const X_REPLAY: HeaderName = HeaderName::from_static("x-replay");
+
+// Fallback untuk mode --replay: semua request dijawab dari REPLAY_FILE
+pub async fn replay_controller(req: Request) -> Response {
+    let (parts, body) = req.into_parts();
+    let bytes = match to_bytes(body, usize::MAX).await {
+        Ok(bytes) => bytes,
+        Err(err) => return (StatusCode::BAD_REQUEST, err.to_string()).into_response(),
+    };
+    let method = parts.method.to_string();
+    let path = parts.uri.path();
+    let query = query_map(&parts.uri);
+    let body = body_value(&parts.headers, &bytes);
+
+    let Some(recording) = replay().find(&method, path, &query, &body) else {
+        let json_response = serde_json::json!({
+            "status": "error",
+            "message": format!("No recorded response matches {} {}", method, path)
+        });
+
+        let mut response = (StatusCode::NOT_FOUND, Json(json_response)).into_response();
+        response
+            .headers_mut()
+            .insert(X_REPLAY, HeaderValue::from_static("miss"));
+        return response;
+    };
+
+    tracing::info!(id = %recording.id, "Replaying recorded response");
+    let mut response = replay_response(recording);
+    response
+        .headers_mut()
+        .insert(X_REPLAY, HeaderValue::from_static("hit"));
+    response
+}
@@ -0,0 +1,221 @@
+use axum::{
+    extract::{Path, Query},
+    http::{header::LOCATION, StatusCode},
+    response::{IntoResponse, Response},
+    Extension, Json,
+};
+use std::sync::Arc;
+
+use crate::{
+    model::{
+        resource::{resources, Access, ResourceAction, ResourceSchema},
+        response::ApiDataResponse,
+        schema::ResourceListQuery,
+    },
+    service::resource::{
+        resource_openapi_service::resource_openapi_document,
+        resource_service::{
+            create_resource_service, delete_resource_service, get_resource_service,
+            list_resources_service, update_resource_service,
+        },
+    },
+    utils::auth::{forbidden, AuthUser},
+};
+
+fn error_response(status: StatusCode, message: &str) -> Response {
+    let json_response = serde_json::json!({
+        "status": "error",
+        "message": message
+    });
+
+    (status, Json(json_response)).into_response()
+}
+
+fn not_found(schema: &ResourceSchema, id: u64) -> Response {
+    error_response(
+        StatusCode::NOT_FOUND,
+        &format!("{} with ID: {} not found", schema.name, id),
+    )
+}
+
+// Izin per aksi dari bagian permissions di file schema, Some berisi response penolakan
+fn denied(
+    schema: &ResourceSchema,
+    action: ResourceAction,
+    user: Option<&AuthUser>,
+) -> Option<Response> {
+    match (schema.permissions.access(action), user) {
+        (Access::Public, _) => None,
+        (_, None) => Some(error_response(
+            StatusCode::UNAUTHORIZED,
+            "Authentication required",
+        )),
+        (Access::User, Some(_)) => None,
+        (Access::Admin, Some(user)) if user.is_admin() => None,
+        (Access::Admin, Some(_)) => Some(forbidden().into_response()),
+    }
+}
+
+#[tracing::instrument(skip_all, fields(resource = %schema.name))]
+pub async fn list_resources_controller(
+    Extension(schema): Extension<Arc<ResourceSchema>>,
+    user: Option<AuthUser>,
+    query: Option<Query<ResourceListQuery>>,
+) -> Response {
+    if let Some(response) = denied(&schema, ResourceAction::List, user.as_ref()) {
+        return response;
+    }
+
+    let Query(query) = query.unwrap_or_default();
+    match list_resources_service(&schema, query.page, query.limit).await {
+        Ok(page) => {
+            let json_response = serde_json::json!({
+                "status": "ok",
+                "message": format!("{} fetched successfully", schema.name),
+                "data": page.items,
+                "pagination": {
+                    "page": page.page,
+                    "limit": page.limit,
+                    "has_more": page.has_more
+                }
+            });
+
+            (StatusCode::OK, Json(json_response)).into_response()
+        }
+        Err(e) => error_response(StatusCode::INTERNAL_SERVER_ERROR, &e),
+    }
+}
+
+#[tracing::instrument(skip_all, fields(resource = %schema.name, id = id))]
+pub async fn get_resource_controller(
+    Extension(schema): Extension<Arc<ResourceSchema>>,
+    user: Option<AuthUser>,
+    Path(id): Path<u64>,
+) -> Response {
+    if let Some(response) = denied(&schema, ResourceAction::Read, user.as_ref()) {
+        return response;
+    }
+
+    match get_resource_service(&schema, id).await {
+        Ok(Some(item)) => {
+            let json_response = serde_json::json!({
+                "status": "ok",
+                "message": format!("{} fetched successfully", schema.name),
+                "data": item
+            });
+
+            (StatusCode::OK, Json(json_response)).into_response()
+        }
+        Ok(None) => not_found(&schema, id),
+        Err(e) => error_response(StatusCode::INTERNAL_SERVER_ERROR, &e),
+    }
+}
+
+#[tracing::instrument(skip_all, fields(resource = %schema.name))]
+pub async fn create_resource_controller(
+    Extension(schema): Extension<Arc<ResourceSchema>>,
+    user: Option<AuthUser>,
+    Json(body): Json<serde_json::Value>,
+) -> Response {
+    if let Some(response) = denied(&schema, ResourceAction::Create, user.as_ref()) {
+        return response;
+    }
+
+    match create_resource_service(&schema, &body).await {
+        Ok(item) => {
+            let location = format!("{}/{}", schema.path(), item["id"]);
+            let json_response = serde_json::json!({
+                "status": "ok",
+                "message": format!("{} created successfully", schema.name),
+                "data": item
+            });
+
+            (
+                StatusCode::CREATED,
+                [(LOCATION, location)],
+                Json(json_response),
+            )
+                .into_response()
+        }
+        Err(e) => error_response(StatusCode::BAD_REQUEST, &e),
+    }
+}
+
+#[tracing::instrument(skip_all, fields(resource = %schema.name, id = id))]
+pub async fn update_resource_controller(
+    Extension(schema): Extension<Arc<ResourceSchema>>,
+    user: Option<AuthUser>,
+    Path(id): Path<u64>,
+    Json(body): Json<serde_json::Value>,
+) -> Response {
+    if let Some(response) = denied(&schema, ResourceAction::Update, user.as_ref()) {
+        return response;
+    }
+
+    match update_resource_service(&schema, id, &body).await {
+        Ok(Some(item)) => {
+            let json_response = serde_json::json!({
+                "status": "ok",
+                "message": format!("{} updated successfully", schema.name),
+                "data": item
+            });
+
+            (StatusCode::OK, Json(json_response)).into_response()
+        }
+        Ok(None) => not_found(&schema, id),
+        Err(e) => error_response(StatusCode::BAD_REQUEST, &e),
+    }
+}
+
+#[tracing::instrument(skip_all, fields(resource = %schema.name, id = id))]
+pub async fn delete_resource_controller(
+    Extension(schema): Extension<Arc<ResourceSchema>>,
+    user: Option<AuthUser>,
+    Path(id): Path<u64>,
+) -> Response {
+    if let Some(response) = denied(&schema, ResourceAction::Delete, user.as_ref()) {
+        return response;
+    }
+
+    match delete_resource_service(&schema, id).await {
+        Ok(true) => {
+            let json_response = serde_json::json!({
+                "status": "ok",
+                "message": format!("{} deleted successfully", schema.name)
+            });
+
+            (StatusCode::OK, Json(json_response)).into_response()
+        }
+        Ok(false) => not_found(&schema, id),
+        Err(e) => error_response(StatusCode::INTERNAL_SERVER_ERROR, &e),
+    }
+}
+
+// Daftar resource yang dimuat dari RESOURCE_DIR
+#[utoipa::path(
+    get,
+    path = "/resources",
+    tag = "resource",
+    responses((status = 200, description = "Loaded resource schemas", body = ApiDataResponse))
+)]
+#[tracing::instrument(skip_all)]
+pub async fn get_resource_schemas_controller() -> Response {
+    let json_response = serde_json::json!({
+        "status": "ok",
+        "message": "Resource schemas fetched successfully",
+        "data": resources()
+    });
+
+    (StatusCode::OK, Json(json_response)).into_response()
+}
+
+#[utoipa::path(
+    get,
+    path = "/resources/openapi.json",
+    tag = "resource",
+    responses((status = 200, description = "OpenAPI document of the resources only", body = Object))
+)]
+#[tracing::instrument(skip_all)]
+pub async fn resource_openapi_controller() -> Response {
+    Json(resource_openapi_document(resources())).into_response()
+}
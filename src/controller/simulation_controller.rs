@@ -0,0 +1,59 @@
+use axum::{
+    http::StatusCode,
+    response::{IntoResponse, Response},
+    Json,
+};
+
+use crate::{
+    service::simulation::simulation_service::reset_simulation_service,
+    utils::{
+        auth::{forbidden, AuthUser},
+        simulation::{simulation_calls, simulation_config},
+    },
+};
+
+// Route admin khusus mode --simulate, route lain memakai controller biasa
+
+// Konfigurasi aktif dan jumlah pemanggilan per route
+pub async fn get_simulation_controller(user: AuthUser) -> Response {
+    if !user.is_admin() {
+        return forbidden().into_response();
+    }
+
+    let json_response = serde_json::json!({
+        "status": "ok",
+        "message": "Simulation state fetched successfully",
+        "data": {
+            "config": simulation_config(),
+            "calls": simulation_calls()
+        }
+    });
+
+    (StatusCode::OK, Json(json_response)).into_response()
+}
+
+pub async fn reset_simulation_controller(user: AuthUser) -> Response {
+    if !user.is_admin() {
+        return forbidden().into_response();
+    }
+
+    match reset_simulation_service().await {
+        Ok(users) => {
+            let json_response = serde_json::json!({
+                "status": "ok",
+                "message": "Simulation state reset successfully",
+                "data": { "users": users }
+            });
+
+            (StatusCode::OK, Json(json_response)).into_response()
+        }
+        Err(e) => {
+            let json_response = serde_json::json!({
+                "status": "error",
+                "message": e
+            });
+
+            (StatusCode::INTERNAL_SERVER_ERROR, Json(json_response)).into_response()
+        }
+    }
+}
@@ -1,31 +1,82 @@
 use axum::{
+    body::Body,
     extract::{Path, Query, State},
-    response::IntoResponse,
+    http::{
+        header::{CONTENT_DISPOSITION, CONTENT_TYPE, ETAG, IF_MATCH, IF_NONE_MATCH, LINK},
+        HeaderMap, HeaderValue, StatusCode,
+    },
+    response::{IntoResponse, Response},
     Json,
 };
 use std::sync::Arc;
 use tokio::sync::OnceCell;
 
 use crate::{
-    model::schema::{CreateUpdateUserSchema, FilterOptions},
-    service::user::user_service::{
-        create_user_service, delete_user_by_id_service, get_all_user_service,
-        get_user_by_id_service, update_user_service,
+    model::{
+        model::UserModel,
+        response::{ApiDataResponse, ApiMessage, ApiResponse, UserListResponse},
+        schema::{
+            CreateUpdateUserSchema, ExportUsersQuery, FilterOptions, PatchUserSchema,
+            SearchUsersQuery,
+        },
+    },
+    service::user::{
+        user_export_service::{export_users_service, parse_columns, ExportFormat},
+        user_search_service::search_users_service,
+        user_service::{
+            create_user_service, delete_user_by_id_service, get_all_user_service,
+            get_user_by_id_service, get_user_presence_service, get_users_page_service,
+            patch_user_service, restore_user_service, unlock_user_service, update_user_service,
+            WriteOutcome,
+        },
+    },
+    utils::{
+        auth::{forbidden, AuthUser},
+        cursor::{link_header, Sort},
+        etag::{etag, EntityTags},
+        idempotency::{self, idempotency_key, Run, IDEMPOTENT_REPLAYED},
+        singleton::{init_app_state, AppState},
+        versioning::API_V1,
     },
-    utils::singleton::{init_app_state, AppState},
 };
 
 static APP_STATE: OnceCell<Arc<AppState>> = OnceCell::const_new();
 
-pub async fn get_all_user_controller(opts: Option<Query<FilterOptions>>) -> impl IntoResponse {
+#[utoipa::path(
+    get,
+    path = "/user",
+    tag = "user",
+    params(FilterOptions),
+    responses(
+        (status = 200, description = "Users, paginated when page, limit or cursor is given", body = UserListResponse,
+            headers(("Link" = String, description = "next/prev cursor links"))),
+        (status = 400, description = "Invalid page, limit, cursor or sort", body = ApiMessage),
+        (status = 403, description = "include_deleted requires admin", body = ApiMessage)
+    ),
+    security((), ("bearerAuth" = []), ("apiKeyAuth" = []))
+)]
+#[tracing::instrument(skip_all)]
+pub async fn get_all_user_controller(
+    user: Option<AuthUser>,
+    opts: Option<Query<FilterOptions>>,
+) -> Response {
     let app_state: Arc<AppState> = APP_STATE.get_or_init(init_app_state).await.clone();
     let data: State<Arc<AppState>> = State(app_state);
 
-    let Query(_opts) = opts.unwrap_or_default();
+    let Query(opts) = opts.unwrap_or_default();
+    let include_deleted = opts.include_deleted.unwrap_or(false);
+    if include_deleted && !user.is_some_and(|user| user.is_admin()) {
+        return forbidden().into_response();
+    }
 
     data.observable.notify_crud("GET", "All Users");
 
-    let res = get_all_user_service().await;
+    // Tanpa page, limit atau cursor semua pengguna dikembalikan seperti sebelumnya
+    if opts.page.is_some() || opts.limit.is_some() || opts.cursor.is_some() {
+        return get_users_page(&opts).await;
+    }
+
+    let res = get_all_user_service(include_deleted).await;
     match res {
         Ok(_) => {
             let json_response = serde_json::json!({
@@ -34,7 +85,7 @@ pub async fn get_all_user_controller(opts: Option<Query<FilterOptions>>) -> impl
                 "data": res.ok()
             });
 
-            Json(json_response)
+            (StatusCode::OK, Json(json_response)).into_response()
         }
         Err(e) => {
             let json_response = serde_json::json!({
@@ -42,26 +93,189 @@ pub async fn get_all_user_controller(opts: Option<Query<FilterOptions>>) -> impl
                 "message": e
             });
 
-            Json(json_response)
+            (StatusCode::OK, Json(json_response)).into_response()
+        }
+    }
+}
+
+async fn get_users_page(opts: &FilterOptions) -> Response {
+    let page = match get_users_page_service(opts).await {
+        Ok(page) => page,
+        Err(e) => return error_response(StatusCode::BAD_REQUEST, &e),
+    };
+
+    let mut params = vec![
+        ("limit", page.limit.to_string()),
+        ("sort", page.sort.as_param()),
+    ];
+    if opts.include_deleted.unwrap_or(false) {
+        params.push(("include_deleted", "true".to_string()));
+    }
+    let mut headers = HeaderMap::new();
+    let link = link_header(
+        &format!("{}/user", API_V1),
+        &params,
+        page.next_cursor.as_deref(),
+        page.prev_cursor.as_deref(),
+    );
+    if let Some(link) = link.and_then(|link| HeaderValue::from_str(&link).ok()) {
+        headers.insert(LINK, link);
+    }
+
+    let json_response = serde_json::json!({
+        "status": "ok",
+        "message": "Users fetched successfully",
+        "data": page.users,
+        "pagination": {
+            "limit": page.limit,
+            "sort": page.sort.as_param(),
+            "next_cursor": page.next_cursor,
+            "prev_cursor": page.prev_cursor
+        }
+    });
+
+    (StatusCode::OK, headers, Json(json_response)).into_response()
+}
+
+// Response di-stream langsung dari database
+#[utoipa::path(
+    get,
+    path = "/user/export",
+    tag = "user",
+    params(ExportUsersQuery),
+    responses(
+        (status = 200, description = "Users streamed as a file",
+            content(("application/json"), ("application/x-ndjson"), ("text/csv"))),
+        (status = 400, description = "Invalid format, columns or sort", body = ApiMessage),
+        (status = 403, description = "Admin privileges required", body = ApiMessage)
+    ),
+    security(("bearerAuth" = []), ("apiKeyAuth" = []))
+)]
+#[tracing::instrument(skip_all)]
+pub async fn export_users_controller(
+    user: AuthUser,
+    query: Option<Query<ExportUsersQuery>>,
+) -> Response {
+    if !user.is_admin() {
+        return forbidden().into_response();
+    }
+
+    let Query(query) = query.unwrap_or_default();
+    let format = match ExportFormat::parse(query.format.as_deref()) {
+        Ok(format) => format,
+        Err(e) => return error_response(StatusCode::BAD_REQUEST, &e),
+    };
+    let columns = match parse_columns(query.columns.as_deref()) {
+        Ok(columns) => columns,
+        Err(e) => return error_response(StatusCode::BAD_REQUEST, &e),
+    };
+
+    let sort = match Sort::parse(Some(query.sort.as_deref().unwrap_or("id"))) {
+        Ok(sort) => sort,
+        Err(e) => return error_response(StatusCode::BAD_REQUEST, &e),
+    };
+
+    let stream = export_users_service(
+        query.include_deleted.unwrap_or(false),
+        sort,
+        format,
+        columns,
+    );
+    (
+        [
+            (CONTENT_TYPE, format.content_type().to_string()),
+            (
+                CONTENT_DISPOSITION,
+                format!("attachment; filename=\"users.{}\"", format.extension()),
+            ),
+        ],
+        Body::from_stream(stream),
+    )
+        .into_response()
+}
+
+#[utoipa::path(
+    get,
+    path = "/user/search",
+    tag = "user",
+    params(SearchUsersQuery),
+    responses(
+        (status = 200, description = "Ranked matches with highlights", body = ApiDataResponse),
+        (status = 400, description = "Query shorter than 2 characters", body = ApiMessage),
+        (status = 403, description = "Admin privileges required", body = ApiMessage)
+    ),
+    security(("bearerAuth" = []), ("apiKeyAuth" = []))
+)]
+#[tracing::instrument(skip_all)]
+pub async fn search_users_controller(
+    user: AuthUser,
+    query: Option<Query<SearchUsersQuery>>,
+) -> Response {
+    if !user.is_admin() {
+        return forbidden().into_response();
+    }
+
+    let Query(query) = query.unwrap_or_default();
+    let q = query.q.unwrap_or_default();
+    if q.trim().chars().count() < 2 {
+        return error_response(
+            StatusCode::BAD_REQUEST,
+            "Search query must be at least 2 characters",
+        );
+    }
+
+    match search_users_service(&q, query.limit.unwrap_or(20)).await {
+        Ok(hits) => {
+            let json_response = serde_json::json!({
+                "status": "ok",
+                "message": "Users searched successfully",
+                "data": hits
+            });
+
+            (StatusCode::OK, Json(json_response)).into_response()
         }
+        Err(e) => error_response(StatusCode::INTERNAL_SERVER_ERROR, &e),
     }
 }
 
-pub async fn get_user_by_id_controller(Path(id): Path<i32>) -> impl IntoResponse {
+#[utoipa::path(
+    get,
+    path = "/user/{id}",
+    tag = "user",
+    params(
+        ("id" = i32, Path, description = "User id"),
+        ("If-None-Match" = Option<String>, Header, description = "ETag from a previous response")
+    ),
+    responses(
+        (status = 200, description = "User, or status \"error\" when it does not exist", body = ApiResponse<UserModel>,
+            headers(("ETag" = String, description = "Current version"))),
+        (status = 304, description = "Not modified")
+    )
+)]
+#[tracing::instrument(skip_all, fields(user_id = id))]
+pub async fn get_user_by_id_controller(Path(id): Path<i32>, headers: HeaderMap) -> Response {
     let app_state: Arc<AppState> = APP_STATE.get_or_init(init_app_state).await.clone();
     let data: State<Arc<AppState>> = State(app_state);
     data.observable.notify_crud("GET", "User");
 
     let res = get_user_by_id_service(id).await;
     match res {
-        Ok(_) => {
+        Ok(user) => {
+            let mut response_headers = HeaderMap::new();
+            response_headers.insert(ETAG, etag(user.version));
+            let not_modified = EntityTags::from_headers(&headers, IF_NONE_MATCH)
+                .is_some_and(|tags| tags.matches(user.version, true));
+            if not_modified {
+                return (StatusCode::NOT_MODIFIED, response_headers).into_response();
+            }
+
             let json_response = serde_json::json!({
                 "status": "ok",
                 "message": "User fetched successfully",
-                "data": res.ok()
+                "data": user
             });
 
-            Json(json_response)
+            (response_headers, Json(json_response)).into_response()
         }
         Err(e) => {
             let json_response = serde_json::json!({
@@ -69,79 +283,262 @@ pub async fn get_user_by_id_controller(Path(id): Path<i32>) -> impl IntoResponse
                 "message": e
             });
 
-            Json(json_response)
+            Json(json_response).into_response()
         }
     }
 }
 
-pub async fn create_user_controller(Json(body): Json<CreateUpdateUserSchema>) -> impl IntoResponse {
+// ETag versi terkini dikirim agar klien dapat mengambil ulang lalu mencoba lagi
+fn precondition_failed(version: i32) -> Response {
+    let json_response = serde_json::json!({
+        "status": "error",
+        "message": "User was modified by another request, fetch the latest version and try again"
+    });
+
+    (
+        StatusCode::PRECONDITION_FAILED,
+        [(ETAG, etag(version))],
+        Json(json_response),
+    )
+        .into_response()
+}
+
+fn error_response(status: StatusCode, message: &str) -> Response {
+    let json_response = serde_json::json!({
+        "status": "error",
+        "message": message
+    });
+
+    (status, Json(json_response)).into_response()
+}
+
+const CREATE_USER_SCOPE: &str = "POST /api/user";
+
+#[utoipa::path(
+    post,
+    path = "/user",
+    tag = "user",
+    params(("Idempotency-Key" = Option<String>, Header, description = "Retries with the same key get the first response")),
+    request_body = CreateUpdateUserSchema,
+    responses(
+        (status = 200, description = "User created, or status \"error\" with the validation errors", body = ApiMessage,
+            headers(("Idempotent-Replayed" = String, description = "\"true\" when replayed"))),
+        (status = 409, description = "Same Idempotency-Key still in progress", body = ApiMessage),
+        (status = 422, description = "Idempotency-Key reused with a different body", body = ApiMessage)
+    )
+)]
+#[tracing::instrument(skip_all)]
+pub async fn create_user_controller(
+    headers: HeaderMap,
+    Json(body): Json<CreateUpdateUserSchema>,
+) -> Response {
     let app_state: Arc<AppState> = APP_STATE.get_or_init(init_app_state).await.clone();
     let data: State<Arc<AppState>> = State(app_state);
-    data.observable.notify_crud("POST", "User");
 
-    let res = create_user_service(axum::Json(body)).await;
-    match res {
-        Ok(_) => {
+    // Retry dengan Idempotency-Key yang sama mendapat response request pertama
+    let key = match idempotency_key(&headers) {
+        Ok(key) => key,
+        Err(e) => return error_response(StatusCode::BAD_REQUEST, &e),
+    };
+    let body_ref = &body;
+    let observable = &data.observable;
+    let res = idempotency::run(
+        data.idempotency.as_ref(),
+        CREATE_USER_SCOPE,
+        key.as_deref(),
+        &body,
+        move || async move {
+            observable.notify_crud("POST", "User");
+            create_user_service(axum::Json(body_ref.clone())).await?;
             let json_response = serde_json::json!({
                 "status": "ok",
                 "message": "User created successfully"
             });
+            Ok::<_, String>((StatusCode::OK, json_response, ()))
+        },
+    )
+    .await;
 
-            Json(json_response)
+    match res {
+        Ok(Run::Done(status, json_response, ())) => (status, Json(json_response)).into_response(),
+        Ok(Run::Replay(status, json_response)) => {
+            (status, [(IDEMPOTENT_REPLAYED, "true")], Json(json_response)).into_response()
         }
-        Err(e) => {
+        Ok(Run::Failed(e)) => {
             let json_response = serde_json::json!({
                 "status": "error",
                 "message": e
             });
 
-            Json(json_response)
+            Json(json_response).into_response()
         }
+        Ok(Run::InProgress) => error_response(
+            StatusCode::CONFLICT,
+            "A request with this Idempotency-Key is still being processed",
+        ),
+        Ok(Run::Mismatch) => error_response(
+            StatusCode::UNPROCESSABLE_ENTITY,
+            "Idempotency-Key was already used with a different request body",
+        ),
+        Err(e) => error_response(StatusCode::INTERNAL_SERVER_ERROR, &e),
     }
 }
 
+#[utoipa::path(
+    put,
+    path = "/user/{id}",
+    tag = "user",
+    params(
+        ("id" = i32, Path, description = "User id"),
+        ("If-Match" = Option<String>, Header, description = "Only update this version")
+    ),
+    request_body = CreateUpdateUserSchema,
+    responses(
+        (status = 200, description = "User updated, or status \"error\" with the validation errors", body = ApiMessage,
+            headers(("ETag" = String, description = "New version"))),
+        (status = 412, description = "Version does not match If-Match", body = ApiMessage)
+    )
+)]
+#[tracing::instrument(skip_all, fields(user_id = id))]
 pub async fn update_user_controller(
     Path(id): Path<i32>,
+    headers: HeaderMap,
     Json(body): Json<CreateUpdateUserSchema>,
-) -> impl IntoResponse {
+) -> Response {
     let app_state: Arc<AppState> = APP_STATE.get_or_init(init_app_state).await.clone();
     let data: State<Arc<AppState>> = State(app_state);
     data.observable.notify_crud("PUT", "User");
 
-    let res = update_user_service(id, axum::Json(body)).await;
+    let if_match = EntityTags::from_headers(&headers, IF_MATCH);
+    let res = update_user_service(id, if_match, axum::Json(body)).await;
     match res {
-        Ok(_) => {
+        Ok(WriteOutcome::Done(version)) => {
             let json_response = serde_json::json!({
                 "status": "ok",
                 "message": "User updated successfully"
             });
 
-            Json(json_response)
+            ([(ETAG, etag(version))], Json(json_response)).into_response()
         }
-        Err(e) => {
+        Ok(WriteOutcome::VersionMismatch(version)) => precondition_failed(version),
+        Ok(WriteOutcome::Taken(e)) | Err(e) => {
             let json_response = serde_json::json!({
                 "status": "error",
                 "message": e
             });
 
-            Json(json_response)
+            Json(json_response).into_response()
+        }
+    }
+}
+
+#[utoipa::path(
+    patch,
+    path = "/user/{id}",
+    tag = "user",
+    params(
+        ("id" = i32, Path, description = "User id"),
+        ("If-Match" = Option<String>, Header, description = "Only update this version")
+    ),
+    request_body = PatchUserSchema,
+    responses(
+        (status = 200, description = "User updated, or status \"error\" with the validation errors", body = ApiMessage,
+            headers(("ETag" = String, description = "New version"))),
+        (status = 412, description = "Version does not match If-Match", body = ApiMessage)
+    )
+)]
+#[tracing::instrument(skip_all, fields(user_id = id))]
+pub async fn patch_user_controller(
+    Path(id): Path<i32>,
+    headers: HeaderMap,
+    Json(body): Json<PatchUserSchema>,
+) -> Response {
+    let app_state: Arc<AppState> = APP_STATE.get_or_init(init_app_state).await.clone();
+    let data: State<Arc<AppState>> = State(app_state);
+    data.observable.notify_crud("PATCH", "User");
+
+    let if_match = EntityTags::from_headers(&headers, IF_MATCH);
+    match patch_user_service(id, if_match, body).await {
+        Ok(WriteOutcome::Done(version)) => {
+            let json_response = serde_json::json!({
+                "status": "ok",
+                "message": "User updated successfully"
+            });
+
+            ([(ETAG, etag(version))], Json(json_response)).into_response()
+        }
+        Ok(WriteOutcome::VersionMismatch(version)) => precondition_failed(version),
+        Ok(WriteOutcome::Taken(e)) | Err(e) => {
+            let json_response = serde_json::json!({
+                "status": "error",
+                "message": e
+            });
+
+            Json(json_response).into_response()
         }
     }
 }
 
-pub async fn delete_user_by_id_controller(Path(id): Path<i32>) -> impl IntoResponse {
+#[utoipa::path(
+    delete,
+    path = "/user/{id}",
+    tag = "user",
+    params(
+        ("id" = i32, Path, description = "User id"),
+        ("If-Match" = Option<String>, Header, description = "Only delete this version")
+    ),
+    responses(
+        (status = 200, description = "User soft deleted, or status \"error\" when it does not exist", body = ApiMessage),
+        (status = 412, description = "Version does not match If-Match", body = ApiMessage)
+    )
+)]
+#[tracing::instrument(skip_all, fields(user_id = id))]
+pub async fn delete_user_by_id_controller(Path(id): Path<i32>, headers: HeaderMap) -> Response {
     let app_state: Arc<AppState> = APP_STATE.get_or_init(init_app_state).await.clone();
     let data: State<Arc<AppState>> = State(app_state);
     data.observable.notify_crud("DEL", "User");
 
-    let res = delete_user_by_id_service(id).await;
+    let if_match = EntityTags::from_headers(&headers, IF_MATCH);
+    let res = delete_user_by_id_service(id, if_match).await;
     match res {
-        Ok(_) => {
+        Ok(WriteOutcome::Done(_)) => {
             let json_response = serde_json::json!({
                 "status": "ok",
                 "message": "User Deleted successfully"
             });
 
+            Json(json_response).into_response()
+        }
+        Ok(WriteOutcome::VersionMismatch(version)) => precondition_failed(version),
+        Ok(WriteOutcome::Taken(e)) | Err(e) => {
+            let json_response = serde_json::json!({
+                "status": "error",
+                "message": e
+            });
+
+            Json(json_response).into_response()
+        }
+    }
+}
+
+#[utoipa::path(
+    get,
+    path = "/user/{id}/presence",
+    tag = "user",
+    params(("id" = i32, Path, description = "User id")),
+    responses((status = 200, description = "Online status from WebSocket connections", body = ApiDataResponse))
+)]
+#[tracing::instrument(skip_all, fields(user_id = id))]
+pub async fn get_user_presence_controller(Path(id): Path<i32>) -> impl IntoResponse {
+    let res = get_user_presence_service(id).await;
+    match res {
+        Ok(presence) => {
+            let json_response = serde_json::json!({
+                "status": "ok",
+                "message": "User presence fetched successfully",
+                "data": presence
+            });
+
             Json(json_response)
         }
         Err(e) => {
@@ -154,3 +551,87 @@ pub async fn delete_user_by_id_controller(Path(id): Path<i32>) -> impl IntoRespo
         }
     }
 }
+
+#[utoipa::path(
+    post,
+    path = "/user/{id}/unlock",
+    tag = "user",
+    params(("id" = i32, Path, description = "User id")),
+    responses(
+        (status = 200, description = "Login lockout cleared", body = ApiMessage),
+        (status = 403, description = "Admin privileges required", body = ApiMessage),
+        (status = 404, description = "User does not exist", body = ApiMessage)
+    ),
+    security(("bearerAuth" = []), ("apiKeyAuth" = []))
+)]
+#[tracing::instrument(skip_all, fields(user_id = id))]
+pub async fn unlock_user_controller(user: AuthUser, Path(id): Path<i32>) -> impl IntoResponse {
+    if !user.is_admin() {
+        return forbidden();
+    }
+
+    let res = unlock_user_service(id).await;
+    match res {
+        Ok(unlocked) => {
+            let message = if unlocked {
+                "User unlocked successfully"
+            } else {
+                "User was not locked"
+            };
+            let json_response = serde_json::json!({
+                "status": "ok",
+                "message": message
+            });
+
+            (StatusCode::OK, Json(json_response))
+        }
+        Err(e) => {
+            let json_response = serde_json::json!({
+                "status": "error",
+                "message": e
+            });
+
+            (StatusCode::NOT_FOUND, Json(json_response))
+        }
+    }
+}
+
+#[utoipa::path(
+    post,
+    path = "/user/{id}/restore",
+    tag = "user",
+    params(("id" = i32, Path, description = "User id")),
+    responses(
+        (status = 200, description = "Soft deleted user restored", body = ApiResponse<UserModel>),
+        (status = 403, description = "Admin privileges required", body = ApiMessage),
+        (status = 409, description = "User is not deleted or its username, email or phone is taken", body = ApiMessage)
+    ),
+    security(("bearerAuth" = []), ("apiKeyAuth" = []))
+)]
+#[tracing::instrument(skip_all, fields(user_id = id))]
+pub async fn restore_user_controller(user: AuthUser, Path(id): Path<i32>) -> impl IntoResponse {
+    if !user.is_admin() {
+        return forbidden();
+    }
+
+    let res = restore_user_service(id).await;
+    match res {
+        Ok(restored) => {
+            let json_response = serde_json::json!({
+                "status": "ok",
+                "message": "User restored successfully",
+                "data": restored
+            });
+
+            (StatusCode::OK, Json(json_response))
+        }
+        Err(e) => {
+            let json_response = serde_json::json!({
+                "status": "error",
+                "message": e
+            });
+
+            (StatusCode::CONFLICT, Json(json_response))
+        }
+    }
+}
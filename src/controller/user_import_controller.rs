@@ -0,0 +1,145 @@
+use axum::{
+    extract::{Path, Query},
+    http::{
+        header::{CONTENT_TYPE, LOCATION},
+        HeaderMap, StatusCode,
+    },
+    response::{IntoResponse, Response},
+    Json,
+};
+
+use crate::{
+    model::{
+        response::{ApiDataResponse, ApiMessage},
+        schema::ImportUsersQuery,
+    },
+    service::user::user_import_service::{
+        get_import_job_service, import_users_service, parse_rows, start_import_job, sync_max_rows,
+        ImportOptions,
+    },
+    utils::{
+        auth::{forbidden, AuthUser},
+        versioning::API_V1,
+    },
+};
+
+fn error_response(status: StatusCode, message: &str) -> Response {
+    let json_response = serde_json::json!({
+        "status": "error",
+        "message": message
+    });
+
+    (status, Json(json_response)).into_response()
+}
+
+#[utoipa::path(
+    post,
+    path = "/user/import",
+    tag = "user",
+    params(ImportUsersQuery),
+    request_body(description = "CSV or NDJSON rows", content(("text/csv"), ("application/x-ndjson"))),
+    responses(
+        (status = 200, description = "Import report", body = ApiDataResponse),
+        (status = 202, description = "Import running as a background job",
+            headers(("Location" = String, description = "Job status URL"))),
+        (status = 400, description = "Unreadable file", body = ApiMessage),
+        (status = 403, description = "Admin privileges required", body = ApiMessage),
+        (status = 422, description = "Atomic import rejected, report lists the failing rows", body = ApiDataResponse)
+    ),
+    security(("bearerAuth" = []), ("apiKeyAuth" = []))
+)]
+#[tracing::instrument(skip_all)]
+pub async fn import_users_controller(
+    user: AuthUser,
+    headers: HeaderMap,
+    query: Option<Query<ImportUsersQuery>>,
+    body: String,
+) -> Response {
+    if !user.is_admin() {
+        return forbidden().into_response();
+    }
+
+    let Query(query) = query.unwrap_or_default();
+    let content_type = headers.get(CONTENT_TYPE).and_then(|v| v.to_str().ok());
+    let options = match ImportOptions::from_query(&query, content_type) {
+        Ok(options) => options,
+        Err(e) => return error_response(StatusCode::BAD_REQUEST, &e),
+    };
+    let rows = match parse_rows(options.format, &body) {
+        Ok(rows) if rows.is_empty() => {
+            return error_response(StatusCode::BAD_REQUEST, "Import file has no rows")
+        }
+        Ok(rows) => rows,
+        Err(e) => return error_response(StatusCode::BAD_REQUEST, &e),
+    };
+
+    if query.background.unwrap_or(false) || rows.len() > sync_max_rows() {
+        let job = start_import_job(rows, options);
+        let json_response = serde_json::json!({
+            "status": "ok",
+            "message": "Import started",
+            "data": job
+        });
+
+        return (
+            StatusCode::ACCEPTED,
+            [(LOCATION, format!("{}/user/import/{}", API_V1, job.id))],
+            Json(json_response),
+        )
+            .into_response();
+    }
+
+    match import_users_service(rows, options, None).await {
+        Ok(report) if report.invalid + report.failed == 0 => {
+            let json_response = serde_json::json!({
+                "status": "ok",
+                "message": "Users imported successfully",
+                "data": report
+            });
+
+            (StatusCode::OK, Json(json_response)).into_response()
+        }
+        Ok(report) => {
+            let json_response = serde_json::json!({
+                "status": "error",
+                "message": "Some rows could not be imported",
+                "data": report
+            });
+
+            (StatusCode::UNPROCESSABLE_ENTITY, Json(json_response)).into_response()
+        }
+        Err(e) => error_response(StatusCode::INTERNAL_SERVER_ERROR, &e),
+    }
+}
+
+#[utoipa::path(
+    get,
+    path = "/user/import/{job_id}",
+    tag = "user",
+    params(("job_id" = String, Path, description = "Background import job id")),
+    responses(
+        (status = 200, description = "Job status and report", body = ApiDataResponse),
+        (status = 403, description = "Admin privileges required", body = ApiMessage),
+        (status = 404, description = "Unknown job", body = ApiMessage)
+    ),
+    security(("bearerAuth" = []), ("apiKeyAuth" = []))
+)]
+#[tracing::instrument(skip_all, fields(job_id = %job_id))]
+pub async fn get_import_job_controller(user: AuthUser, Path(job_id): Path<String>) -> Response {
+    if !user.is_admin() {
+        return forbidden().into_response();
+    }
+
+    match get_import_job_service(&job_id) {
+        Ok(job) => {
+            let json_response = serde_json::json!({
+                "status": "ok",
+                "message": "Import job fetched successfully",
+                "data": job
+            });
+
+            (StatusCode::OK, Json(json_response)).into_response()
+        }
+        Err(e) => error_response(StatusCode::NOT_FOUND, &e),
+    }
+}
@@ -0,0 +1,519 @@
+use axum::{
+    extract::{
+        rejection::{JsonRejection, PathRejection, QueryRejection},
+        Path, Query, State,
+    },
+    http::{
+        header::{ETAG, IF_MATCH, IF_NONE_MATCH, LINK, LOCATION},
+        HeaderMap, HeaderValue, StatusCode,
+    },
+    response::{IntoResponse, Response},
+    Json,
+};
+use std::sync::Arc;
+use tokio::sync::OnceCell;
+
+use crate::{
+    model::{
+        response::{DataResponse, ErrorDetail, ErrorResponse, PageResponse, Pagination, UserDto},
+        schema::{CreateUpdateUserSchema, CursorOptions, FilterOptions, PatchUserSchema},
+    },
+    service::user::{
+        user_repository::Insert,
+        user_service::{
+            delete_user_by_id_service, get_user_by_id_service, get_users_page_service,
+            insert_user_service, patch_user_service, update_user_service, WriteOutcome,
+        },
+        user_validator::{create_validation, patch_validation, update_validation},
+    },
+    utils::{
+        auth::AuthUser,
+        context::RequestContext,
+        cursor::link_header,
+        etag::{etag, EntityTags},
+        idempotency::{self, idempotency_key, Run, IDEMPOTENT_REPLAYED},
+        singleton::{init_app_state, AppState},
+        versioning::API_V2,
+    },
+};
+
+static APP_STATE: OnceCell<Arc<AppState>> = OnceCell::const_new();
+
+const CREATE_USER_SCOPE: &str = "POST /api/v2/user";
+
+fn error_body(code: &str, message: &str) -> ErrorResponse {
+    ErrorResponse {
+        error: ErrorDetail {
+            code: code.to_string(),
+            message: message.to_string(),
+            request_id: RequestContext::current().and_then(|ctx| ctx.request_id),
+        },
+    }
+}
+
+// Semua kegagalan di v2 berbentuk {"error": {"code", "message", "request_id"}}
+fn error_response(status: StatusCode, code: &str, message: &str) -> Response {
+    (status, Json(error_body(code, message))).into_response()
+}
+
+fn not_found(id: i32) -> Response {
+    error_response(
+        StatusCode::NOT_FOUND,
+        "not_found",
+        &format!("User with id {} does not exist", id),
+    )
+}
+
+fn precondition_failed(version: i32) -> Response {
+    let response = error_response(
+        StatusCode::PRECONDITION_FAILED,
+        "precondition_failed",
+        "User was modified by another request, fetch the latest version and try again",
+    );
+    ([(ETAG, etag(version))], response).into_response()
+}
+
+fn user_path(id: i32) -> String {
+    format!("{}/user/{}", API_V2, id)
+}
+
+// Route yang tidak ada di /api/v2 tetap mendapat error v2
+pub async fn v2_fallback_controller() -> Response {
+    error_response(StatusCode::NOT_FOUND, "not_found", "Route does not exist")
+}
+
+#[utoipa::path(
+    get,
+    path = "/user",
+    tag = "v2",
+    params(CursorOptions),
+    responses(
+        (status = 200, description = "One page of users", body = PageResponse<UserDto>,
+            headers(("Link" = String, description = "next/prev cursor links"))),
+        (status = 400, description = "Invalid limit, cursor or sort", body = ErrorResponse),
+        (status = 403, description = "include_deleted requires admin", body = ErrorResponse)
+    ),
+    security((), ("bearerAuth" = []), ("apiKeyAuth" = []))
+)]
+#[tracing::instrument(skip_all)]
+pub async fn get_users_v2_controller(
+    user: Option<AuthUser>,
+    opts: Result<Query<CursorOptions>, QueryRejection>,
+) -> Response {
+    let opts: FilterOptions = match opts {
+        Ok(Query(opts)) => opts.into(),
+        Err(rejection) => {
+            return error_response(
+                StatusCode::BAD_REQUEST,
+                "invalid_query",
+                &rejection.body_text(),
+            )
+        }
+    };
+    let include_deleted = opts.include_deleted.unwrap_or(false);
+    if include_deleted && !user.is_some_and(|user| user.is_admin()) {
+        return error_response(
+            StatusCode::FORBIDDEN,
+            "forbidden",
+            "Admin privileges required",
+        );
+    }
+
+    let app_state: Arc<AppState> = APP_STATE.get_or_init(init_app_state).await.clone();
+    let data: State<Arc<AppState>> = State(app_state);
+    data.observable.notify_crud("GET", "All Users");
+
+    let page = match get_users_page_service(&opts).await {
+        Ok(page) => page,
+        Err(e) => return error_response(StatusCode::BAD_REQUEST, "invalid_query", &e),
+    };
+
+    let mut params = vec![
+        ("limit", page.limit.to_string()),
+        ("sort", page.sort.as_param()),
+    ];
+    if include_deleted {
+        params.push(("include_deleted", "true".to_string()));
+    }
+    let mut headers = HeaderMap::new();
+    let link = link_header(
+        &format!("{}/user", API_V2),
+        &params,
+        page.next_cursor.as_deref(),
+        page.prev_cursor.as_deref(),
+    );
+    if let Some(link) = link.and_then(|link| HeaderValue::from_str(&link).ok()) {
+        headers.insert(LINK, link);
+    }
+
+    let body = PageResponse {
+        data: page.users.into_iter().map(UserDto::from).collect(),
+        pagination: Pagination {
+            limit: page.limit,
+            sort: page.sort.as_param(),
+            next_cursor: page.next_cursor,
+            prev_cursor: page.prev_cursor,
+        },
+    };
+    (headers, Json(body)).into_response()
+}
+
+#[utoipa::path(
+    get,
+    path = "/user/{id}",
+    tag = "v2",
+    params(
+        ("id" = i32, Path, description = "User id"),
+        ("If-None-Match" = Option<String>, Header, description = "304 when the version is unchanged")
+    ),
+    responses(
+        (status = 200, description = "User", body = DataResponse<UserDto>,
+            headers(("ETag" = String, description = "Current version"))),
+        (status = 304, description = "Not modified"),
+        (status = 404, description = "User does not exist", body = ErrorResponse)
+    )
+)]
+#[tracing::instrument(skip_all)]
+pub async fn get_user_v2_controller(
+    id: Result<Path<i32>, PathRejection>,
+    headers: HeaderMap,
+) -> Response {
+    let id = match id {
+        Ok(Path(id)) => id,
+        Err(rejection) => {
+            return error_response(
+                StatusCode::BAD_REQUEST,
+                "invalid_path",
+                &rejection.body_text(),
+            )
+        }
+    };
+    let app_state: Arc<AppState> = APP_STATE.get_or_init(init_app_state).await.clone();
+    let data: State<Arc<AppState>> = State(app_state);
+    data.observable.notify_crud("GET", "User");
+
+    let Ok(user) = get_user_by_id_service(id).await else {
+        return not_found(id);
+    };
+    let version = user.version;
+    if EntityTags::from_headers(&headers, IF_NONE_MATCH)
+        .is_some_and(|tags| tags.matches(version, true))
+    {
+        return (StatusCode::NOT_MODIFIED, [(ETAG, etag(version))]).into_response();
+    }
+
+    let body = DataResponse {
+        data: UserDto::from(user),
+    };
+    ([(ETAG, etag(version))], Json(body)).into_response()
+}
+
+#[utoipa::path(
+    post,
+    path = "/user",
+    tag = "v2",
+    params(("Idempotency-Key" = Option<String>, Header, description = "Retries with the same key get the first response")),
+    request_body = CreateUpdateUserSchema,
+    responses(
+        (status = 201, description = "User created", body = DataResponse<UserDto>,
+            headers(
+                ("Location" = String, description = "URL of the new user"),
+                ("ETag" = String, description = "Current version"),
+                ("Idempotent-Replayed" = String, description = "\"true\" when replayed")
+            )),
+        (status = 400, description = "Malformed body or Idempotency-Key", body = ErrorResponse),
+        (status = 409, description = "Same Idempotency-Key still in progress", body = ErrorResponse),
+        (status = 422, description = "Validation failed, or Idempotency-Key reused with a different body", body = ErrorResponse)
+    )
+)]
+#[tracing::instrument(skip_all)]
+pub async fn create_user_v2_controller(
+    headers: HeaderMap,
+    body: Result<Json<CreateUpdateUserSchema>, JsonRejection>,
+) -> Response {
+    let body = match body {
+        Ok(Json(body)) => body,
+        Err(rejection) => {
+            return error_response(
+                StatusCode::BAD_REQUEST,
+                "invalid_body",
+                &rejection.body_text(),
+            )
+        }
+    };
+    let key = match idempotency_key(&headers) {
+        Ok(key) => key,
+        Err(e) => return error_response(StatusCode::BAD_REQUEST, "invalid_idempotency_key", &e),
+    };
+    let app_state: Arc<AppState> = APP_STATE.get_or_init(init_app_state).await.clone();
+    let data: State<Arc<AppState>> = State(app_state);
+
+    // Retry dengan Idempotency-Key yang sama mendapat response request pertama
+    let body_ref = &body;
+    let observable = &data.observable;
+    let res = idempotency::run(
+        data.idempotency.as_ref(),
+        CREATE_USER_SCOPE,
+        key.as_deref(),
+        &body,
+        move || async move {
+            observable.notify_crud("POST", "User");
+            create_validation(body_ref)
+                .await
+                .map_err(|e| (StatusCode::UNPROCESSABLE_ENTITY, "validation_failed", e))?;
+            let internal = |e| (StatusCode::INTERNAL_SERVER_ERROR, "internal", e);
+            let id = match insert_user_service(body_ref).await.map_err(internal)? {
+                Insert::Created(id) => id,
+                Insert::Taken(e) => {
+                    return Err((StatusCode::UNPROCESSABLE_ENTITY, "validation_failed", e))
+                }
+            };
+            let user = get_user_by_id_service(id).await.map_err(internal)?;
+            let version = user.version;
+            let body = DataResponse {
+                data: UserDto::from(user),
+            };
+            let json_response = serde_json::to_value(&body).unwrap_or_default();
+            Ok((StatusCode::CREATED, json_response, version))
+        },
+    )
+    .await;
+
+    let mut headers = HeaderMap::new();
+    let (status, json_response) = match res {
+        Ok(Run::Done(status, json_response, version)) => {
+            headers.insert(ETAG, etag(version));
+            (status, json_response)
+        }
+        Ok(Run::Replay(status, json_response)) => {
+            headers.insert(IDEMPOTENT_REPLAYED, HeaderValue::from_static("true"));
+            (status, json_response)
+        }
+        Ok(Run::Failed((status, code, e))) => return error_response(status, code, &e),
+        Ok(Run::InProgress) => {
+            return error_response(
+                StatusCode::CONFLICT,
+                "idempotency_in_progress",
+                "A request with this Idempotency-Key is still being processed",
+            );
+        }
+        Ok(Run::Mismatch) => {
+            return error_response(
+                StatusCode::UNPROCESSABLE_ENTITY,
+                "idempotency_mismatch",
+                "Idempotency-Key was already used with a different request body",
+            );
+        }
+        Err(e) => return error_response(StatusCode::INTERNAL_SERVER_ERROR, "internal", &e),
+    };
+
+    let location = json_response["data"]["id"]
+        .as_i64()
+        .and_then(|id| HeaderValue::from_str(&user_path(id as i32)).ok());
+    if let Some(location) = location {
+        headers.insert(LOCATION, location);
+    }
+    (status, headers, Json(json_response)).into_response()
+}
+
+#[utoipa::path(
+    put,
+    path = "/user/{id}",
+    tag = "v2",
+    params(
+        ("id" = i32, Path, description = "User id"),
+        ("If-Match" = Option<String>, Header, description = "Only update this version")
+    ),
+    request_body = CreateUpdateUserSchema,
+    responses(
+        (status = 200, description = "Updated user", body = DataResponse<UserDto>,
+            headers(("ETag" = String, description = "New version"))),
+        (status = 400, description = "Malformed id or body", body = ErrorResponse),
+        (status = 404, description = "User does not exist", body = ErrorResponse),
+        (status = 412, description = "Version does not match If-Match", body = ErrorResponse),
+        (status = 422, description = "Validation failed, or username, email or phone already taken", body = ErrorResponse)
+    )
+)]
+#[tracing::instrument(skip_all)]
+pub async fn update_user_v2_controller(
+    id: Result<Path<i32>, PathRejection>,
+    headers: HeaderMap,
+    body: Result<Json<CreateUpdateUserSchema>, JsonRejection>,
+) -> Response {
+    let id = match id {
+        Ok(Path(id)) => id,
+        Err(rejection) => {
+            return error_response(
+                StatusCode::BAD_REQUEST,
+                "invalid_path",
+                &rejection.body_text(),
+            )
+        }
+    };
+    let body = match body {
+        Ok(Json(body)) => body,
+        Err(rejection) => {
+            return error_response(
+                StatusCode::BAD_REQUEST,
+                "invalid_body",
+                &rejection.body_text(),
+            )
+        }
+    };
+    if let Err(e) = update_validation(id, &body).await {
+        return error_response(StatusCode::UNPROCESSABLE_ENTITY, "validation_failed", &e);
+    }
+
+    let app_state: Arc<AppState> = APP_STATE.get_or_init(init_app_state).await.clone();
+    let data: State<Arc<AppState>> = State(app_state);
+    data.observable.notify_crud("PUT", "User");
+
+    if get_user_by_id_service(id).await.is_err() {
+        return not_found(id);
+    }
+    let if_match = EntityTags::from_headers(&headers, IF_MATCH);
+    match update_user_service(id, if_match, Json(body)).await {
+        Ok(WriteOutcome::Done(_)) => {}
+        Ok(WriteOutcome::VersionMismatch(version)) => return precondition_failed(version),
+        // Unique index username, email atau phone, bukan kegagalan server
+        Ok(WriteOutcome::Taken(e)) => {
+            return error_response(StatusCode::UNPROCESSABLE_ENTITY, "validation_failed", &e)
+        }
+        Err(e) => return error_response(StatusCode::INTERNAL_SERVER_ERROR, "internal", &e),
+    }
+
+    match get_user_by_id_service(id).await {
+        Ok(user) => {
+            let version = user.version;
+            let body = DataResponse {
+                data: UserDto::from(user),
+            };
+            ([(ETAG, etag(version))], Json(body)).into_response()
+        }
+        Err(_) => not_found(id),
+    }
+}
+
+#[utoipa::path(
+    patch,
+    path = "/user/{id}",
+    tag = "v2",
+    params(
+        ("id" = i32, Path, description = "User id"),
+        ("If-Match" = Option<String>, Header, description = "Only update this version")
+    ),
+    request_body = PatchUserSchema,
+    responses(
+        (status = 200, description = "Updated user", body = DataResponse<UserDto>,
+            headers(("ETag" = String, description = "New version"))),
+        (status = 400, description = "Malformed id or body", body = ErrorResponse),
+        (status = 404, description = "User does not exist", body = ErrorResponse),
+        (status = 412, description = "Version does not match If-Match", body = ErrorResponse),
+        (status = 422, description = "Validation failed, or username, email or phone already taken", body = ErrorResponse)
+    )
+)]
+#[tracing::instrument(skip_all)]
+pub async fn patch_user_v2_controller(
+    id: Result<Path<i32>, PathRejection>,
+    headers: HeaderMap,
+    body: Result<Json<PatchUserSchema>, JsonRejection>,
+) -> Response {
+    let id = match id {
+        Ok(Path(id)) => id,
+        Err(rejection) => {
+            return error_response(
+                StatusCode::BAD_REQUEST,
+                "invalid_path",
+                &rejection.body_text(),
+            )
+        }
+    };
+    let body = match body {
+        Ok(Json(body)) => body,
+        Err(rejection) => {
+            return error_response(
+                StatusCode::BAD_REQUEST,
+                "invalid_body",
+                &rejection.body_text(),
+            )
+        }
+    };
+    if let Err(e) = patch_validation(id, &body).await {
+        return error_response(StatusCode::UNPROCESSABLE_ENTITY, "validation_failed", &e);
+    }
+
+    let app_state: Arc<AppState> = APP_STATE.get_or_init(init_app_state).await.clone();
+    let data: State<Arc<AppState>> = State(app_state);
+    data.observable.notify_crud("PATCH", "User");
+
+    if get_user_by_id_service(id).await.is_err() {
+        return not_found(id);
+    }
+    let if_match = EntityTags::from_headers(&headers, IF_MATCH);
+    match patch_user_service(id, if_match, body).await {
+        Ok(WriteOutcome::Done(_)) => {}
+        Ok(WriteOutcome::VersionMismatch(version)) => return precondition_failed(version),
+        Ok(WriteOutcome::Taken(e)) => {
+            return error_response(StatusCode::UNPROCESSABLE_ENTITY, "validation_failed", &e)
+        }
+        Err(e) => return error_response(StatusCode::INTERNAL_SERVER_ERROR, "internal", &e),
+    }
+
+    match get_user_by_id_service(id).await {
+        Ok(user) => {
+            let version = user.version;
+            let body = DataResponse {
+                data: UserDto::from(user),
+            };
+            ([(ETAG, etag(version))], Json(body)).into_response()
+        }
+        Err(_) => not_found(id),
+    }
+}
+
+#[utoipa::path(
+    delete,
+    path = "/user/{id}",
+    tag = "v2",
+    params(
+        ("id" = i32, Path, description = "User id"),
+        ("If-Match" = Option<String>, Header, description = "Only delete this version")
+    ),
+    responses(
+        (status = 204, description = "User soft deleted"),
+        (status = 400, description = "Malformed id", body = ErrorResponse),
+        (status = 404, description = "User does not exist", body = ErrorResponse),
+        (status = 412, description = "Version does not match If-Match", body = ErrorResponse)
+    )
+)]
+#[tracing::instrument(skip_all)]
+pub async fn delete_user_v2_controller(
+    id: Result<Path<i32>, PathRejection>,
+    headers: HeaderMap,
+) -> Response {
+    let id = match id {
+        Ok(Path(id)) => id,
+        Err(rejection) => {
+            return error_response(
+                StatusCode::BAD_REQUEST,
+                "invalid_path",
+                &rejection.body_text(),
+            )
+        }
+    };
+    let app_state: Arc<AppState> = APP_STATE.get_or_init(init_app_state).await.clone();
+    let data: State<Arc<AppState>> = State(app_state);
+    data.observable.notify_crud("DEL", "User");
+
+    if get_user_by_id_service(id).await.is_err() {
+        return not_found(id);
+    }
+    let if_match = EntityTags::from_headers(&headers, IF_MATCH);
+    match delete_user_by_id_service(id, if_match).await {
+        Ok(WriteOutcome::Done(_)) => StatusCode::NO_CONTENT.into_response(),
+        Ok(WriteOutcome::VersionMismatch(version)) => precondition_failed(version),
+        Ok(WriteOutcome::Taken(e)) | Err(e) => {
+            error_response(StatusCode::INTERNAL_SERVER_ERROR, "internal", &e)
+        }
+    }
+}
@@ -0,0 +1,161 @@
+use axum::{
+    extract::Query,
+    http::{header::RETRY_AFTER, HeaderMap, HeaderValue, StatusCode},
+    response::IntoResponse,
+    Json,
+};
+
+use crate::{
+    model::{
+        response::ApiMessage,
+        schema::{SendEmailVerificationSchema, VerifyEmailQuery, VerifyPhoneSchema},
+    },
+    service::auth::verification_service::{
+        send_email_verification_service, send_phone_verification_service, verify_email_service,
+        verify_phone_service, SendOutcome,
+    },
+    utils::auth::AuthUser,
+};
+
+#[utoipa::path(
+    post,
+    path = "/auth/verify/email",
+    tag = "auth",
+    request_body = SendEmailVerificationSchema,
+    responses((status = 200, description = "Verification link sent if the email is registered and unverified", body = ApiMessage))
+)]
+pub async fn send_email_verification_controller(
+    Json(body): Json<SendEmailVerificationSchema>,
+) -> impl IntoResponse {
+    let res = send_email_verification_service(axum::Json(body)).await;
+    match res {
+        Ok(_) => {
+            let json_response = serde_json::json!({
+                "status": "ok",
+                "message": "If the email is registered and not yet verified, a verification link has been sent"
+            });
+
+            (StatusCode::OK, Json(json_response))
+        }
+        Err(e) => {
+            let json_response = serde_json::json!({
+                "status": "error",
+                "message": e
+            });
+
+            (StatusCode::INTERNAL_SERVER_ERROR, Json(json_response))
+        }
+    }
+}
+
+#[utoipa::path(
+    get,
+    path = "/auth/verify/email",
+    tag = "auth",
+    params(VerifyEmailQuery),
+    responses(
+        (status = 200, description = "Email verified", body = ApiMessage),
+        (status = 400, description = "Invalid or expired token", body = ApiMessage)
+    )
+)]
+pub async fn verify_email_controller(Query(query): Query<VerifyEmailQuery>) -> impl IntoResponse {
+    let res = verify_email_service(query.token).await;
+    match res {
+        Ok(_) => {
+            let json_response = serde_json::json!({
+                "status": "ok",
+                "message": "Email address verified successfully"
+            });
+
+            (StatusCode::OK, Json(json_response))
+        }
+        Err(e) => {
+            let json_response = serde_json::json!({
+                "status": "error",
+                "message": e
+            });
+
+            (StatusCode::BAD_REQUEST, Json(json_response))
+        }
+    }
+}
+
+#[utoipa::path(
+    post,
+    path = "/auth/verify/phone/send",
+    tag = "auth",
+    responses(
+        (status = 200, description = "Code sent by SMS", body = ApiMessage),
+        (status = 400, description = "Phone already verified", body = ApiMessage),
+        (status = 429, description = "Too many codes requested", body = ApiMessage,
+            headers(("Retry-After" = u64, description = "Seconds")))
+    ),
+    security(("bearerAuth" = []), ("apiKeyAuth" = []))
+)]
+pub async fn send_phone_verification_controller(user: AuthUser) -> impl IntoResponse {
+    let mut headers = HeaderMap::new();
+    let res = send_phone_verification_service(user.id).await;
+    match res {
+        Ok(SendOutcome::Sent) => {
+            let json_response = serde_json::json!({
+                "status": "ok",
+                "message": "Verification code has been sent"
+            });
+
+            (StatusCode::OK, headers, Json(json_response))
+        }
+        Ok(SendOutcome::RateLimited(retry_after)) => {
+            headers.insert(RETRY_AFTER, HeaderValue::from(retry_after));
+            let json_response = serde_json::json!({
+                "status": "error",
+                "message": "Too many verification codes requested, please try again later"
+            });
+
+            (StatusCode::TOO_MANY_REQUESTS, headers, Json(json_response))
+        }
+        Err(e) => {
+            let json_response = serde_json::json!({
+                "status": "error",
+                "message": e
+            });
+
+            (StatusCode::BAD_REQUEST, headers, Json(json_response))
+        }
+    }
+}
+
+#[utoipa::path(
+    post,
+    path = "/auth/verify/phone",
+    tag = "auth",
+    request_body = VerifyPhoneSchema,
+    responses(
+        (status = 200, description = "Phone verified", body = ApiMessage),
+        (status = 400, description = "Invalid or expired code", body = ApiMessage)
+    ),
+    security(("bearerAuth" = []), ("apiKeyAuth" = []))
+)]
+pub async fn verify_phone_controller(
+    user: AuthUser,
+    Json(body): Json<VerifyPhoneSchema>,
+) -> impl IntoResponse {
+    let res = verify_phone_service(user.id, axum::Json(body)).await;
+    match res {
+        Ok(_) => {
+            let json_response = serde_json::json!({
+                "status": "ok",
+                "message": "Phone number verified successfully"
+            });
+
+            (StatusCode::OK, Json(json_response))
+        }
+        Err(e) => {
+            let json_response = serde_json::json!({
+                "status": "error",
+                "message": e
+            });
+
+            (StatusCode::BAD_REQUEST, Json(json_response))
+        }
+    }
+}
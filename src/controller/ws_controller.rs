@@ -0,0 +1,142 @@
+use std::collections::HashSet;
+use std::sync::Arc;
+
+use axum::{
+    extract::{
+        ws::{Message, WebSocket, WebSocketUpgrade},
+        Query,
+    },
+    http::{HeaderMap, StatusCode},
+    response::{IntoResponse, Response},
+    Json,
+};
+use futures_util::{SinkExt, StreamExt};
+use tokio::sync::{broadcast::error::RecvError, OnceCell};
+
+use crate::{
+    model::{
+        response::ApiMessage,
+        schema::{WsClientMessage, WsQuery},
+    },
+    utils::{
+        auth::{authenticate, bearer_token, require_verified, AuthUser},
+        event_hub::topic_matches,
+        observer::Event,
+        singleton::{init_app_state, AppState},
+    },
+};
+
+static APP_STATE: OnceCell<Arc<AppState>> = OnceCell::const_new();
+
+#[utoipa::path(
+    get,
+    path = "/ws",
+    tag = "system",
+    params(WsQuery),
+    responses(
+        (status = 101, description = "WebSocket upgrade for event subscriptions"),
+        (status = 401, description = "Missing or invalid token", body = ApiMessage)
+    ),
+    security((), ("bearerAuth" = []))
+)]
+pub async fn ws_controller(
+    ws: WebSocketUpgrade,
+    Query(query): Query<WsQuery>,
+    headers: HeaderMap,
+) -> Response {
+    let token = bearer_token(&headers).map(str::to_string).or(query.token);
+    let user = match token {
+        Some(token) => match authenticate(&token).await {
+            Ok(user) => user,
+            Err(e) => return unauthorized(&e),
+        },
+        None => return unauthorized("Missing token"),
+    };
+    if let Err(rejection) = require_verified(&user) {
+        return rejection.into_response();
+    }
+
+    ws.on_upgrade(move |socket| handle_socket(socket, user))
+}
+
+fn unauthorized(message: &str) -> Response {
+    let json_response = serde_json::json!({
+        "status": "error",
+        "message": message
+    });
+
+    (StatusCode::UNAUTHORIZED, Json(json_response)).into_response()
+}
+
+async fn handle_socket(socket: WebSocket, user: AuthUser) {
+    let app_state: Arc<AppState> = APP_STATE.get_or_init(init_app_state).await.clone();
+    let mut events = app_state.event_hub.subscribe();
+    let (mut sender, mut receiver) = socket.split();
+    let mut topics: HashSet<String> = HashSet::new();
+
+    if app_state.presence.connect(user.id) {
+        app_state.observable.notify_event(Event::new(
+            "presence.online",
+            Some(user.id),
+            serde_json::json!({ "username": user.username }),
+        ));
+    }
+
+    let welcome = serde_json::json!({ "type": "welcome", "user_id": user.id });
+    let mut open = sender
+        .send(Message::Text(welcome.to_string()))
+        .await
+        .is_ok();
+
+    while open {
+        tokio::select! {
+            message = receiver.next() => match message {
+                Some(Ok(Message::Text(text))) => {
+                    app_state.presence.touch(user.id);
+                    let reply = handle_client_message(&text, &mut topics);
+                    open = sender.send(Message::Text(reply.to_string())).await.is_ok();
+                }
+                Some(Ok(Message::Close(_))) | Some(Err(_)) | None => open = false,
+                Some(Ok(_)) => app_state.presence.touch(user.id),
+            },
+            event = events.recv() => match event {
+                Ok(event) => {
+                    if topics.iter().any(|pattern| topic_matches(pattern, &event.topic)) {
+                        let payload = serde_json::json!({ "type": "event", "event": event });
+                        open = sender.send(Message::Text(payload.to_string())).await.is_ok();
+                    }
+                }
+                Err(RecvError::Lagged(skipped)) => {
+                    let payload = serde_json::json!({ "type": "lagged", "skipped": skipped });
+                    open = sender.send(Message::Text(payload.to_string())).await.is_ok();
+                }
+                Err(RecvError::Closed) => open = false,
+            },
+        }
+    }
+
+    if app_state.presence.disconnect(user.id) {
+        app_state.observable.notify_event(Event::new(
+            "presence.offline",
+            Some(user.id),
+            serde_json::json!({ "username": user.username }),
+        ));
+    }
+}
+
+fn handle_client_message(text: &str, topics: &mut HashSet<String>) -> serde_json::Value {
+    match serde_json::from_str::<WsClientMessage>(text) {
+        Ok(WsClientMessage::Subscribe { topics: requested }) => {
+            topics.extend(requested);
+            serde_json::json!({ "type": "subscribed", "topics": topics })
+        }
+        Ok(WsClientMessage::Unsubscribe { topics: requested }) => {
+            for topic in &requested {
+                topics.remove(topic);
+            }
+            serde_json::json!({ "type": "unsubscribed", "topics": topics })
+        }
+        Ok(WsClientMessage::Ping) => serde_json::json!({ "type": "pong" }),
+        Err(e) => serde_json::json!({ "type": "error", "message": e.to_string() }),
+    }
+}
@@ -4,23 +4,150 @@ mod route;
 mod service;
 mod utils;
 
-use axum::http::{header::CONTENT_TYPE, Method};
-use route::create_router;
+use std::net::SocketAddr;
+
+use axum::{
+    http::{
+        header::{AUTHORIZATION, CONTENT_TYPE, ETAG, IF_MATCH, IF_NONE_MATCH, LINK, RETRY_AFTER},
+        HeaderName, Method,
+    },
+    middleware,
+};
+use route::{create_replay_router, create_router, create_simulation_router};
+use service::resource::resource_migration_service::{migrate_resources, write_migrations};
+use service::user::user_service::purge_deleted_users_job;
 use tower_http::cors::{Any, CorsLayer};
+use utils::{
+    hashing::check_password_hashing,
+    logging::init_tracing,
+    recording::{record_traffic, recording_enabled, replay},
+    simulation::simulation_config,
+    versioning::{DEPRECATION, SUNSET},
+};
+
+const X_REQUEST_ID: HeaderName = HeaderName::from_static("x-request-id");
+const X_API_KEY: HeaderName = HeaderName::from_static("x-api-key");
+const IDEMPOTENCY_KEY: HeaderName = HeaderName::from_static("idempotency-key");
+const IDEMPOTENT_REPLAYED: HeaderName = HeaderName::from_static("idempotent-replayed");
+const RATELIMIT_LIMIT: HeaderName = HeaderName::from_static("ratelimit-limit");
+const RATELIMIT_REMAINING: HeaderName = HeaderName::from_static("ratelimit-remaining");
+const RATELIMIT_RESET: HeaderName = HeaderName::from_static("ratelimit-reset");
+const X_SIMULATED_FAULT: HeaderName = HeaderName::from_static("x-simulated-fault");
+const X_REPLAY: HeaderName = HeaderName::from_static("x-replay");
 
 #[tokio::main]
 async fn main() {
-    println!("🌟 REST API Service 🌟");
+    let tracer_provider = init_tracing();
+
+    // `api-simu generate-migrations` menulis file migrasi untuk resource lalu keluar
+    if std::env::args().nth(1).as_deref() == Some("generate-migrations") {
+        match write_migrations(std::path::Path::new("migrations")) {
+            Ok(files) => files
+                .iter()
+                .for_each(|file| tracing::info!("Created {}", file.display())),
+            Err(err) => {
+                tracing::error!("❌ Failed to generate migrations: {}", err);
+                std::process::exit(1);
+            }
+        }
+        return;
+    }
+
+    tracing::info!("🌟 REST API Service 🌟");
+
+    if let Err(err) = check_password_hashing() {
+        tracing::error!("❌ Invalid password hashing configuration: {}", err);
+        std::process::exit(1);
+    }
 
     let cors = CorsLayer::new()
-        .allow_methods([Method::GET, Method::POST, Method::PUT, Method::DELETE])
+        .allow_methods([
+            Method::GET,
+            Method::POST,
+            Method::PUT,
+            Method::PATCH,
+            Method::DELETE,
+        ])
         .allow_origin(Any)
-        .allow_headers([CONTENT_TYPE]);
+        .allow_headers([
+            CONTENT_TYPE,
+            AUTHORIZATION,
+            X_REQUEST_ID,
+            X_API_KEY,
+            IDEMPOTENCY_KEY,
+            IF_MATCH,
+            IF_NONE_MATCH,
+        ])
+        .expose_headers([
+            X_REQUEST_ID,
+            ETAG,
+            LINK,
+            IDEMPOTENT_REPLAYED,
+            RATELIMIT_LIMIT,
+            RATELIMIT_REMAINING,
+            RATELIMIT_RESET,
+            RETRY_AFTER,
+            X_SIMULATED_FAULT,
+            X_REPLAY,
+            DEPRECATION,
+            SUNSET,
+        ]);
 
-    let app = create_router().await.layer(cors);
+    // `--replay` menjawab dari rekaman REPLAY_FILE, `--simulate` melayani route pengguna
+    // dari memori; keduanya tidak membutuhkan MySQL
+    let replaying = std::env::args().any(|arg| arg == "--replay");
+    let app = if replaying {
+        let replay = replay();
+        tracing::info!(
+            recordings = replay.recordings().len(),
+            mode = ?replay.mode,
+            "📼 Replay mode"
+        );
+        create_replay_router()
+    } else if std::env::args().any(|arg| arg == "--simulate") {
+        let config = simulation_config();
+        tracing::info!(
+            seed = config.seed,
+            users = config.users,
+            "🧪 Simulation mode"
+        );
+        create_simulation_router()
+    } else {
+        let app = create_router().await;
+        if let Err(err) = migrate_resources().await {
+            tracing::error!("❌ Failed to create resource tables: {}", err);
+        }
+        tokio::spawn(purge_deleted_users_job());
+        app
+    };
+    // RECORD_FILE merekam traffic mode biasa maupun --simulate
+    let app = if !replaying && recording_enabled() {
+        tracing::info!("⏺️ Recording traffic");
+        app.layer(middleware::from_fn(record_traffic))
+    } else {
+        app
+    };
+    let app = app.layer(cors);
 
     let listener = tokio::net::TcpListener::bind("0.0.0.0:3245").await.unwrap();
-    axum::serve(listener, app.into_make_service())
+    axum::serve(
+        listener,
+        app.into_make_service_with_connect_info::<SocketAddr>(),
+    )
+    .with_graceful_shutdown(shutdown_signal())
+    .await
+    .unwrap();
+
+    // Flush span yang masih tertahan di batch exporter
+    if let Some(provider) = tracer_provider {
+        if let Err(err) = provider.shutdown() {
+            tracing::error!("Failed to shut down the tracer provider: {:?}", err);
+        }
+    }
+}
+
+async fn shutdown_signal() {
+    tokio::signal::ctrl_c()
         .await
-        .unwrap();
+        .expect("failed to install the Ctrl+C handler");
 }
@@ -1,2 +1,5 @@
+pub mod resource;
+pub mod response;
 pub mod schema;
+#[allow(clippy::module_inception)]
 pub mod model;
\ No newline at end of file
@@ -1,7 +1,8 @@
 use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
 
 // For sqlx
-#[derive(Debug, Deserialize, Serialize, sqlx::FromRow)]
+#[derive(Debug, Clone, Deserialize, Serialize, sqlx::FromRow, ToSchema)]
 #[allow(non_snake_case)]
 pub struct UserModel {
     pub id: i32,
@@ -9,13 +10,25 @@ pub struct UserModel {
     pub email: String,
     pub phone: String,
     pub password: String,
+    pub role: String,
+    pub token_version: i32,
+    pub version: i32,
+    pub email_verified_at: Option<chrono::DateTime<chrono::Utc>>,
+    pub phone_verified_at: Option<chrono::DateTime<chrono::Utc>>,
+    // Terenkripsi, lihat utils::crypto
+    #[serde(skip_serializing)]
+    pub totp_secret: Option<String>,
+    pub totp_enabled_at: Option<chrono::DateTime<chrono::Utc>>,
+    #[serde(skip_serializing)]
+    pub totp_last_step: Option<i64>,
+    pub deleted_at: Option<chrono::DateTime<chrono::Utc>>,
     pub created_at: Option<chrono::DateTime<chrono::Utc>>,
     pub updated_at: Option<chrono::DateTime<chrono::Utc>>,
 }
 
 // For json response
 #[derive(Debug, Deserialize, Serialize)]
-#[allow(non_snake_case)]
+#[allow(non_snake_case, dead_code)]
 pub struct UserModelResponse {
     pub id: i32,
     pub username: String,
@@ -25,3 +38,86 @@ pub struct UserModelResponse {
     pub created_at: chrono::DateTime<chrono::Utc>,
     pub updated_at: chrono::DateTime<chrono::Utc>,
 }
+
+#[derive(Debug, Deserialize, Serialize, sqlx::FromRow)]
+pub struct AuditLogModel {
+    pub id: i64,
+    pub actor_id: Option<i32>,
+    pub actor: Option<String>,
+    pub action: String,
+    pub target_user_id: Option<i32>,
+    pub changes: Option<sqlx::types::Json<serde_json::Value>>,
+    pub ip: Option<String>,
+    pub user_agent: Option<String>,
+    pub created_at: Option<chrono::DateTime<chrono::Utc>>,
+}
+
+#[derive(Debug, Deserialize, Serialize, sqlx::FromRow)]
+pub struct PasswordResetTokenModel {
+    pub id: i64,
+    pub user_id: i32,
+    pub token_hash: String,
+    pub expires_at: chrono::DateTime<chrono::Utc>,
+    pub used_at: Option<chrono::DateTime<chrono::Utc>>,
+    pub created_at: Option<chrono::DateTime<chrono::Utc>>,
+}
+
+#[derive(Debug, Deserialize, Serialize, sqlx::FromRow)]
+pub struct VerificationCodeModel {
+    pub id: i64,
+    pub user_id: i32,
+    pub channel: String,
+    pub target: String,
+    pub code_hash: String,
+    pub attempts: i32,
+    pub expires_at: chrono::DateTime<chrono::Utc>,
+    pub used_at: Option<chrono::DateTime<chrono::Utc>>,
+    pub created_at: Option<chrono::DateTime<chrono::Utc>>,
+}
+
+#[derive(Debug, Deserialize, Serialize, sqlx::FromRow)]
+pub struct RecoveryCodeModel {
+    pub id: i64,
+    pub user_id: i32,
+    pub code_hash: String,
+    pub used_at: Option<chrono::DateTime<chrono::Utc>>,
+    pub created_at: Option<chrono::DateTime<chrono::Utc>>,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize, sqlx::FromRow)]
+pub struct LoginThrottleModel {
+    pub id: i64,
+    pub scope: String,
+    pub throttle_key: String,
+    pub failures: i32,
+    pub last_failure_at: Option<chrono::DateTime<chrono::Utc>>,
+    pub locked_until: Option<chrono::DateTime<chrono::Utc>>,
+    pub updated_at: Option<chrono::DateTime<chrono::Utc>>,
+}
+
+#[derive(Debug, Deserialize, Serialize, sqlx::FromRow)]
+pub struct ApiKeyModel {
+    pub id: i64,
+    pub user_id: i32,
+    pub name: String,
+    // key_hash sengaja tidak dimuat
+    pub prefix: String,
+    pub scopes: sqlx::types::Json<Vec<String>>,
+    pub expires_at: Option<chrono::DateTime<chrono::Utc>>,
+    pub last_used_at: Option<chrono::DateTime<chrono::Utc>>,
+    pub revoked_at: Option<chrono::DateTime<chrono::Utc>>,
+    pub created_at: Option<chrono::DateTime<chrono::Utc>>,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize, sqlx::FromRow)]
+pub struct IdempotencyKeyModel {
+    pub id: i64,
+    pub scope: String,
+    pub idempotency_key: String,
+    pub fingerprint: String,
+    pub status_code: Option<i16>,
+    pub response: Option<sqlx::types::Json<serde_json::Value>>,
+    pub locked_until: Option<chrono::DateTime<chrono::Utc>>,
+    pub expires_at: chrono::DateTime<chrono::Utc>,
+    pub created_at: Option<chrono::DateTime<chrono::Utc>>,
+}
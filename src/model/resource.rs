@@ -0,0 +1,388 @@
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::{
+    path::Path,
+    sync::{Arc, LazyLock},
+};
+
+use crate::utils::versioning::API_V1;
+
+// Rule yang dikenal ValidationChain, lihat utils::validation_chain
+pub const RESOURCE_RULES: [&str; 7] = [
+    "required",
+    "min_length",
+    "max_length",
+    "email",
+    "contains",
+    "unique",
+    "phone",
+];
+
+// Kolom yang selalu dibuat untuk setiap resource
+pub const RESOURCE_SYSTEM_COLUMNS: [&str; 3] = ["id", "created_at", "updated_at"];
+
+// Nama yang sudah dipakai route atau tabel bawaan
+const RESERVED_NAMES: [&str; 22] = [
+    "user",
+    "users",
+    "auth",
+    "keys",
+    "api_keys",
+    "ws",
+    "audit",
+    "audit_log",
+    "audit_logs",
+    "healthcheck",
+    "metrics",
+    "resources",
+    "password_reset_tokens",
+    "login_throttles",
+    "idempotency_keys",
+    "recovery_codes",
+    "verification_codes",
+    "openapi",
+    "docs",
+    "simulation",
+    "v1",
+    "v2",
+];
+
+static IDENTIFIER_REGEX: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"^[a-z][a-z0-9_]{0,63}$").unwrap());
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FieldType {
+    String,
+    Text,
+    Integer,
+    Number,
+    Boolean,
+    Datetime,
+}
+
+// public: tanpa login, user: pengguna yang login, admin: hanya admin
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Access {
+    Public,
+    User,
+    Admin,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ResourceAction {
+    List,
+    Read,
+    Create,
+    Update,
+    Delete,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ResourceField {
+    pub name: String,
+    #[serde(rename = "type")]
+    pub field_type: FieldType,
+    // Format ValidationChain, mis. "required" atau "max_length:100"
+    #[serde(default)]
+    pub rules: Vec<String>,
+    #[serde(default)]
+    pub description: Option<String>,
+}
+
+impl ResourceField {
+    pub fn rules(&self) -> impl Iterator<Item = (&str, Option<&str>)> {
+        self.rules.iter().map(|rule| match rule.split_once(':') {
+            Some((name, value)) => (name, Some(value)),
+            None => (rule.as_str(), None),
+        })
+    }
+
+    pub fn has_rule(&self, name: &str) -> bool {
+        self.rules().any(|(rule, _)| rule == name)
+    }
+
+    pub fn rule_value(&self, name: &str) -> Option<&str> {
+        self.rules()
+            .find(|(rule, _)| *rule == name)
+            .and_then(|(_, value)| value)
+    }
+
+    pub fn is_required(&self) -> bool {
+        self.has_rule("required")
+    }
+}
+
+// Default: siapa pun boleh membaca, hanya admin yang boleh mengubah
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ResourcePermissions {
+    pub list: Access,
+    pub read: Access,
+    pub create: Access,
+    pub update: Access,
+    pub delete: Access,
+}
+
+impl Default for ResourcePermissions {
+    fn default() -> Self {
+        ResourcePermissions {
+            list: Access::Public,
+            read: Access::Public,
+            create: Access::Admin,
+            update: Access::Admin,
+            delete: Access::Admin,
+        }
+    }
+}
+
+impl ResourcePermissions {
+    pub fn access(&self, action: ResourceAction) -> Access {
+        match action {
+            ResourceAction::List => self.list,
+            ResourceAction::Read => self.read,
+            ResourceAction::Create => self.create,
+            ResourceAction::Update => self.update,
+            ResourceAction::Delete => self.delete,
+        }
+    }
+}
+
+// Satu file schema di RESOURCE_DIR, route-nya /api/v1/<name>
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ResourceSchema {
+    pub name: String,
+    // Default sama dengan name
+    #[serde(default)]
+    pub table: Option<String>,
+    #[serde(default)]
+    pub description: Option<String>,
+    pub fields: Vec<ResourceField>,
+    // Kolom dengan UNIQUE index
+    #[serde(default)]
+    pub unique: Vec<String>,
+    #[serde(default)]
+    pub permissions: ResourcePermissions,
+}
+
+impl ResourceSchema {
+    pub fn parse(file_name: &str, content: &str) -> Result<Self, String> {
+        let schema: ResourceSchema = if file_name.ends_with(".json") {
+            serde_json::from_str(content).map_err(|err| err.to_string())?
+        } else {
+            serde_yaml::from_str(content).map_err(|err| err.to_string())?
+        };
+        schema.check()?;
+        Ok(schema)
+    }
+
+    pub fn table(&self) -> &str {
+        self.table.as_deref().unwrap_or(&self.name)
+    }
+
+    pub fn path(&self) -> String {
+        format!("{}/{}", API_V1, self.name)
+    }
+
+    pub fn field(&self, name: &str) -> Option<&ResourceField> {
+        self.fields.iter().find(|field| field.name == name)
+    }
+
+    pub fn is_unique(&self, field: &str) -> bool {
+        self.unique.iter().any(|column| column == field)
+            || self.field(field).is_some_and(|f| f.has_rule("unique"))
+    }
+
+    // Nama tabel dan kolom dipakai langsung di SQL, jadi harus identifier yang aman
+    fn check(&self) -> Result<(), String> {
+        for name in [self.name.as_str(), self.table()] {
+            if !IDENTIFIER_REGEX.is_match(name) {
+                return Err(format!(
+                    "'{}' must be lowercase letters, digits or underscores",
+                    name
+                ));
+            }
+            if RESERVED_NAMES.contains(&name) {
+                return Err(format!("'{}' is reserved", name));
+            }
+        }
+        if self.fields.is_empty() {
+            return Err("A resource needs at least one field".to_string());
+        }
+
+        for (i, field) in self.fields.iter().enumerate() {
+            if !IDENTIFIER_REGEX.is_match(&field.name) {
+                return Err(format!(
+                    "Field '{}' must be lowercase letters, digits or underscores",
+                    field.name
+                ));
+            }
+            if RESOURCE_SYSTEM_COLUMNS.contains(&field.name.as_str()) {
+                return Err(format!("Field '{}' is created automatically", field.name));
+            }
+            if self.fields[..i].iter().any(|f| f.name == field.name) {
+                return Err(format!("Field '{}' is declared twice", field.name));
+            }
+            for (rule, value) in field.rules() {
+                if !RESOURCE_RULES.contains(&rule) {
+                    return Err(format!(
+                        "Unknown rule '{}' on field '{}', allowed: {}",
+                        rule,
+                        field.name,
+                        RESOURCE_RULES.join(", ")
+                    ));
+                }
+                let needs_number = matches!(rule, "min_length" | "max_length");
+                if needs_number && value.and_then(|v| v.parse::<usize>().ok()).is_none() {
+                    return Err(format!(
+                        "Rule '{}' on field '{}' needs a number, e.g. {}:10",
+                        rule, field.name, rule
+                    ));
+                }
+            }
+        }
+
+        if let Some(column) = self.unique.iter().find(|c| self.field(c).is_none()) {
+            return Err(format!("Unique column '{}' is not a field", column));
+        }
+        // MySQL tidak bisa membuat UNIQUE index pada TEXT tanpa panjang prefix
+        if let Some(field) = self
+            .fields
+            .iter()
+            .find(|f| f.field_type == FieldType::Text && self.is_unique(&f.name))
+        {
+            return Err(format!("Text field '{}' cannot be unique", field.name));
+        }
+        Ok(())
+    }
+}
+
+// Semua file *.yaml, *.yml dan *.json di direktori, diurutkan menurut nama file
+pub fn load_resources(dir: &Path) -> Result<Vec<ResourceSchema>, String> {
+    if !dir.is_dir() {
+        return Ok(Vec::new());
+    }
+
+    let mut paths: Vec<_> = std::fs::read_dir(dir)
+        .map_err(|err| format!("{}: {}", dir.display(), err))?
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|path| {
+            matches!(
+                path.extension().and_then(|ext| ext.to_str()),
+                Some("yaml" | "yml" | "json")
+            )
+        })
+        .collect();
+    paths.sort();
+
+    let mut schemas: Vec<ResourceSchema> = Vec::new();
+    for path in paths {
+        let content =
+            std::fs::read_to_string(&path).map_err(|err| format!("{}: {}", path.display(), err))?;
+        let file_name = path.to_string_lossy();
+        let schema = ResourceSchema::parse(&file_name, &content)
+            .map_err(|err| format!("{}: {}", path.display(), err))?;
+        if schemas
+            .iter()
+            .any(|s| s.name == schema.name || s.table() == schema.table())
+        {
+            return Err(format!(
+                "{}: resource '{}' is declared twice",
+                path.display(),
+                schema.name
+            ));
+        }
+        schemas.push(schema);
+    }
+    Ok(schemas)
+}
+
+static RESOURCES: LazyLock<Vec<Arc<ResourceSchema>>> = LazyLock::new(|| {
+    dotenv::dotenv().ok();
+    let dir = std::env::var("RESOURCE_DIR").unwrap_or_else(|_| "resources".to_string());
+    match load_resources(Path::new(&dir)) {
+        Ok(schemas) => {
+            tracing::info!(count = schemas.len(), dir = %dir, "✅ Resource schemas loaded");
+            schemas.into_iter().map(Arc::new).collect()
+        }
+        Err(err) => {
+            tracing::error!("❌ Failed to load resource schemas: {}", err);
+            std::process::exit(1);
+        }
+    }
+});
+
+// Dibaca sekali dari RESOURCE_DIR (default "resources")
+pub fn resources() -> &'static [Arc<ResourceSchema>] {
+    &RESOURCES
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(yaml: &str) -> Result<ResourceSchema, String> {
+        ResourceSchema::parse("schema.yaml", yaml)
+    }
+
+    #[test]
+    fn reserved_sql_words_are_allowed() {
+        let schema = parse(
+            "name: order\nfields:\n  - name: key\n    type: string\n    rules: [required, unique]\n  - name: group\n    type: integer\n",
+        )
+        .unwrap();
+
+        assert_eq!(schema.table(), "order");
+        assert!(schema.is_unique("key"));
+        assert!(!schema.field("group").unwrap().is_required());
+    }
+
+    #[test]
+    fn check_rejects_invalid_schemas() {
+        let cases = [
+            (
+                "name: Orders\nfields:\n  - {name: a, type: string}\n",
+                "'Orders' must be lowercase letters, digits or underscores",
+            ),
+            (
+                "name: users\nfields:\n  - {name: a, type: string}\n",
+                "'users' is reserved",
+            ),
+            (
+                "name: orders\nfields: []\n",
+                "A resource needs at least one field",
+            ),
+            (
+                "name: orders\nfields:\n  - {name: id, type: integer}\n",
+                "Field 'id' is created automatically",
+            ),
+            (
+                "name: orders\nfields:\n  - {name: a, type: string}\n  - {name: a, type: text}\n",
+                "Field 'a' is declared twice",
+            ),
+            (
+                "name: orders\nfields:\n  - {name: a, type: string, rules: [max_length]}\n",
+                "Rule 'max_length' on field 'a' needs a number, e.g. max_length:10",
+            ),
+            (
+                "name: orders\nunique: [b]\nfields:\n  - {name: a, type: string}\n",
+                "Unique column 'b' is not a field",
+            ),
+            (
+                "name: orders\nfields:\n  - {name: a, type: text, rules: [unique]}\n",
+                "Text field 'a' cannot be unique",
+            ),
+        ];
+        for (yaml, expected) in cases {
+            assert_eq!(parse(yaml).unwrap_err(), expected, "{}", yaml);
+        }
+
+        let unknown = parse("name: orders\nfields:\n  - {name: a, type: string, rules: [regex]}\n");
+        assert!(unknown
+            .unwrap_err()
+            .starts_with("Unknown rule 'regex' on field 'a'"));
+    }
+}
@@ -0,0 +1,144 @@
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use utoipa::ToSchema;
+
+use crate::model::model::UserModel;
+
+// Bentuk response untuk dokumen OpenAPI; controller tetap membangun JSON-nya langsung
+
+#[derive(Serialize, ToSchema)]
+#[allow(dead_code)]
+pub struct ApiMessage {
+    /// "ok" atau "error"
+    pub status: String,
+    pub message: String,
+}
+
+#[derive(Serialize, ToSchema)]
+#[allow(dead_code)]
+pub struct ApiResponse<T> {
+    /// "ok" atau "error"
+    pub status: String,
+    pub message: String,
+    pub data: T,
+}
+
+// Untuk data yang bentuknya belum didokumentasikan per field
+#[derive(Serialize, ToSchema)]
+#[allow(dead_code)]
+pub struct ApiDataResponse {
+    /// "ok" atau "error"
+    pub status: String,
+    pub message: String,
+    #[schema(value_type = Object)]
+    pub data: serde_json::Value,
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct Pagination {
+    pub limit: usize,
+    pub sort: String,
+    pub next_cursor: Option<String>,
+    pub prev_cursor: Option<String>,
+}
+
+// Tanpa page, limit dan cursor semua pengguna dikembalikan tanpa pagination
+#[derive(Serialize, ToSchema)]
+#[allow(dead_code)]
+pub struct UserListResponse {
+    pub status: String,
+    pub message: String,
+    pub data: Vec<UserModel>,
+    pub pagination: Option<Pagination>,
+}
+
+#[derive(Serialize, ToSchema)]
+#[allow(dead_code)]
+pub struct TokenData {
+    pub token: String,
+    /// Selalu "Bearer"
+    pub token_type: String,
+    /// Detik
+    pub expires_in: i64,
+}
+
+// Login dengan 2FA aktif mengembalikan token antara, bukan token login
+#[derive(Serialize, ToSchema)]
+#[allow(dead_code)]
+pub struct MfaRequiredData {
+    pub mfa_required: bool,
+    pub mfa_token: String,
+    /// Detik
+    pub expires_in: i64,
+}
+
+#[derive(Serialize, ToSchema)]
+#[allow(dead_code)]
+#[serde(untagged)]
+pub enum LoginData {
+    Token(TokenData),
+    MfaRequired(MfaRequiredData),
+}
+
+// Kontrak /api/v2: hasil di "data", kegagalan di "error" dengan status HTTP yang sesuai
+
+// Pengguna tanpa hash password dan kolom internal (token_version, version)
+#[derive(Serialize, ToSchema)]
+pub struct UserDto {
+    pub id: i32,
+    pub username: String,
+    pub email: String,
+    pub phone: String,
+    pub role: String,
+    pub email_verified_at: Option<DateTime<Utc>>,
+    pub phone_verified_at: Option<DateTime<Utc>>,
+    pub mfa_enabled: bool,
+    pub created_at: Option<DateTime<Utc>>,
+    pub updated_at: Option<DateTime<Utc>>,
+    /// Hanya terisi untuk pengguna yang di-soft delete
+    pub deleted_at: Option<DateTime<Utc>>,
+}
+
+impl From<UserModel> for UserDto {
+    fn from(user: UserModel) -> Self {
+        UserDto {
+            id: user.id,
+            username: user.username,
+            email: user.email,
+            phone: user.phone,
+            role: user.role,
+            email_verified_at: user.email_verified_at,
+            phone_verified_at: user.phone_verified_at,
+            mfa_enabled: user.totp_enabled_at.is_some(),
+            created_at: user.created_at,
+            updated_at: user.updated_at,
+            deleted_at: user.deleted_at,
+        }
+    }
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct DataResponse<T> {
+    pub data: T,
+}
+
+// Daftar di v2 selalu memakai cursor
+#[derive(Serialize, ToSchema)]
+pub struct PageResponse<T> {
+    pub data: Vec<T>,
+    pub pagination: Pagination,
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct ErrorDetail {
+    /// Kode stabil untuk klien, mis. "not_found" atau "validation_failed"
+    pub code: String,
+    pub message: String,
+    /// Sama dengan header X-Request-Id
+    pub request_id: Option<String>,
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct ErrorResponse {
+    pub error: ErrorDetail,
+}
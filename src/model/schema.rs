@@ -1,23 +1,206 @@
 use serde::{Deserialize, Serialize};
+use utoipa::{IntoParams, ToSchema};
 
 // List
-#[derive(Deserialize, Debug, Default)]
+#[derive(Deserialize, Debug, Default, IntoParams)]
+#[into_params(parameter_in = Query)]
 pub struct FilterOptions {
     pub page: Option<usize>,
     pub limit: Option<usize>,
+    /// Cursor dari next_cursor / prev_cursor, lebih cepat dari page untuk halaman jauh
+    pub cursor: Option<String>,
+    /// Kolom sort, awali dengan "-" untuk urutan turun, mis. "-created_at"
+    pub sort: Option<String>,
+    /// Khusus admin
+    pub include_deleted: Option<bool>,
+}
+
+// List di /api/v2, tanpa page
+#[derive(Deserialize, Debug, Default, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct CursorOptions {
+    /// 1-100, default 20
+    pub limit: Option<usize>,
+    /// Cursor dari next_cursor / prev_cursor
+    pub cursor: Option<String>,
+    /// Kolom sort, awali dengan "-" untuk urutan turun, mis. "-created_at"
+    pub sort: Option<String>,
+    /// Khusus admin
+    pub include_deleted: Option<bool>,
+}
+
+impl From<CursorOptions> for FilterOptions {
+    fn from(opts: CursorOptions) -> Self {
+        FilterOptions {
+            page: None,
+            limit: Some(opts.limit.unwrap_or(20)),
+            cursor: opts.cursor,
+            sort: opts.sort,
+            include_deleted: opts.include_deleted,
+        }
+    }
+}
+
+// List resource dari file schema
+#[derive(Deserialize, Debug, Default)]
+pub struct ResourceListQuery {
+    pub page: Option<usize>,
+    pub limit: Option<usize>,
 }
 
 // Read/Delete
 #[derive(Deserialize, Debug)]
+#[allow(dead_code)]
 pub struct ParamOptions {
     pub id: String,
 }
 
 // Create
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, Clone, ToSchema)]
 pub struct CreateUpdateUserSchema {
     pub username: String,
     pub email: String,
     pub phone: String,
     pub password: String,
-}
\ No newline at end of file
+}
+
+// PATCH, field yang tidak dikirim tidak diubah
+#[derive(Serialize, Deserialize, Debug, Default, Clone, ToSchema)]
+pub struct PatchUserSchema {
+    pub username: Option<String>,
+    pub email: Option<String>,
+    pub phone: Option<String>,
+    pub password: Option<String>,
+}
+// Audit log list
+#[derive(Deserialize, Debug, Default, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct AuditFilterOptions {
+    pub actor_id: Option<i32>,
+    pub target_user_id: Option<i32>,
+    pub action: Option<String>,
+    pub from: Option<chrono::DateTime<chrono::Utc>>,
+    pub to: Option<chrono::DateTime<chrono::Utc>>,
+    pub page: Option<usize>,
+    pub limit: Option<usize>,
+}
+
+// Login, username can also be an email
+#[derive(Serialize, Deserialize, Debug, ToSchema)]
+pub struct LoginSchema {
+    pub username: String,
+    pub password: String,
+}
+
+// Forgot password
+#[derive(Serialize, Deserialize, Debug, ToSchema)]
+pub struct ForgotPasswordSchema {
+    pub email: String,
+}
+
+// Reset password
+#[derive(Serialize, Deserialize, Debug, ToSchema)]
+pub struct ResetPasswordSchema {
+    pub token: String,
+    pub password: String,
+}
+
+// Kirim ulang tautan verifikasi email
+#[derive(Serialize, Deserialize, Debug, ToSchema)]
+pub struct SendEmailVerificationSchema {
+    pub email: String,
+}
+
+// Tautan verifikasi email
+#[derive(Deserialize, Debug, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct VerifyEmailQuery {
+    pub token: String,
+}
+
+// Kode verifikasi nomor telepon
+#[derive(Serialize, Deserialize, Debug, ToSchema)]
+pub struct VerifyPhoneSchema {
+    pub code: String,
+}
+
+// Konfirmasi pendaftaran 2FA
+#[derive(Serialize, Deserialize, Debug, ToSchema)]
+pub struct ConfirmMfaSchema {
+    pub code: String,
+}
+
+// Langkah kedua login, salah satu dari code atau recovery_code
+#[derive(Serialize, Deserialize, Debug, ToSchema)]
+pub struct MfaChallengeSchema {
+    pub mfa_token: String,
+    pub code: Option<String>,
+    pub recovery_code: Option<String>,
+}
+
+// Menonaktifkan 2FA butuh password dan faktor kedua
+#[derive(Serialize, Deserialize, Debug, ToSchema)]
+pub struct DisableMfaSchema {
+    pub password: String,
+    pub code: Option<String>,
+    pub recovery_code: Option<String>,
+}
+
+// API key baru untuk pemanggilan antar service
+#[derive(Serialize, Deserialize, Debug, ToSchema)]
+pub struct CreateApiKeySchema {
+    pub name: String,
+    pub scopes: Option<Vec<String>>,
+    pub expires_in_days: Option<i64>,
+}
+
+// WebSocket upgrade, browsers cannot send the Authorization header
+#[derive(Deserialize, Debug, Default, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct WsQuery {
+    pub token: Option<String>,
+}
+
+// WebSocket client message
+#[derive(Deserialize, Debug)]
+#[serde(tag = "action", rename_all = "snake_case")]
+pub enum WsClientMessage {
+    Subscribe { topics: Vec<String> },
+    Unsubscribe { topics: Vec<String> },
+    Ping,
+}
+
+// Export pengguna
+#[derive(Deserialize, Debug, Default, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct ExportUsersQuery {
+    /// csv | ndjson | json (default)
+    pub format: Option<String>,
+    /// Dipisah koma, mis. "id,username,email"
+    pub columns: Option<String>,
+    /// Sama dengan GET /api/user, default "id"; awali dengan "-" untuk urutan turun
+    pub sort: Option<String>,
+    pub include_deleted: Option<bool>,
+}
+
+// Pencarian pengguna
+#[derive(Deserialize, Debug, Default, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct SearchUsersQuery {
+    pub q: Option<String>,
+    /// Default 20, maksimal 50
+    pub limit: Option<usize>,
+}
+
+// Import pengguna dari CSV / NDJSON
+#[derive(Deserialize, Debug, Default, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct ImportUsersQuery {
+    /// csv | ndjson, default dari Content-Type
+    pub format: Option<String>,
+    /// atomic (default) | best_effort
+    pub mode: Option<String>,
+    pub dry_run: Option<bool>,
+    /// Paksa dijalankan sebagai background job
+    pub background: Option<bool>,
+}
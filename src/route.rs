@@ -1,34 +1,687 @@
 use std::sync::Arc;
 
-use axum::{routing::get, Router};
+use axum::{
+    extract::{DefaultBodyLimit, Request},
+    middleware,
+    routing::{delete, get, post, MethodRouter},
+    Extension, Router,
+};
 use tokio::sync::OnceCell;
+use tower::ServiceBuilder;
+use tower_http::{
+    request_id::{MakeRequestUuid, PropagateRequestIdLayer, SetRequestIdLayer},
+    trace::TraceLayer,
+};
+use utoipa_swagger_ui::{Config, SwaggerUi};
 
 use crate::{
     controller::{
+        api_key_controller::{
+            create_api_key_controller, get_api_keys_controller, revoke_api_key_controller,
+        },
+        audit_controller::get_audit_logs_controller,
+        auth_controller::{
+            forgot_password_controller, login_controller, reset_password_controller,
+        },
         healthcheck_controller::healthcheck_controller,
+        metrics_controller::metrics_controller,
+        mfa_controller::{
+            confirm_mfa_controller, disable_mfa_controller, enroll_mfa_controller,
+            mfa_challenge_controller, mfa_status_controller,
+        },
+        openapi_controller::openapi_controller,
+        replay_controller::replay_controller,
+        resource_controller::{
+            create_resource_controller, delete_resource_controller, get_resource_controller,
+            get_resource_schemas_controller, list_resources_controller,
+            resource_openapi_controller, update_resource_controller,
+        },
+        simulation_controller::{get_simulation_controller, reset_simulation_controller},
         user_controller::{
-            create_user_controller, delete_user_by_id_controller, get_all_user_controller,
-            get_user_by_id_controller, update_user_controller,
+            create_user_controller, delete_user_by_id_controller, export_users_controller,
+            get_all_user_controller, get_user_by_id_controller, get_user_presence_controller,
+            patch_user_controller, restore_user_controller, search_users_controller,
+            unlock_user_controller, update_user_controller,
         },
+        user_import_controller::{get_import_job_controller, import_users_controller},
+        user_v2_controller::{
+            create_user_v2_controller, delete_user_v2_controller, get_user_v2_controller,
+            get_users_v2_controller, patch_user_v2_controller, update_user_v2_controller,
+            v2_fallback_controller,
+        },
+        verification_controller::{
+            send_email_verification_controller, send_phone_verification_controller,
+            verify_email_controller, verify_phone_controller,
+        },
+        ws_controller::ws_controller,
+    },
+    model::resource::resources,
+    service::simulation::simulation_service::init_simulation_app_state,
+    utils::{
+        context::request_context,
+        metrics::track_metrics,
+        rate_limit::rate_limit,
+        simulation::simulate_faults,
+        singleton::{init_app_state, AppState},
+        versioning::mount_versions,
     },
-    utils::singleton::{init_app_state, AppState},
 };
 
 static APP_STATE: OnceCell<Arc<AppState>> = OnceCell::const_new();
 
-pub async fn create_router() -> Router {
-    let app_state: Arc<AppState> = APP_STATE.get_or_init(init_app_state).await.clone();
-    Router::new()
-        .route("/api/healthcheck", get(healthcheck_controller))
-        .route(
-            "/api/user",
+// File import jauh lebih besar dari batas body default (2 MB)
+fn import_max_bytes() -> usize {
+    std::env::var("IMPORT_MAX_BYTES")
+        .ok()
+        .and_then(|v| v.parse().ok())
+        .unwrap_or(10 * 1024 * 1024)
+}
+
+fn request_span(req: &Request) -> tracing::Span {
+    let request_id = req
+        .headers()
+        .get("x-request-id")
+        .and_then(|value| value.to_str().ok())
+        .unwrap_or_default();
+    // Tanpa query string: ?token= pada /ws dan verifikasi email tidak boleh masuk log
+    tracing::info_span!(
+        "request",
+        method = %req.method(),
+        path = %req.uri().path(),
+        request_id = %request_id,
+    )
+}
+
+// Route CRUD untuk setiap file schema di RESOURCE_DIR
+fn resource_routes() -> Router<Arc<AppState>> {
+    resources().iter().fold(Router::new(), |router, schema| {
+        let path = format!("/{}", schema.name);
+        let routes = Router::new()
+            .route(
+                &path,
+                get(list_resources_controller).post(create_resource_controller),
+            )
+            .route(
+                &format!("{}/:id", path),
+                get(get_resource_controller)
+                    .put(update_resource_controller)
+                    .delete(delete_resource_controller),
+            )
+            .layer(Extension(schema.clone()));
+        router.merge(routes)
+    })
+}
+
+// Path dan method router, satu daftar untuk Router dan uji drift OpenAPI
+type Routes<S> = Vec<(&'static str, MethodRouter<S>)>;
+
+fn router<S>(routes: Routes<S>) -> Router<S>
+where
+    S: Clone + Send + Sync + 'static,
+{
+    routes
+        .into_iter()
+        .fold(Router::new(), |router, (path, method_router)| {
+            router.route(path, method_router)
+        })
+}
+
+// Di luar prefix versi
+fn root_routes() -> Routes<Arc<AppState>> {
+    vec![
+        ("/metrics", get(metrics_controller)),
+        ("/api/openapi.json", get(openapi_controller)),
+    ]
+}
+
+// Route yang datanya lewat AppState.users, throttles dan idempotency, jadi juga
+// dilayani mode --simulate
+fn user_routes<S>() -> Routes<S>
+where
+    S: Clone + Send + Sync + 'static,
+{
+    vec![
+        ("/healthcheck", get(healthcheck_controller)),
+        (
+            "/user",
             get(get_all_user_controller).post(create_user_controller),
-        )
-        .route(
-            "/api/user/:id",
+        ),
+        (
+            "/user/:id",
             get(get_user_by_id_controller)
                 .put(update_user_controller)
+                .patch(patch_user_controller)
                 .delete(delete_user_by_id_controller),
+        ),
+        ("/user/:id/presence", get(get_user_presence_controller)),
+        ("/user/:id/unlock", post(unlock_user_controller)),
+        ("/user/:id/restore", post(restore_user_controller)),
+        ("/auth/login", post(login_controller)),
+    ]
+}
+
+// Dilayani di /api/v1 dan di /api (deprecated), ditambah route resource
+fn v1_routes() -> Routes<Arc<AppState>> {
+    let mut routes = user_routes();
+    routes.extend([
+        ("/user/export", get(export_users_controller)),
+        ("/user/search", get(search_users_controller)),
+        (
+            "/user/import",
+            post(import_users_controller).layer(DefaultBodyLimit::max(import_max_bytes())),
+        ),
+        ("/user/import/:job_id", get(get_import_job_controller)),
+        ("/auth/forgot-password", post(forgot_password_controller)),
+        ("/auth/reset-password", post(reset_password_controller)),
+        (
+            "/auth/verify/email",
+            get(verify_email_controller).post(send_email_verification_controller),
+        ),
+        ("/auth/verify/phone", post(verify_phone_controller)),
+        (
+            "/auth/verify/phone/send",
+            post(send_phone_verification_controller),
+        ),
+        ("/auth/2fa", get(mfa_status_controller)),
+        ("/auth/2fa/enroll", post(enroll_mfa_controller)),
+        ("/auth/2fa/confirm", post(confirm_mfa_controller)),
+        ("/auth/2fa/challenge", post(mfa_challenge_controller)),
+        ("/auth/2fa/disable", post(disable_mfa_controller)),
+        (
+            "/keys",
+            get(get_api_keys_controller).post(create_api_key_controller),
+        ),
+        ("/keys/:id", delete(revoke_api_key_controller)),
+        ("/ws", get(ws_controller)),
+        ("/audit", get(get_audit_logs_controller)),
+        ("/resources", get(get_resource_schemas_controller)),
+        ("/resources/openapi.json", get(resource_openapi_controller)),
+    ]);
+    routes
+}
+
+// Kontrak response baru: {"data": ...} atau {"error": {...}}, lihat model::response
+fn v2_routes<S>() -> Routes<S>
+where
+    S: Clone + Send + Sync + 'static,
+{
+    vec![
+        (
+            "/user",
+            get(get_users_v2_controller).post(create_user_v2_controller),
+        ),
+        (
+            "/user/:id",
+            get(get_user_v2_controller)
+                .put(update_user_v2_controller)
+                .patch(patch_user_v2_controller)
+                .delete(delete_user_v2_controller),
+        ),
+    ]
+}
+
+pub async fn create_router() -> Router {
+    let app_state: Arc<AppState> = APP_STATE.get_or_init(init_app_state).await.clone();
+    router(root_routes())
+        .merge(SwaggerUi::new("/api/docs").config(Config::from("/api/openapi.json")))
+        .merge(mount_versions(
+            router(v1_routes()).merge(resource_routes()),
+            router(v2_routes()).fallback(v2_fallback_controller),
+        ))
+        .layer(
+            ServiceBuilder::new()
+                .layer(SetRequestIdLayer::x_request_id(MakeRequestUuid))
+                .layer(TraceLayer::new_for_http().make_span_with(request_span))
+                .layer(PropagateRequestIdLayer::x_request_id())
+                .layer(middleware::from_fn(track_metrics))
+                .layer(middleware::from_fn(request_context))
+                .layer(middleware::from_fn(rate_limit)),
         )
         .with_state(app_state)
 }
+
+// Mode --simulate: controller yang sama dengan mode biasa, data dari memori tanpa database
+fn simulation_routes() -> Routes<()> {
+    let mut routes = user_routes();
+    routes.extend([
+        ("/simulation", get(get_simulation_controller)),
+        ("/simulation/reset", post(reset_simulation_controller)),
+    ]);
+    routes
+}
+
+pub fn create_simulation_router() -> Router {
+    init_simulation_app_state();
+    mount_versions(
+        router(simulation_routes()),
+        router(v2_routes()).fallback(v2_fallback_controller),
+    )
+    .layer(
+        ServiceBuilder::new()
+            .layer(SetRequestIdLayer::x_request_id(MakeRequestUuid))
+            .layer(TraceLayer::new_for_http().make_span_with(request_span))
+            .layer(PropagateRequestIdLayer::x_request_id())
+            .layer(middleware::from_fn(track_metrics))
+            .layer(middleware::from_fn(request_context))
+            .layer(middleware::from_fn(simulate_faults)),
+    )
+}
+
+// Mode --replay: tidak ada route, semua request dicocokkan dengan rekaman
+pub fn create_replay_router() -> Router {
+    Router::new().fallback(replay_controller).layer(
+        ServiceBuilder::new()
+            .layer(SetRequestIdLayer::x_request_id(MakeRequestUuid))
+            .layer(TraceLayer::new_for_http().make_span_with(request_span))
+            .layer(PropagateRequestIdLayer::x_request_id()),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::{
+        body::{to_bytes, Body},
+        http::{
+            header::{CONTENT_TYPE, ETAG, LINK},
+            HeaderMap, Method, Request, StatusCode,
+        },
+    };
+    use serde_json::Value;
+    use std::collections::BTreeSet;
+    use tower::ServiceExt;
+    use utoipa::OpenApi;
+
+    use super::*;
+    use crate::{
+        service::openapi_service::ApiDoc,
+        utils::versioning::{API_V1, API_V2, DEPRECATION, SUNSET},
+    };
+
+    async fn send(
+        router: Router,
+        method: Method,
+        uri: &str,
+        body: &str,
+    ) -> (StatusCode, HeaderMap, Value) {
+        send_with(router, method, uri, &[], body).await
+    }
+
+    async fn send_with(
+        router: Router,
+        method: Method,
+        uri: &str,
+        headers: &[(&str, &str)],
+        body: &str,
+    ) -> (StatusCode, HeaderMap, Value) {
+        let mut req = Request::builder()
+            .method(method)
+            .uri(uri)
+            .header(CONTENT_TYPE, "application/json");
+        for (name, value) in headers {
+            req = req.header(*name, *value);
+        }
+        let req = req.body(Body::from(body.to_string())).unwrap();
+        let response = router.oneshot(req).await.unwrap();
+        let status = response.status();
+        let headers = response.headers().clone();
+        let bytes = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        (
+            status,
+            headers,
+            serde_json::from_slice(&bytes).unwrap_or_default(),
+        )
+    }
+
+    async fn get(router: Router, uri: &str) -> (StatusCode, HeaderMap, Value) {
+        send(router, Method::GET, uri, "").await
+    }
+
+    #[tokio::test]
+    async fn v1_serves_current_contract() {
+        let (status, headers, body) = get(create_simulation_router(), "/api/v1/user/1").await;
+
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["status"], "ok");
+        assert_eq!(body["data"]["id"], 1);
+        assert!(headers.get(DEPRECATION).is_none());
+        assert!(headers.get(SUNSET).is_none());
+    }
+
+    #[tokio::test]
+    async fn unversioned_alias_is_deprecated() {
+        let (_, _, v1_body) = get(create_simulation_router(), "/api/v1/user/1").await;
+        let (status, headers, body) = get(create_simulation_router(), "/api/user/1").await;
+
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body, v1_body);
+        let deprecation = headers[DEPRECATION].to_str().unwrap();
+        assert!(deprecation.starts_with('@'), "{}", deprecation);
+        assert!(headers[SUNSET].to_str().unwrap().ends_with(" GMT"));
+        assert_eq!(headers[LINK], "</api/v1/user/1>; rel=\"successor-version\"");
+    }
+
+    #[tokio::test]
+    async fn v2_errors_use_error_object() {
+        let cases = [
+            ("GET", "/api/v2/nope", "", 404, "not_found"),
+            ("GET", "/api/v2/user/abc", "", 400, "invalid_path"),
+            ("GET", "/api/v2/user?limit=abc", "", 400, "invalid_query"),
+            (
+                "GET",
+                "/api/v2/user?include_deleted=true",
+                "",
+                403,
+                "forbidden",
+            ),
+            ("POST", "/api/v2/user", "{", 400, "invalid_body"),
+            ("PUT", "/api/v2/user/1", "{", 400, "invalid_body"),
+        ];
+        for (method, uri, request_body, expected_status, code) in cases {
+            let method = Method::from_bytes(method.as_bytes()).unwrap();
+            let (status, headers, body) =
+                send(create_simulation_router(), method, uri, request_body).await;
+
+            assert_eq!(status.as_u16(), expected_status, "{}", uri);
+            assert_eq!(body["error"]["code"], code, "{}", uri);
+            assert!(body["error"]["message"].is_string(), "{}", uri);
+            assert!(body.get("status").is_none(), "{}", uri);
+            assert!(headers.get(DEPRECATION).is_none(), "{}", uri);
+        }
+    }
+
+    #[tokio::test]
+    async fn v2_is_not_aliased_under_api() {
+        let (status, _, _) = get(create_simulation_router(), "/api/v1/v2/user").await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+
+        let (status, _, body) = get(create_simulation_router(), "/api/v2/user?limit=2").await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["data"].as_array().map(Vec::len), Some(2));
+    }
+
+    // Controller dan service yang sama dengan mode biasa, termasuk login dan Idempotency-Key
+    #[tokio::test]
+    async fn simulation_serves_regular_controllers() {
+        use crate::service::simulation::simulation_service::SEED_PASSWORD;
+
+        let login = serde_json::json!({ "username": "admin", "password": SEED_PASSWORD });
+        let (status, _, body) = send(
+            create_simulation_router(),
+            Method::POST,
+            "/api/v1/auth/login",
+            &login.to_string(),
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK, "{}", body);
+        let token = format!("Bearer {}", body["data"]["token"].as_str().unwrap());
+
+        let (status, _, body) = send_with(
+            create_simulation_router(),
+            Method::GET,
+            "/api/simulation",
+            &[("authorization", &token)],
+            "",
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK, "{}", body);
+
+        let new_user = serde_json::json!({
+            "username": "simulasi",
+            "email": "simulasi@example.com",
+            "phone": "+6281234567890",
+            "password": "Password123!"
+        })
+        .to_string();
+        let headers = [("idempotency-key", "simulasi-1")];
+        let (_, first_headers, first) = send_with(
+            create_simulation_router(),
+            Method::POST,
+            "/api/v1/user",
+            &headers,
+            &new_user,
+        )
+        .await;
+        assert_eq!(first["status"], "ok", "{}", first);
+        assert!(first_headers.get("idempotent-replayed").is_none());
+
+        let (_, replay_headers, replay) = send_with(
+            create_simulation_router(),
+            Method::POST,
+            "/api/v1/user",
+            &headers,
+            &new_user,
+        )
+        .await;
+        assert_eq!(replay, first);
+        assert_eq!(replay_headers["idempotent-replayed"], "true");
+
+        let (_, _, duplicate) = send(
+            create_simulation_router(),
+            Method::POST,
+            "/api/v1/user",
+            &new_user,
+        )
+        .await;
+        assert_eq!(
+            duplicate["message"],
+            "username is already taken; email is already taken; phone is already taken"
+        );
+    }
+
+    // Kredensial salah 401, bukan 500; kegagalan database/hashing baru 500
+    #[tokio::test]
+    async fn login_rejects_invalid_credentials_with_401() {
+        for username in ["admin", "pengguna-tidak-ada"] {
+            let login = serde_json::json!({ "username": username, "password": "salah-total" });
+            let (status, _, body) = send(
+                create_simulation_router(),
+                Method::POST,
+                "/api/v1/auth/login",
+                &login.to_string(),
+            )
+            .await;
+            assert_eq!(status, StatusCode::UNAUTHORIZED, "{}", body);
+            assert_eq!(body["message"], "Invalid username or password");
+        }
+    }
+
+    #[tokio::test]
+    async fn v2_update_with_taken_email_is_validation_error() {
+        let (_, _, other) = get(create_simulation_router(), "/api/v2/user/2").await;
+        let body = serde_json::json!({
+            "username": "putsimulasi",
+            "email": other["data"]["email"],
+            "phone": "+6281234567891",
+            "password": "Password123!"
+        });
+        let (status, _, body) = send(
+            create_simulation_router(),
+            Method::PUT,
+            "/api/v2/user/1",
+            &body.to_string(),
+        )
+        .await;
+
+        assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY, "{}", body);
+        assert_eq!(body["error"]["code"], "validation_failed");
+        assert_eq!(body["error"]["message"], "email is already taken");
+
+        // Nilai milik pengguna itu sendiri tidak dianggap bentrok
+        let body = serde_json::json!({
+            "username": other["data"]["username"],
+            "email": other["data"]["email"],
+            "phone": other["data"]["phone"],
+            "password": "Password123!"
+        });
+        let (status, _, body) = send(
+            create_simulation_router(),
+            Method::PUT,
+            "/api/v2/user/2",
+            &body.to_string(),
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK, "{}", body);
+    }
+
+    #[tokio::test]
+    async fn patch_updates_only_sent_fields_and_honors_if_match() {
+        let (_, headers, before) = get(create_simulation_router(), "/api/v2/user/4").await;
+        let current = headers[ETAG].to_str().unwrap().to_string();
+        let body = serde_json::json!({ "phone": "+6281234567892" }).to_string();
+
+        let (status, _, _) = send_with(
+            create_simulation_router(),
+            Method::PATCH,
+            "/api/v2/user/4",
+            &[("if-match", "\"0\"")],
+            &body,
+        )
+        .await;
+        assert_eq!(status, StatusCode::PRECONDITION_FAILED);
+
+        let (status, headers, after) = send_with(
+            create_simulation_router(),
+            Method::PATCH,
+            "/api/v2/user/4",
+            &[("if-match", &current)],
+            &body,
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK, "{}", after);
+        assert_ne!(headers[ETAG].to_str().unwrap(), current);
+        assert_eq!(after["data"]["phone"], "+6281234567892");
+        assert_eq!(after["data"]["username"], before["data"]["username"]);
+        assert_eq!(after["data"]["email"], before["data"]["email"]);
+
+        // Password lama tetap berlaku karena tidak dikirim
+        use crate::service::simulation::simulation_service::SEED_PASSWORD;
+        let login = serde_json::json!({
+            "username": before["data"]["username"],
+            "password": SEED_PASSWORD
+        });
+        let (status, _, body) = send(
+            create_simulation_router(),
+            Method::POST,
+            "/api/v1/auth/login",
+            &login.to_string(),
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK, "{}", body);
+
+        let (status, _, body) = send(
+            create_simulation_router(),
+            Method::PATCH,
+            "/api/v1/user/4",
+            &serde_json::json!({ "email": "bukan-email" }).to_string(),
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["status"], "error");
+    }
+
+    const HTTP_METHODS: [Method; 5] = [
+        Method::GET,
+        Method::POST,
+        Method::PUT,
+        Method::DELETE,
+        Method::PATCH,
+    ];
+
+    async fn teapot(_: Request<Body>, _: middleware::Next) -> StatusCode {
+        StatusCode::IM_A_TEAPOT
+    }
+
+    // Handler diganti 418 supaya tidak menyentuh database, method lain tetap 405
+    fn stubbed<S>(routes: Routes<S>) -> Routes<S>
+    where
+        S: Clone + Send + Sync + 'static,
+    {
+        routes
+            .into_iter()
+            .map(|(path, method_router)| {
+                (path, method_router.route_layer(middleware::from_fn(teapot)))
+            })
+            .collect()
+    }
+
+    // Router dari daftar route yang sama dengan create_router
+    fn probe_router() -> Router {
+        router(stubbed(root_routes()))
+            .merge(mount_versions(
+                router(stubbed(v1_routes())),
+                router(stubbed(v2_routes())),
+            ))
+            .with_state(init_simulation_app_state())
+    }
+
+    // Method yang dijawab Router untuk path, format "METHOD /path/{param}" seperti OpenAPI
+    async fn routed_operations(router: &Router, path: &str) -> BTreeSet<String> {
+        let segments = path.split('/');
+        let uri: Vec<_> = segments
+            .clone()
+            .map(|segment| {
+                if segment.starts_with(':') || segment.starts_with('{') {
+                    "1"
+                } else {
+                    segment
+                }
+            })
+            .collect();
+        let documented: Vec<_> = segments
+            .map(|segment| match segment.strip_prefix(':') {
+                Some(param) => format!("{{{}}}", param),
+                None => segment.to_string(),
+            })
+            .collect();
+
+        let mut operations = BTreeSet::new();
+        for method in HTTP_METHODS {
+            let (status, _, _) = send(router.clone(), method.clone(), &uri.join("/"), "").await;
+            if status == StatusCode::IM_A_TEAPOT {
+                operations.insert(format!("{} {}", method, documented.join("/")));
+            }
+        }
+        operations
+    }
+
+    #[tokio::test]
+    async fn spec_matches_routes() {
+        let router = probe_router();
+        let tables = [
+            ("", root_routes()),
+            (API_V1, v1_routes()),
+            (API_V2, v2_routes()),
+        ];
+        let mut routes = BTreeSet::new();
+        for (prefix, table) in tables {
+            for (path, _) in table {
+                routes.extend(routed_operations(&router, &format!("{}{}", prefix, path)).await);
+            }
+        }
+
+        let document = serde_json::to_value(ApiDoc::openapi()).unwrap();
+        let mut spec = BTreeSet::new();
+        for (path, item) in document["paths"].as_object().unwrap() {
+            for method in HTTP_METHODS {
+                if item.get(method.as_str().to_lowercase()).is_some() {
+                    spec.insert(format!("{} {}", method, path));
+                }
+            }
+        }
+        // Operasi di spec harus benar-benar dijawab Router, bukan hanya tercatat di daftar
+        let mut served = BTreeSet::new();
+        for operation in &spec {
+            let path = operation.split_once(' ').unwrap().1;
+            served.extend(routed_operations(&router, path).await);
+        }
+
+        let undocumented: Vec<_> = routes.difference(&spec).collect();
+        let stale: Vec<_> = spec.difference(&served).collect();
+        assert!(
+            undocumented.is_empty() && stale.is_empty(),
+            "OpenAPI drifted from route.rs\n  routes without #[utoipa::path] in ApiDoc: {:?}\n  documented but not routed: {:?}",
+            undocumented,
+            stale
+        );
+    }
+}
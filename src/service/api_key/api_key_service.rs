@@ -0,0 +1,141 @@
+use crate::{
+    model::{model::ApiKeyModel, schema::CreateApiKeySchema},
+    utils::{
+        adapter::sql_span,
+        auth::{AuthUser, API_KEY_SCOPES},
+        observer::Event,
+        singleton::{init_app_state, AppState},
+        token::{generate_token, hash_token},
+    },
+};
+use axum::{extract::State, Json};
+use std::sync::Arc;
+use tokio::sync::OnceCell;
+use tracing::Instrument;
+
+static APP_STATE: OnceCell<Arc<AppState>> = OnceCell::const_new();
+
+// Key lengkap hanya dikembalikan sekali saat dibuat
+#[tracing::instrument(skip_all, fields(user_id = owner.id))]
+pub async fn create_api_key_service(
+    owner: &AuthUser,
+    Json(body): Json<CreateApiKeySchema>,
+) -> Result<(ApiKeyModel, String), String> {
+    if owner.api_key_id.is_some() {
+        return Err("API keys cannot create other API keys".to_string());
+    }
+    let name = body.name.trim();
+    if name.is_empty() || name.len() > 100 {
+        return Err("name must be between 1 and 100 characters".to_string());
+    }
+    let scopes = body.scopes.unwrap_or_default();
+    if let Some(scope) = scopes
+        .iter()
+        .find(|s| !API_KEY_SCOPES.contains(&s.as_str()))
+    {
+        return Err(format!(
+            "Unknown scope '{}', allowed scopes: {}",
+            scope,
+            API_KEY_SCOPES.join(", ")
+        ));
+    }
+    if body.expires_in_days.is_some_and(|days| days <= 0) {
+        return Err("expires_in_days must be positive".to_string());
+    }
+
+    let app_state: Arc<AppState> = APP_STATE.get_or_init(init_app_state).await.clone();
+    let data: State<Arc<AppState>> = State(app_state);
+    let prefix = generate_token()[..8].to_string();
+    let key = format!("simu_{}_{}", prefix, generate_token());
+    let expires_at = body
+        .expires_in_days
+        .map(|days| chrono::Utc::now() + chrono::Duration::days(days));
+
+    let insert_query = "INSERT INTO api_keys (user_id, name, prefix, key_hash, scopes, expires_at) VALUES (?, ?, ?, ?, ?, ?);";
+    let res = sqlx::query(insert_query)
+        .bind(owner.id)
+        .bind(name)
+        .bind(&prefix)
+        .bind(hash_token(&key))
+        .bind(sqlx::types::Json(&scopes))
+        .bind(expires_at)
+        .execute(&data.db)
+        .instrument(sql_span(insert_query))
+        .await
+        .map_err(|err: sqlx::Error| err.to_string())?;
+
+    let query = data
+        .query_builder
+        .select_by_id("api_keys", res.last_insert_id());
+    let api_key = sqlx::query_as::<_, ApiKeyModel>(&query)
+        .fetch_one(&data.db)
+        .instrument(sql_span(&query))
+        .await
+        .map_err(|err: sqlx::Error| err.to_string())?;
+
+    data.observable.notify_event(Event::new(
+        "auth.api_key_created",
+        Some(owner.id),
+        serde_json::json!({ "api_key_id": api_key.id, "prefix": prefix, "scopes": scopes }),
+    ));
+
+    Ok((api_key, key))
+}
+
+// Admin melihat semua key, pengguna lain hanya miliknya
+#[tracing::instrument(skip_all, fields(user_id = user.id))]
+pub async fn get_api_keys_service(user: &AuthUser) -> Result<Vec<ApiKeyModel>, String> {
+    let app_state: Arc<AppState> = APP_STATE.get_or_init(init_app_state).await.clone();
+    let data: State<Arc<AppState>> = State(app_state);
+    let conditions: &[&str] = if user.is_admin() {
+        &[]
+    } else {
+        &["user_id = ?"]
+    };
+    let query =
+        data.query_builder
+            .select_where("api_keys", conditions, false, "created_at DESC", 1000, 0);
+    let mut q = sqlx::query_as::<_, ApiKeyModel>(&query);
+    if !user.is_admin() {
+        q = q.bind(user.id);
+    }
+
+    q.fetch_all(&data.db)
+        .instrument(sql_span(&query))
+        .await
+        .map_err(|err: sqlx::Error| err.to_string())
+}
+
+#[tracing::instrument(skip_all, fields(user_id = user.id, api_key_id = id))]
+pub async fn revoke_api_key_service(user: &AuthUser, id: i64) -> Result<(), String> {
+    let app_state: Arc<AppState> = APP_STATE.get_or_init(init_app_state).await.clone();
+    let data: State<Arc<AppState>> = State(app_state);
+    let query = data.query_builder.select_by_id("api_keys", id as u64);
+    let api_key = sqlx::query_as::<_, ApiKeyModel>(&query)
+        .fetch_optional(&data.db)
+        .instrument(sql_span(&query))
+        .await
+        .map_err(|err: sqlx::Error| err.to_string())?
+        .filter(|api_key| user.is_admin() || api_key.user_id == user.id)
+        .ok_or_else(|| format!("API key with id {} does not exist", id))?;
+    if api_key.revoked_at.is_some() {
+        return Ok(());
+    }
+
+    let revoke_query = "UPDATE api_keys SET revoked_at = ? WHERE id = ?;";
+    sqlx::query(revoke_query)
+        .bind(chrono::Utc::now())
+        .bind(id)
+        .execute(&data.db)
+        .instrument(sql_span(revoke_query))
+        .await
+        .map_err(|err: sqlx::Error| err.to_string())?;
+
+    data.observable.notify_event(Event::new(
+        "auth.api_key_revoked",
+        Some(api_key.user_id),
+        serde_json::json!({ "api_key_id": id, "prefix": api_key.prefix }),
+    ));
+
+    Ok(())
+}
@@ -0,0 +1,72 @@
+use crate::{
+    model::{model::AuditLogModel, schema::AuditFilterOptions},
+    utils::{
+        adapter::sql_span,
+        singleton::{init_app_state, AppState},
+    },
+};
+use axum::extract::State;
+use std::sync::Arc;
+use tokio::sync::OnceCell;
+use tracing::Instrument;
+
+static APP_STATE: OnceCell<Arc<AppState>> = OnceCell::const_new();
+
+#[tracing::instrument]
+pub async fn get_audit_logs_service(
+    opts: AuditFilterOptions,
+) -> Result<Vec<AuditLogModel>, String> {
+    let app_state: Arc<AppState> = APP_STATE.get_or_init(init_app_state).await.clone();
+    let data: State<Arc<AppState>> = State(app_state);
+
+    let limit = opts.limit.unwrap_or(50).clamp(1, 200);
+    let offset = (opts.page.unwrap_or(1).max(1) - 1) * limit;
+
+    let mut conditions = Vec::new();
+    if opts.actor_id.is_some() {
+        conditions.push("actor_id = ?");
+    }
+    if opts.target_user_id.is_some() {
+        conditions.push("target_user_id = ?");
+    }
+    if opts.action.is_some() {
+        conditions.push("action = ?");
+    }
+    if opts.from.is_some() {
+        conditions.push("created_at >= ?");
+    }
+    if opts.to.is_some() {
+        conditions.push("created_at <= ?");
+    }
+
+    let query = data.query_builder.select_where(
+        "audit_log",
+        &conditions,
+        false,
+        "created_at DESC, id DESC",
+        limit,
+        offset,
+    );
+    let mut audit_query = sqlx::query_as::<_, AuditLogModel>(&query);
+    if let Some(actor_id) = opts.actor_id {
+        audit_query = audit_query.bind(actor_id);
+    }
+    if let Some(target_user_id) = opts.target_user_id {
+        audit_query = audit_query.bind(target_user_id);
+    }
+    if let Some(action) = opts.action {
+        audit_query = audit_query.bind(action);
+    }
+    if let Some(from) = opts.from {
+        audit_query = audit_query.bind(from);
+    }
+    if let Some(to) = opts.to {
+        audit_query = audit_query.bind(to);
+    }
+
+    audit_query
+        .fetch_all(&data.db)
+        .instrument(sql_span(&query))
+        .await
+        .map_err(|err: sqlx::Error| err.to_string())
+}
@@ -0,0 +1,273 @@
+use crate::{
+    model::{
+        model::PasswordResetTokenModel,
+        schema::{ForgotPasswordSchema, LoginSchema, ResetPasswordSchema},
+    },
+    service::user::user_validator::password_validation,
+    utils::{
+        adapter::sql_span,
+        auth::{create_mfa_token, create_token, verification_policy, VerificationPolicy},
+        context::RequestContext,
+        hashing::{hash_password, needs_rehash, verify_password},
+        lockout::{self, ThrottleKey},
+        mailer::{app_url, Mail},
+        observer::Event,
+        singleton::{init_app_state, AppState},
+        token::{generate_token, hash_token},
+    },
+};
+use axum::{extract::State, Json};
+use std::sync::Arc;
+use tokio::sync::OnceCell;
+use tracing::Instrument;
+
+static APP_STATE: OnceCell<Arc<AppState>> = OnceCell::const_new();
+
+pub enum LoginOutcome {
+    // Token akses dan masa berlakunya (detik)
+    Token(String, i64),
+    // Password benar, tetapi pengguna harus menyelesaikan challenge 2FA
+    MfaRequired(String, i64),
+    // Terlalu banyak percobaan gagal, detik sampai boleh mencoba lagi
+    Throttled(i64),
+    // Kredensial ditolak (401); Err dipakai untuk kegagalan database/hashing (500)
+    Rejected(String),
+}
+
+// Penghitung per akun dan per IP pemanggil
+pub fn login_throttle_keys(user_id: Option<i32>, identifier: &str) -> Vec<ThrottleKey> {
+    let mut keys = vec![ThrottleKey::account(user_id, identifier)];
+    if let Some(ip) = RequestContext::current().and_then(|ctx| ctx.ip) {
+        keys.push(ThrottleKey::ip(&ip));
+    }
+    keys
+}
+
+pub async fn record_login_failure(
+    data: &AppState,
+    keys: &[ThrottleKey],
+    user_id: Option<i32>,
+    identifier: &str,
+    reason: &str,
+) -> Result<(), String> {
+    data.observable.notify_event(Event::new(
+        "auth.login_failed",
+        user_id,
+        serde_json::json!({ "identifier": identifier, "reason": reason }),
+    ));
+
+    for key in keys {
+        if lockout::record_failure(data.throttles.as_ref(), key).await? {
+            data.observable.notify_event(Event::new(
+                "auth.login_locked",
+                user_id,
+                serde_json::json!({ "identifier": identifier, "scope": key.scope.as_str() }),
+            ));
+        }
+    }
+
+    Ok(())
+}
+
+#[tracing::instrument(skip_all, fields(username = %body.username))]
+pub async fn login_service(Json(body): Json<LoginSchema>) -> Result<LoginOutcome, String> {
+    let app_state: Arc<AppState> = APP_STATE.get_or_init(init_app_state).await.clone();
+    let data: State<Arc<AppState>> = State(app_state);
+    let field = if body.username.contains('@') {
+        "email"
+    } else {
+        "username"
+    };
+    let user = data.users.find_by_field(field, &body.username).await?;
+
+    let keys = login_throttle_keys(user.as_ref().map(|user| user.id), &body.username);
+    if let Some(retry_after) = lockout::retry_after(data.throttles.as_ref(), &keys).await? {
+        data.observable.notify_event(Event::new(
+            "auth.login_failed",
+            user.as_ref().map(|user| user.id),
+            serde_json::json!({ "identifier": body.username, "reason": "throttled" }),
+        ));
+        return Ok(LoginOutcome::Throttled(retry_after));
+    }
+
+    let user = match user {
+        Some(user) => user,
+        None => {
+            record_login_failure(&data, &keys, None, &body.username, "unknown_user").await?;
+            return Ok(LoginOutcome::Rejected(
+                "Invalid username or password".to_string(),
+            ));
+        }
+    };
+    if !verify_password(&body.password, &user.password).await? {
+        record_login_failure(
+            &data,
+            &keys,
+            Some(user.id),
+            &body.username,
+            "invalid_password",
+        )
+        .await?;
+        return Ok(LoginOutcome::Rejected(
+            "Invalid username or password".to_string(),
+        ));
+    }
+    if verification_policy() == VerificationPolicy::Login && user.email_verified_at.is_none() {
+        return Ok(LoginOutcome::Rejected(
+            "Email address is not verified".to_string(),
+        ));
+    }
+
+    if needs_rehash(&user.password) {
+        if let Err(err) = rehash_password(&data, user.id, &body.password).await {
+            tracing::warn!(
+                user_id = user.id,
+                "Failed to upgrade password hash: {}",
+                err
+            );
+        }
+    }
+
+    if user.totp_enabled_at.is_some() {
+        let (token, expires_in) = create_mfa_token(&user)?;
+        return Ok(LoginOutcome::MfaRequired(token, expires_in));
+    }
+
+    // Penghitung akun baru dikosongkan saat token benar-benar diterbitkan
+    lockout::reset(data.throttles.as_ref(), &keys[0]).await?;
+    data.observable.notify_event(Event::new(
+        "auth.login",
+        Some(user.id),
+        serde_json::json!({}),
+    ));
+
+    let (token, expires_in) = create_token(&user)?;
+    Ok(LoginOutcome::Token(token, expires_in))
+}
+
+// Upgrade hash lama ke algoritma/parameter yang sedang dikonfigurasi
+async fn rehash_password(data: &AppState, id: i32, password: &str) -> Result<(), String> {
+    let password = hash_password(password).await?;
+    data.users.set_password(id, &password).await
+}
+
+fn password_reset_ttl_minutes() -> i64 {
+    std::env::var("PASSWORD_RESET_TTL_MINUTES")
+        .ok()
+        .and_then(|v| v.parse().ok())
+        .unwrap_or(30)
+}
+
+// Selalu berhasil agar tidak membocorkan email mana yang terdaftar
+#[tracing::instrument(skip_all)]
+pub async fn forgot_password_service(Json(body): Json<ForgotPasswordSchema>) -> Result<(), String> {
+    let app_state: Arc<AppState> = APP_STATE.get_or_init(init_app_state).await.clone();
+    let data: State<Arc<AppState>> = State(app_state);
+    let user = match data.users.find_by_field("email", &body.email).await? {
+        Some(user) => user,
+        None => return Ok(()),
+    };
+
+    let token = generate_token();
+    let ttl = password_reset_ttl_minutes();
+    let expires_at = chrono::Utc::now() + chrono::Duration::minutes(ttl);
+    let insert_query =
+        "INSERT INTO password_reset_tokens (user_id, token_hash, expires_at) VALUES (?, ?, ?);";
+    sqlx::query(insert_query)
+        .bind(user.id)
+        .bind(hash_token(&token))
+        .bind(expires_at)
+        .execute(&data.db)
+        .instrument(sql_span(insert_query))
+        .await
+        .map_err(|err: sqlx::Error| err.to_string())?;
+
+    let mail = Mail {
+        to: user.email.clone(),
+        subject: "Reset your password".to_string(),
+        body: format!(
+            "Hi {},\n\nUse the link below to reset your password. It expires in {} minutes and can only be used once.\n\n{}/reset-password?token={}\n\nIf you did not request this, you can ignore this email.",
+            user.username,
+            ttl,
+            app_url(),
+            token
+        ),
+    };
+    if let Err(err) = data.mailer.send(&mail) {
+        tracing::error!(
+            user_id = user.id,
+            "Failed to send password reset email: {}",
+            err
+        );
+    }
+
+    data.observable.notify_event(Event::new(
+        "auth.password_reset_requested",
+        Some(user.id),
+        serde_json::json!({}),
+    ));
+
+    Ok(())
+}
+
+#[tracing::instrument(skip_all)]
+pub async fn reset_password_service(Json(body): Json<ResetPasswordSchema>) -> Result<(), String> {
+    password_validation(&body.password).await?;
+    let app_state: Arc<AppState> = APP_STATE.get_or_init(init_app_state).await.clone();
+    let data: State<Arc<AppState>> = State(app_state);
+    let invalid_token = || "Invalid or expired reset token".to_string();
+
+    let mut tx = data.db.begin().await.map_err(|err| err.to_string())?;
+    let select_query = "SELECT * FROM password_reset_tokens WHERE token_hash = ? FOR UPDATE;";
+    let reset_token = sqlx::query_as::<_, PasswordResetTokenModel>(select_query)
+        .bind(hash_token(&body.token))
+        .fetch_optional(&mut *tx)
+        .instrument(sql_span(select_query))
+        .await
+        .map_err(|err: sqlx::Error| err.to_string())?
+        .ok_or_else(invalid_token)?;
+
+    let now = chrono::Utc::now();
+    if reset_token.used_at.is_some() || reset_token.expires_at <= now {
+        return Err(invalid_token());
+    }
+    // Hash baru dihitung setelah token terbukti valid, token palsu tidak memakan waktu argon2
+    let password = hash_password(&body.password).await?;
+
+    // Token ini dan token lain milik pengguna yang sama tidak bisa dipakai lagi
+    let use_query =
+        "UPDATE password_reset_tokens SET used_at = ? WHERE user_id = ? AND used_at IS NULL;";
+    sqlx::query(use_query)
+        .bind(now)
+        .bind(reset_token.user_id)
+        .execute(&mut *tx)
+        .instrument(sql_span(use_query))
+        .await
+        .map_err(|err: sqlx::Error| err.to_string())?;
+
+    // Menaikkan token_version mencabut semua token login yang sudah terbit
+    let update_query =
+        "UPDATE users SET password = ?, token_version = token_version + 1 WHERE id = ?;";
+    sqlx::query(update_query)
+        .bind(&password)
+        .bind(reset_token.user_id)
+        .execute(&mut *tx)
+        .instrument(sql_span(update_query))
+        .await
+        .map_err(|err: sqlx::Error| err.to_string())?;
+
+    tx.commit().await.map_err(|err| err.to_string())?;
+
+    data.observable.notify_event(
+        Event::new(
+            "user.password_reset",
+            Some(reset_token.user_id),
+            serde_json::json!({}),
+        )
+        .with_changes(serde_json::json!({
+            "password": { "before": "[REDACTED]", "after": "[REDACTED]" }
+        })),
+    );
+
+    Ok(())
+}
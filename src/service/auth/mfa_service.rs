@@ -0,0 +1,331 @@
+use crate::{
+    model::{
+        model::{RecoveryCodeModel, UserModel},
+        schema::{ConfirmMfaSchema, DisableMfaSchema, MfaChallengeSchema},
+    },
+    service::auth::auth_service::{login_throttle_keys, record_login_failure},
+    utils::{
+        adapter::sql_span,
+        auth::{create_token, verify_mfa_token},
+        crypto::{decrypt, encrypt},
+        hashing::verify_password,
+        lockout::{self, ThrottleKey},
+        observer::Event,
+        singleton::{init_app_state, AppState},
+        token::hash_token,
+        totp::{generate_secret, otpauth_uri, verify_code},
+    },
+};
+use axum::{extract::State, Json};
+use rand::RngCore;
+use std::sync::Arc;
+use tokio::sync::OnceCell;
+use tracing::Instrument;
+
+static APP_STATE: OnceCell<Arc<AppState>> = OnceCell::const_new();
+
+const RECOVERY_CODE_COUNT: usize = 10;
+
+fn invalid_code() -> String {
+    "Invalid two-factor authentication code".to_string()
+}
+
+async fn find_user(data: &AppState, id: i32) -> Result<UserModel, String> {
+    data.users
+        .find(id, false)
+        .await?
+        .ok_or_else(|| format!("User with id {} does not exist", id))
+}
+
+// Format xxxxx-xxxxx, tanda hubung dan huruf besar diabaikan saat dicocokkan
+fn generate_recovery_code() -> String {
+    let mut bytes = [0u8; 5];
+    rand::thread_rng().fill_bytes(&mut bytes);
+    let code = hex::encode(bytes);
+    format!("{}-{}", &code[..5], &code[5..])
+}
+
+fn recovery_code_hash(user_id: i32, code: &str) -> String {
+    let normalized: String = code
+        .chars()
+        .filter(|c| c.is_ascii_alphanumeric())
+        .collect::<String>()
+        .to_lowercase();
+    hash_token(&format!("{}:{}", user_id, normalized))
+}
+
+async fn check_totp(data: &AppState, user: &UserModel, code: &str) -> Result<(), String> {
+    let secret = user
+        .totp_secret
+        .as_deref()
+        .ok_or_else(|| "Two-factor authentication is not enrolled".to_string())?;
+    let secret = decrypt(secret)?;
+    let step = verify_code(&secret, code, user.totp_last_step)?.ok_or_else(invalid_code)?;
+
+    // Compare-and-set agar kode yang sama tidak lolos dua kali bersamaan
+    let query = "UPDATE users SET totp_last_step = ? WHERE id = ? AND (totp_last_step IS NULL OR totp_last_step < ?);";
+    let res = sqlx::query(query)
+        .bind(step)
+        .bind(user.id)
+        .bind(step)
+        .execute(&data.db)
+        .instrument(sql_span(query))
+        .await
+        .map_err(|err: sqlx::Error| err.to_string())?;
+    if res.rows_affected() == 0 {
+        return Err(invalid_code());
+    }
+
+    Ok(())
+}
+
+async fn use_recovery_code(data: &AppState, user: &UserModel, code: &str) -> Result<(), String> {
+    let query = "UPDATE recovery_codes SET used_at = ? WHERE user_id = ? AND code_hash = ? AND used_at IS NULL;";
+    let res = sqlx::query(query)
+        .bind(chrono::Utc::now())
+        .bind(user.id)
+        .bind(recovery_code_hash(user.id, code))
+        .execute(&data.db)
+        .instrument(sql_span(query))
+        .await
+        .map_err(|err: sqlx::Error| err.to_string())?;
+    if res.rows_affected() == 0 {
+        return Err(invalid_code());
+    }
+
+    data.observable.notify_event(Event::new(
+        "auth.recovery_code_used",
+        Some(user.id),
+        serde_json::json!({}),
+    ));
+
+    Ok(())
+}
+
+// Faktor kedua: kode TOTP atau recovery code sekali pakai
+async fn verify_second_factor(
+    data: &AppState,
+    user: &UserModel,
+    code: Option<&str>,
+    recovery_code: Option<&str>,
+) -> Result<(), String> {
+    if user.totp_enabled_at.is_none() {
+        return Err("Two-factor authentication is not enabled".to_string());
+    }
+
+    match (code, recovery_code) {
+        (Some(code), _) => check_totp(data, user, code).await,
+        (None, Some(recovery_code)) => use_recovery_code(data, user, recovery_code).await,
+        (None, None) => Err("code or recovery_code is required".to_string()),
+    }
+}
+
+// Secret baru disimpan terenkripsi, 2FA baru aktif setelah dikonfirmasi
+#[tracing::instrument]
+pub async fn enroll_mfa_service(user_id: i32) -> Result<(String, String), String> {
+    let app_state: Arc<AppState> = APP_STATE.get_or_init(init_app_state).await.clone();
+    let data: State<Arc<AppState>> = State(app_state);
+    let user = find_user(&data, user_id).await?;
+    if user.totp_enabled_at.is_some() {
+        return Err("Two-factor authentication is already enabled".to_string());
+    }
+
+    let secret = generate_secret();
+    let uri = otpauth_uri(&secret, &user.email)?;
+    let encrypted = encrypt(&secret)?;
+    let query = data
+        .query_builder
+        .update("users", user_id as u64, &[("totp_secret", &encrypted)]);
+    sqlx::query(&query)
+        .execute(&data.db)
+        .instrument(sql_span(&query))
+        .await
+        .map_err(|err: sqlx::Error| err.to_string())?;
+
+    Ok((secret, uri))
+}
+
+// Mengaktifkan 2FA dan mengembalikan recovery code, hanya ditampilkan sekali
+#[tracing::instrument(skip(body))]
+pub async fn confirm_mfa_service(
+    user_id: i32,
+    Json(body): Json<ConfirmMfaSchema>,
+) -> Result<Vec<String>, String> {
+    let app_state: Arc<AppState> = APP_STATE.get_or_init(init_app_state).await.clone();
+    let data: State<Arc<AppState>> = State(app_state);
+    let user = find_user(&data, user_id).await?;
+    if user.totp_enabled_at.is_some() {
+        return Err("Two-factor authentication is already enabled".to_string());
+    }
+    check_totp(&data, &user, &body.code).await?;
+
+    let codes: Vec<String> = (0..RECOVERY_CODE_COUNT)
+        .map(|_| generate_recovery_code())
+        .collect();
+
+    let mut tx = data.db.begin().await.map_err(|err| err.to_string())?;
+    let delete_query = "DELETE FROM recovery_codes WHERE user_id = ?;";
+    sqlx::query(delete_query)
+        .bind(user_id)
+        .execute(&mut *tx)
+        .instrument(sql_span(delete_query))
+        .await
+        .map_err(|err: sqlx::Error| err.to_string())?;
+
+    let insert_query = "INSERT INTO recovery_codes (user_id, code_hash) VALUES (?, ?);";
+    for code in &codes {
+        sqlx::query(insert_query)
+            .bind(user_id)
+            .bind(recovery_code_hash(user_id, code))
+            .execute(&mut *tx)
+            .instrument(sql_span(insert_query))
+            .await
+            .map_err(|err: sqlx::Error| err.to_string())?;
+    }
+
+    let enable_query = "UPDATE users SET totp_enabled_at = ? WHERE id = ?;";
+    sqlx::query(enable_query)
+        .bind(chrono::Utc::now())
+        .bind(user_id)
+        .execute(&mut *tx)
+        .instrument(sql_span(enable_query))
+        .await
+        .map_err(|err: sqlx::Error| err.to_string())?;
+
+    tx.commit().await.map_err(|err| err.to_string())?;
+
+    data.observable.notify_event(
+        Event::new("user.mfa_enabled", Some(user_id), serde_json::json!({}))
+            .with_changes(serde_json::json!({ "mfa": { "before": false, "after": true } })),
+    );
+
+    Ok(codes)
+}
+
+// Langkah kedua login setelah login_service mengembalikan mfa_token
+#[tracing::instrument(skip_all)]
+pub async fn mfa_challenge_service(
+    Json(body): Json<MfaChallengeSchema>,
+) -> Result<(String, i64), String> {
+    let claims = verify_mfa_token(&body.mfa_token)?;
+    let app_state: Arc<AppState> = APP_STATE.get_or_init(init_app_state).await.clone();
+    let data: State<Arc<AppState>> = State(app_state);
+    let user = find_user(&data, claims.sub).await?;
+    if user.token_version != claims.ver {
+        return Err("Invalid or expired MFA token".to_string());
+    }
+
+    // Kode 6 digit mudah ditebak tanpa batas percobaan, jadi punya penghitung sendiri
+    let mut keys = login_throttle_keys(Some(user.id), &user.username);
+    let account_key = std::mem::replace(&mut keys[0], ThrottleKey::mfa(user.id));
+    if lockout::retry_after(data.throttles.as_ref(), &keys)
+        .await?
+        .is_some()
+    {
+        return Err("Too many failed attempts, please try again later".to_string());
+    }
+    if let Err(err) = verify_second_factor(
+        &data,
+        &user,
+        body.code.as_deref(),
+        body.recovery_code.as_deref(),
+    )
+    .await
+    {
+        record_login_failure(
+            &data,
+            &keys,
+            Some(user.id),
+            &user.username,
+            "invalid_mfa_code",
+        )
+        .await?;
+        return Err(err);
+    }
+    // Login selesai, penghitung akun ikut dikosongkan
+    lockout::reset(data.throttles.as_ref(), &keys[0]).await?;
+    lockout::reset(data.throttles.as_ref(), &account_key).await?;
+
+    data.observable.notify_event(Event::new(
+        "auth.login",
+        Some(user.id),
+        serde_json::json!({ "mfa": true }),
+    ));
+
+    create_token(&user)
+}
+
+// Butuh password dan faktor kedua agar token yang dicuri saja tidak cukup
+#[tracing::instrument(skip(body))]
+pub async fn disable_mfa_service(
+    user_id: i32,
+    Json(body): Json<DisableMfaSchema>,
+) -> Result<(), String> {
+    let app_state: Arc<AppState> = APP_STATE.get_or_init(init_app_state).await.clone();
+    let data: State<Arc<AppState>> = State(app_state);
+    let user = find_user(&data, user_id).await?;
+    if !verify_password(&body.password, &user.password).await? {
+        return Err("Invalid password".to_string());
+    }
+    let key = ThrottleKey::mfa(user.id);
+    if lockout::retry_after(data.throttles.as_ref(), std::slice::from_ref(&key))
+        .await?
+        .is_some()
+    {
+        return Err("Too many failed attempts, please try again later".to_string());
+    }
+    if let Err(err) = verify_second_factor(
+        &data,
+        &user,
+        body.code.as_deref(),
+        body.recovery_code.as_deref(),
+    )
+    .await
+    {
+        lockout::record_failure(data.throttles.as_ref(), &key).await?;
+        return Err(err);
+    }
+    lockout::reset(data.throttles.as_ref(), &key).await?;
+
+    let mut tx = data.db.begin().await.map_err(|err| err.to_string())?;
+    let disable_query = "UPDATE users SET totp_secret = NULL, totp_enabled_at = NULL, totp_last_step = NULL WHERE id = ?;";
+    sqlx::query(disable_query)
+        .bind(user_id)
+        .execute(&mut *tx)
+        .instrument(sql_span(disable_query))
+        .await
+        .map_err(|err: sqlx::Error| err.to_string())?;
+
+    let delete_query = "DELETE FROM recovery_codes WHERE user_id = ?;";
+    sqlx::query(delete_query)
+        .bind(user_id)
+        .execute(&mut *tx)
+        .instrument(sql_span(delete_query))
+        .await
+        .map_err(|err: sqlx::Error| err.to_string())?;
+
+    tx.commit().await.map_err(|err| err.to_string())?;
+
+    data.observable.notify_event(
+        Event::new("user.mfa_disabled", Some(user_id), serde_json::json!({}))
+            .with_changes(serde_json::json!({ "mfa": { "before": true, "after": false } })),
+    );
+
+    Ok(())
+}
+
+// Status 2FA dan jumlah recovery code yang belum dipakai
+#[tracing::instrument]
+pub async fn mfa_status_service(user_id: i32) -> Result<(bool, usize), String> {
+    let app_state: Arc<AppState> = APP_STATE.get_or_init(init_app_state).await.clone();
+    let user = find_user(&app_state, user_id).await?;
+    let query = "SELECT * FROM recovery_codes WHERE user_id = ? AND used_at IS NULL;";
+    let codes = sqlx::query_as::<_, RecoveryCodeModel>(query)
+        .bind(user_id)
+        .fetch_all(&app_state.db)
+        .instrument(sql_span(query))
+        .await
+        .map_err(|err: sqlx::Error| err.to_string())?;
+
+    Ok((user.totp_enabled_at.is_some(), codes.len()))
+}
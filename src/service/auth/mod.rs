@@ -0,0 +1,3 @@
+pub mod auth_service;
+pub mod mfa_service;
+pub mod verification_service;
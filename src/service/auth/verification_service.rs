@@ -0,0 +1,358 @@
+use crate::{
+    model::{
+        model::{UserModel, VerificationCodeModel},
+        schema::{SendEmailVerificationSchema, VerifyPhoneSchema},
+    },
+    utils::{
+        adapter::sql_span,
+        audit::diff_fields,
+        mailer::{app_url, Mail},
+        observer::Event,
+        singleton::{init_app_state, AppState},
+        sms::Sms,
+        token::{generate_code, generate_token, hash_token},
+    },
+};
+use axum::{extract::State, Json};
+use chrono::{DateTime, Duration, Utc};
+use std::sync::Arc;
+use tokio::sync::OnceCell;
+use tracing::Instrument;
+
+static APP_STATE: OnceCell<Arc<AppState>> = OnceCell::const_new();
+
+const EMAIL: &str = "email";
+const PHONE: &str = "phone";
+
+// Kode telepon hangus setelah sekian kali salah tebak
+const MAX_CODE_ATTEMPTS: i32 = 5;
+
+pub enum SendOutcome {
+    Sent,
+    // Detik sampai kode boleh dikirim lagi
+    RateLimited(i64),
+}
+
+fn env_or(key: &str, default: i64) -> i64 {
+    std::env::var(key)
+        .ok()
+        .and_then(|v| v.parse().ok())
+        .unwrap_or(default)
+}
+
+fn invalid_code() -> String {
+    "Invalid or expired verification code".to_string()
+}
+
+async fn find_user(data: &AppState, field: &str, value: &str) -> Result<Option<UserModel>, String> {
+    let query = data.query_builder.select_by_field("users", field);
+    sqlx::query_as::<_, UserModel>(&query)
+        .bind(value)
+        .fetch_optional(&data.db)
+        .instrument(sql_span(&query))
+        .await
+        .map_err(|err: sqlx::Error| err.to_string())
+}
+
+// Batas pengiriman: jeda antar kirim dan jumlah maksimum per jam
+async fn send_limit(data: &AppState, user_id: i32, channel: &str) -> Result<Option<i64>, String> {
+    let cooldown = Duration::seconds(env_or("VERIFICATION_RESEND_COOLDOWN_SECONDS", 60));
+    let max_sends = env_or("VERIFICATION_MAX_SENDS_PER_HOUR", 5) as usize;
+    let now = Utc::now();
+    let window_start = now - Duration::hours(1);
+
+    let query = "SELECT * FROM verification_codes WHERE user_id = ? AND channel = ? AND created_at > ? ORDER BY created_at DESC;";
+    let sent = sqlx::query_as::<_, VerificationCodeModel>(query)
+        .bind(user_id)
+        .bind(channel)
+        .bind(window_start)
+        .fetch_all(&data.db)
+        .instrument(sql_span(query))
+        .await
+        .map_err(|err: sqlx::Error| err.to_string())?;
+
+    let sent_at: Vec<DateTime<Utc>> = sent.iter().filter_map(|code| code.created_at).collect();
+    let mut retry_at = None;
+    if let Some(latest) = sent_at.first() {
+        if *latest + cooldown > now {
+            retry_at = Some(*latest + cooldown);
+        }
+    }
+    if sent_at.len() >= max_sends {
+        if let Some(oldest) = sent_at.last() {
+            let window_end = *oldest + Duration::hours(1);
+            retry_at = Some(retry_at.map_or(window_end, |at: DateTime<Utc>| at.max(window_end)));
+        }
+    }
+
+    Ok(retry_at.map(|at| (at - now).num_seconds().max(1)))
+}
+
+// Kode lama untuk channel yang sama tidak berlaku lagi
+async fn store_code(
+    data: &AppState,
+    user_id: i32,
+    channel: &str,
+    target: &str,
+    code_hash: &str,
+    ttl: Duration,
+) -> Result<(), String> {
+    let now = Utc::now();
+    let revoke_query = "UPDATE verification_codes SET used_at = ? WHERE user_id = ? AND channel = ? AND used_at IS NULL;";
+    sqlx::query(revoke_query)
+        .bind(now)
+        .bind(user_id)
+        .bind(channel)
+        .execute(&data.db)
+        .instrument(sql_span(revoke_query))
+        .await
+        .map_err(|err: sqlx::Error| err.to_string())?;
+
+    let insert_query = "INSERT INTO verification_codes (user_id, channel, target, code_hash, expires_at) VALUES (?, ?, ?, ?, ?);";
+    sqlx::query(insert_query)
+        .bind(user_id)
+        .bind(channel)
+        .bind(target)
+        .bind(code_hash)
+        .bind(now + ttl)
+        .execute(&data.db)
+        .instrument(sql_span(insert_query))
+        .await
+        .map_err(|err: sqlx::Error| err.to_string())?;
+
+    Ok(())
+}
+
+// Kode telepon hanya 6 digit, jadi hash-nya diikat ke user_id
+fn phone_code_hash(user_id: i32, code: &str) -> String {
+    hash_token(&format!("{}:{}", user_id, code))
+}
+
+fn verified_event(topic: &str, user_id: i32, field: &str, at: DateTime<Utc>) -> Event {
+    let at = at.to_rfc3339();
+    Event::new(topic, Some(user_id), serde_json::json!({}))
+        .with_changes(diff_fields(&[(field, None)], &[(field, Some(at.as_str()))]))
+}
+
+// Selalu berhasil agar tidak membocorkan email mana yang terdaftar
+#[tracing::instrument(skip_all)]
+pub async fn send_email_verification_service(
+    Json(body): Json<SendEmailVerificationSchema>,
+) -> Result<(), String> {
+    let app_state: Arc<AppState> = APP_STATE.get_or_init(init_app_state).await.clone();
+    let data: State<Arc<AppState>> = State(app_state);
+
+    let user = match find_user(&data, "email", &body.email).await? {
+        Some(user) if user.email_verified_at.is_none() => user,
+        _ => return Ok(()),
+    };
+    if let Some(retry_after) = send_limit(&data, user.id, EMAIL).await? {
+        tracing::warn!(
+            user_id = user.id,
+            retry_after,
+            "Email verification rate limited"
+        );
+        return Ok(());
+    }
+
+    let token = generate_token();
+    let ttl = env_or("VERIFICATION_LINK_TTL_MINUTES", 1440);
+    store_code(
+        &data,
+        user.id,
+        EMAIL,
+        &user.email,
+        &hash_token(&token),
+        Duration::minutes(ttl),
+    )
+    .await?;
+
+    let mail = Mail {
+        to: user.email.clone(),
+        subject: "Verify your email address".to_string(),
+        body: format!(
+            "Hi {},\n\nOpen the link below to verify your email address. It expires in {} minutes.\n\n{}/api/v1/auth/verify/email?token={}\n",
+            user.username,
+            ttl,
+            app_url(),
+            token
+        ),
+    };
+    if let Err(err) = data.mailer.send(&mail) {
+        tracing::error!(
+            user_id = user.id,
+            "Failed to send verification email: {}",
+            err
+        );
+    }
+
+    Ok(())
+}
+
+#[tracing::instrument(skip_all)]
+pub async fn verify_email_service(token: String) -> Result<(), String> {
+    let app_state: Arc<AppState> = APP_STATE.get_or_init(init_app_state).await.clone();
+    let data: State<Arc<AppState>> = State(app_state);
+
+    let mut tx = data.db.begin().await.map_err(|err| err.to_string())?;
+    let select_query =
+        "SELECT * FROM verification_codes WHERE code_hash = ? AND channel = ? FOR UPDATE;";
+    let code = sqlx::query_as::<_, VerificationCodeModel>(select_query)
+        .bind(hash_token(&token))
+        .bind(EMAIL)
+        .fetch_optional(&mut *tx)
+        .instrument(sql_span(select_query))
+        .await
+        .map_err(|err: sqlx::Error| err.to_string())?
+        .ok_or_else(invalid_code)?;
+
+    let now = Utc::now();
+    if code.used_at.is_some() || code.expires_at <= now {
+        return Err(invalid_code());
+    }
+
+    // Email yang diverifikasi harus sama dengan email saat tautan dikirim
+    let update_query = "UPDATE users SET email_verified_at = ? WHERE id = ? AND email = ?;";
+    let res = sqlx::query(update_query)
+        .bind(now)
+        .bind(code.user_id)
+        .bind(&code.target)
+        .execute(&mut *tx)
+        .instrument(sql_span(update_query))
+        .await
+        .map_err(|err: sqlx::Error| err.to_string())?;
+    if res.rows_affected() == 0 {
+        return Err(invalid_code());
+    }
+
+    let use_query = "UPDATE verification_codes SET used_at = ? WHERE id = ?;";
+    sqlx::query(use_query)
+        .bind(now)
+        .bind(code.id)
+        .execute(&mut *tx)
+        .instrument(sql_span(use_query))
+        .await
+        .map_err(|err: sqlx::Error| err.to_string())?;
+
+    tx.commit().await.map_err(|err| err.to_string())?;
+
+    data.observable.notify_event(verified_event(
+        "user.email_verified",
+        code.user_id,
+        "email_verified_at",
+        now,
+    ));
+
+    Ok(())
+}
+
+#[tracing::instrument]
+pub async fn send_phone_verification_service(user_id: i32) -> Result<SendOutcome, String> {
+    let app_state: Arc<AppState> = APP_STATE.get_or_init(init_app_state).await.clone();
+    let data: State<Arc<AppState>> = State(app_state);
+
+    let user = find_user(&data, "id", &user_id.to_string())
+        .await?
+        .ok_or_else(|| format!("User with id {} does not exist", user_id))?;
+    if user.phone_verified_at.is_some() {
+        return Err("Phone number is already verified".to_string());
+    }
+    if let Some(retry_after) = send_limit(&data, user.id, PHONE).await? {
+        return Ok(SendOutcome::RateLimited(retry_after));
+    }
+
+    let code = generate_code();
+    let ttl = env_or("VERIFICATION_CODE_TTL_MINUTES", 10);
+    store_code(
+        &data,
+        user.id,
+        PHONE,
+        &user.phone,
+        &phone_code_hash(user.id, &code),
+        Duration::minutes(ttl),
+    )
+    .await?;
+
+    data.sms.send(&Sms {
+        to: user.phone.clone(),
+        body: format!(
+            "Your verification code is {}. It expires in {} minutes.",
+            code, ttl
+        ),
+    })?;
+
+    Ok(SendOutcome::Sent)
+}
+
+#[tracing::instrument(skip(body))]
+pub async fn verify_phone_service(
+    user_id: i32,
+    Json(body): Json<VerifyPhoneSchema>,
+) -> Result<(), String> {
+    let app_state: Arc<AppState> = APP_STATE.get_or_init(init_app_state).await.clone();
+    let data: State<Arc<AppState>> = State(app_state);
+
+    let mut tx = data.db.begin().await.map_err(|err| err.to_string())?;
+    let select_query = "SELECT * FROM verification_codes WHERE user_id = ? AND channel = ? AND used_at IS NULL ORDER BY created_at DESC LIMIT 1 FOR UPDATE;";
+    let code = sqlx::query_as::<_, VerificationCodeModel>(select_query)
+        .bind(user_id)
+        .bind(PHONE)
+        .fetch_optional(&mut *tx)
+        .instrument(sql_span(select_query))
+        .await
+        .map_err(|err: sqlx::Error| err.to_string())?
+        .ok_or_else(invalid_code)?;
+
+    let now = Utc::now();
+    if code.expires_at <= now {
+        return Err(invalid_code());
+    }
+
+    if code.code_hash != phone_code_hash(user_id, body.code.trim()) {
+        let attempt_query = "UPDATE verification_codes SET used_at = IF(attempts + 1 >= ?, ?, NULL), attempts = attempts + 1 WHERE id = ?;";
+        sqlx::query(attempt_query)
+            .bind(MAX_CODE_ATTEMPTS)
+            .bind(now)
+            .bind(code.id)
+            .execute(&mut *tx)
+            .instrument(sql_span(attempt_query))
+            .await
+            .map_err(|err: sqlx::Error| err.to_string())?;
+        tx.commit().await.map_err(|err| err.to_string())?;
+        return Err(invalid_code());
+    }
+
+    // Nomor telepon yang diverifikasi harus sama dengan nomor saat kode dikirim
+    let update_query = "UPDATE users SET phone_verified_at = ? WHERE id = ? AND phone = ?;";
+    let res = sqlx::query(update_query)
+        .bind(now)
+        .bind(user_id)
+        .bind(&code.target)
+        .execute(&mut *tx)
+        .instrument(sql_span(update_query))
+        .await
+        .map_err(|err: sqlx::Error| err.to_string())?;
+    if res.rows_affected() == 0 {
+        return Err(invalid_code());
+    }
+
+    let use_query = "UPDATE verification_codes SET used_at = ? WHERE id = ?;";
+    sqlx::query(use_query)
+        .bind(now)
+        .bind(code.id)
+        .execute(&mut *tx)
+        .instrument(sql_span(use_query))
+        .await
+        .map_err(|err: sqlx::Error| err.to_string())?;
+
+    tx.commit().await.map_err(|err| err.to_string())?;
+
+    data.observable.notify_event(verified_event(
+        "user.phone_verified",
+        user_id,
+        "phone_verified_at",
+        now,
+    ));
+
+    Ok(())
+}
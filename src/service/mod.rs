@@ -1,2 +1,8 @@
+pub mod api_key;
+pub mod audit;
+pub mod auth;
 pub mod healthcheck_service;
+pub mod openapi_service;
+pub mod resource;
+pub mod simulation;
 pub mod user;
\ No newline at end of file
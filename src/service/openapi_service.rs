@@ -0,0 +1,205 @@
+use serde_json::Value;
+use std::sync::LazyLock;
+use utoipa::OpenApi;
+
+use crate::{
+    controller::{
+        api_key_controller, audit_controller, auth_controller, healthcheck_controller,
+        metrics_controller, mfa_controller, openapi_controller, resource_controller,
+        user_controller, user_import_controller, user_v2_controller, verification_controller,
+        ws_controller,
+    },
+    model::{
+        model::UserModel,
+        resource::resources,
+        response::{
+            ApiDataResponse, ApiMessage, ErrorDetail, ErrorResponse, LoginData, MfaRequiredData,
+            Pagination, TokenData, UserDto, UserListResponse,
+        },
+        schema::{
+            ConfirmMfaSchema, CreateApiKeySchema, CreateUpdateUserSchema, DisableMfaSchema,
+            ForgotPasswordSchema, LoginSchema, MfaChallengeSchema, PatchUserSchema,
+            ResetPasswordSchema, SendEmailVerificationSchema, VerifyPhoneSchema,
+        },
+    },
+    service::{
+        resource::resource_openapi_service::{resource_openapi, security_schemes},
+        user::user_validator::{password_validation_chain, user_validation_chain},
+    },
+    utils::validation_chain::ValidationChain,
+};
+
+// Path relatif terhadap /api/v1 (juga dilayani di /api, deprecated)
+#[derive(OpenApi)]
+#[openapi(paths(
+    healthcheck_controller::healthcheck_controller,
+    user_controller::get_all_user_controller,
+    user_controller::create_user_controller,
+    user_controller::export_users_controller,
+    user_controller::search_users_controller,
+    user_import_controller::import_users_controller,
+    user_import_controller::get_import_job_controller,
+    user_controller::get_user_by_id_controller,
+    user_controller::update_user_controller,
+    user_controller::patch_user_controller,
+    user_controller::delete_user_by_id_controller,
+    user_controller::get_user_presence_controller,
+    user_controller::unlock_user_controller,
+    user_controller::restore_user_controller,
+    auth_controller::login_controller,
+    auth_controller::forgot_password_controller,
+    auth_controller::reset_password_controller,
+    verification_controller::verify_email_controller,
+    verification_controller::send_email_verification_controller,
+    verification_controller::verify_phone_controller,
+    verification_controller::send_phone_verification_controller,
+    mfa_controller::mfa_status_controller,
+    mfa_controller::enroll_mfa_controller,
+    mfa_controller::confirm_mfa_controller,
+    mfa_controller::mfa_challenge_controller,
+    mfa_controller::disable_mfa_controller,
+    api_key_controller::get_api_keys_controller,
+    api_key_controller::create_api_key_controller,
+    api_key_controller::revoke_api_key_controller,
+    ws_controller::ws_controller,
+    audit_controller::get_audit_logs_controller,
+    resource_controller::get_resource_schemas_controller,
+    resource_controller::resource_openapi_controller,
+))]
+struct V1Api;
+
+// Path relatif terhadap /api/v2
+#[derive(OpenApi)]
+#[openapi(paths(
+    user_v2_controller::get_users_v2_controller,
+    user_v2_controller::create_user_v2_controller,
+    user_v2_controller::get_user_v2_controller,
+    user_v2_controller::update_user_v2_controller,
+    user_v2_controller::patch_user_v2_controller,
+    user_v2_controller::delete_user_v2_controller,
+))]
+struct V2Api;
+
+#[derive(OpenApi)]
+#[openapi(
+    info(
+        title = "API-SIMU",
+        description = "REST API pengguna dengan autentikasi, audit log dan resource dari file schema. \
+            Route /api/v1 juga tersedia tanpa versi di /api, dengan header Deprecation dan Sunset.",
+        license(name = "MIT")
+    ),
+    paths(
+        metrics_controller::metrics_controller,
+        openapi_controller::openapi_controller,
+    ),
+    nest(
+        (path = "/api/v1", api = V1Api),
+        (path = "/api/v2", api = V2Api),
+    ),
+    components(schemas(
+        ApiMessage,
+        ApiDataResponse,
+        UserModel,
+        UserListResponse,
+        Pagination,
+        LoginData,
+        TokenData,
+        MfaRequiredData,
+        UserDto,
+        ErrorResponse,
+        ErrorDetail,
+        CreateUpdateUserSchema,
+        PatchUserSchema,
+        LoginSchema,
+        ForgotPasswordSchema,
+        ResetPasswordSchema,
+        SendEmailVerificationSchema,
+        VerifyPhoneSchema,
+        ConfirmMfaSchema,
+        MfaChallengeSchema,
+        DisableMfaSchema,
+        CreateApiKeySchema,
+    )),
+    tags(
+        (name = "user", description = "Pengguna"),
+        (name = "auth", description = "Login, reset password dan verifikasi"),
+        (name = "2fa", description = "Autentikasi dua faktor"),
+        (name = "api-key", description = "API key antar service"),
+        (name = "audit", description = "Audit log"),
+        (name = "resource", description = "Resource dari file schema"),
+        (name = "system", description = "Healthcheck, metrics dan WebSocket"),
+        (name = "v2", description = "Pengguna dengan kontrak response v2: data di \"data\", error di \"error\""),
+    )
+)]
+pub struct ApiDoc;
+
+// Body yang divalidasi ValidationChain, constraint-nya diambil dari rule yang sama
+fn validated_schemas() -> [(&'static str, ValidationChain); 3] {
+    [
+        ("CreateUpdateUserSchema", user_validation_chain()),
+        ("PatchUserSchema", user_validation_chain()),
+        ("ResetPasswordSchema", password_validation_chain()),
+    ]
+}
+
+fn apply_validation_constraints(document: &mut Value) {
+    for (component, validation_chain) in validated_schemas() {
+        let properties = document["components"]["schemas"][component]["properties"].as_object_mut();
+        for (field, property) in properties.into_iter().flatten() {
+            if let Some(property) = property.as_object_mut() {
+                property.extend(validation_chain.json_schema(field));
+            }
+        }
+    }
+}
+
+// Dokumen lengkap: handler yang dianotasi, constraint validasi dan resource dari file schema
+static OPENAPI: LazyLock<Value> = LazyLock::new(|| {
+    let mut document = serde_json::to_value(ApiDoc::openapi()).unwrap_or_default();
+    apply_validation_constraints(&mut document);
+
+    let (paths, schemas) = resource_openapi(resources());
+    if let Some(document_paths) = document["paths"].as_object_mut() {
+        document_paths.extend(paths);
+    }
+    if let Some(document_schemas) = document["components"]["schemas"].as_object_mut() {
+        document_schemas.extend(schemas);
+    }
+    document["components"]["securitySchemes"] = security_schemes();
+    document
+});
+
+pub fn openapi_document() -> &'static Value {
+    &OPENAPI
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn user_schema_has_validation_constraints() {
+        let properties =
+            &openapi_document()["components"]["schemas"]["CreateUpdateUserSchema"]["properties"];
+
+        assert_eq!(properties["username"]["minLength"], 3);
+        assert_eq!(properties["username"]["maxLength"], 20);
+        assert_eq!(properties["email"]["format"], "email");
+        assert_eq!(properties["phone"]["maxLength"], 15);
+        assert!(properties["phone"]["pattern"].is_string());
+        assert_eq!(properties["password"]["minLength"], 8);
+        assert!(properties["password"]["pattern"].is_string());
+    }
+
+    #[test]
+    fn spec_includes_resources() {
+        let paths = &openapi_document()["paths"];
+        for schema in resources() {
+            assert!(
+                paths.get(schema.path()).is_some(),
+                "{} missing",
+                schema.path()
+            );
+        }
+    }
+}
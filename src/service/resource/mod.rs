@@ -0,0 +1,3 @@
+pub mod resource_migration_service;
+pub mod resource_openapi_service;
+pub mod resource_service;
\ No newline at end of file
@@ -0,0 +1,137 @@
+use crate::{
+    model::resource::{resources, FieldType, ResourceField, ResourceSchema},
+    utils::{
+        adapter::sql_span,
+        factory::quote_identifier,
+        singleton::{init_app_state, AppState},
+    },
+};
+use std::{
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+use tokio::sync::OnceCell;
+use tracing::Instrument;
+
+static APP_STATE: OnceCell<Arc<AppState>> = OnceCell::const_new();
+
+fn column_type(field: &ResourceField) -> String {
+    match field.field_type {
+        FieldType::String => format!(
+            "VARCHAR({})",
+            field.rule_value("max_length").unwrap_or("255")
+        ),
+        FieldType::Text => "TEXT".to_string(),
+        FieldType::Integer => "BIGINT".to_string(),
+        FieldType::Number => "DOUBLE".to_string(),
+        FieldType::Boolean => "BOOLEAN".to_string(),
+        FieldType::Datetime => "DATETIME".to_string(),
+    }
+}
+
+pub fn create_table_sql(schema: &ResourceSchema) -> String {
+    let table = schema.table();
+    let mut columns = vec!["    id BIGINT PRIMARY KEY NOT NULL AUTO_INCREMENT".to_string()];
+    columns.extend(schema.fields.iter().map(|field| {
+        let null = if field.is_required() {
+            "NOT NULL"
+        } else {
+            "NULL"
+        };
+        format!(
+            "    {} {} {}",
+            quote_identifier(&field.name),
+            column_type(field),
+            null
+        )
+    }));
+    columns.push("    created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP".to_string());
+    columns.push(
+        "    updated_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP ON UPDATE CURRENT_TIMESTAMP"
+            .to_string(),
+    );
+    columns.extend(
+        schema
+            .fields
+            .iter()
+            .filter(|field| schema.is_unique(&field.name))
+            .map(|field| {
+                format!(
+                    "    UNIQUE KEY {} ({})",
+                    quote_identifier(&format!("uq_{}_{}", table, field.name)),
+                    quote_identifier(&field.name)
+                )
+            }),
+    );
+
+    format!(
+        "CREATE TABLE IF NOT EXISTS {} (\n{}\n);",
+        quote_identifier(table),
+        columns.join(",\n")
+    )
+}
+
+pub fn drop_table_sql(schema: &ResourceSchema) -> String {
+    format!("DROP TABLE IF EXISTS {};", quote_identifier(schema.table()))
+}
+
+// Membuat tabel yang belum ada saat startup. Perubahan pada tabel yang sudah ada
+// tetap lewat file migrasi (generate-migrations).
+pub async fn migrate_resources() -> Result<(), String> {
+    let app_state: Arc<AppState> = APP_STATE.get_or_init(init_app_state).await.clone();
+    for schema in resources() {
+        let query = create_table_sql(schema);
+        sqlx::query(&query)
+            .execute(&app_state.db)
+            .instrument(sql_span(&query))
+            .await
+            .map_err(|err: sqlx::Error| format!("{}: {}", schema.name, err))?;
+    }
+    Ok(())
+}
+
+// File migrasi sqlx untuk resource yang belum punya migrasi create table di dir
+pub fn write_migrations(dir: &Path) -> Result<Vec<PathBuf>, String> {
+    let existing: Vec<String> = std::fs::read_dir(dir)
+        .map_err(|err| format!("{}: {}", dir.display(), err))?
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.file_name().to_string_lossy().into_owned())
+        .collect();
+    let version = chrono::Utc::now().format("%Y%m%d%H%M%S").to_string();
+
+    let mut written = Vec::new();
+    for (i, schema) in resources().iter().enumerate() {
+        let name = format!("create_{}_table", schema.table());
+        let suffix = format!("_{}.up.sql", name);
+        if existing.iter().any(|file| file.ends_with(&suffix)) {
+            continue;
+        }
+
+        // Versi dibuat unik per file agar urutan sqlx tetap stabil
+        let prefix = format!("{}{:02}_{}", &version[..12], i, name);
+        let up = dir.join(format!("{}.up.sql", prefix));
+        let down = dir.join(format!("{}.down.sql", prefix));
+        let files = [
+            (
+                &up,
+                format!(
+                    "-- Add up migration script here\n{}\n",
+                    create_table_sql(schema)
+                ),
+            ),
+            (
+                &down,
+                format!(
+                    "-- Add down migration script here\n{}\n",
+                    drop_table_sql(schema)
+                ),
+            ),
+        ];
+        for (path, content) in files {
+            std::fs::write(path, content).map_err(|err| format!("{}: {}", path.display(), err))?;
+        }
+        written.push(up);
+        written.push(down);
+    }
+    Ok(written)
+}
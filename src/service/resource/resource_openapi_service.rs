@@ -0,0 +1,218 @@
+use crate::{
+    model::resource::{Access, FieldType, ResourceAction, ResourceField, ResourceSchema},
+    utils::validation_chain::rule_json_schema,
+};
+use serde_json::{json, Map, Value};
+use std::sync::Arc;
+
+fn schema_name(schema: &ResourceSchema) -> String {
+    schema
+        .name
+        .split('_')
+        .map(|part| {
+            let mut chars = part.chars();
+            chars
+                .next()
+                .map(|first| first.to_uppercase().chain(chars).collect::<String>())
+                .unwrap_or_default()
+        })
+        .collect()
+}
+
+fn field_schema(field: &ResourceField) -> Value {
+    let mut property = match field.field_type {
+        FieldType::String | FieldType::Text => json!({ "type": "string" }),
+        FieldType::Integer => json!({ "type": "integer", "format": "int64" }),
+        FieldType::Number => json!({ "type": "number", "format": "double" }),
+        FieldType::Boolean => json!({ "type": "boolean" }),
+        FieldType::Datetime => json!({ "type": "string", "format": "date-time" }),
+    };
+    let object = property.as_object_mut().unwrap();
+
+    for (rule, value) in field.rules() {
+        rule_json_schema(object, rule, value);
+    }
+    // OpenAPI 3.1: nullable ditulis sebagai union tipe
+    if !field.is_required() {
+        let kind = object["type"].clone();
+        object.insert("type".to_string(), json!([kind, "null"]));
+    }
+    if let Some(description) = &field.description {
+        object.insert("description".to_string(), json!(description));
+    }
+    property
+}
+
+fn component_schemas(schema: &ResourceSchema) -> [(String, Value); 2] {
+    let properties: Map<String, Value> = schema
+        .fields
+        .iter()
+        .map(|field| (field.name.clone(), field_schema(field)))
+        .collect();
+    let required: Vec<&str> = schema
+        .fields
+        .iter()
+        .filter(|field| field.is_required())
+        .map(|field| field.name.as_str())
+        .collect();
+
+    let input = json!({
+        "type": "object",
+        "properties": properties,
+        "required": required,
+        "additionalProperties": false,
+    });
+
+    let mut record_properties = properties.clone();
+    record_properties.insert(
+        "id".to_string(),
+        json!({ "type": "integer", "format": "int64" }),
+    );
+    for column in ["created_at", "updated_at"] {
+        record_properties.insert(
+            column.to_string(),
+            json!({ "type": ["string", "null"], "format": "date-time" }),
+        );
+    }
+    let mut record_required = vec!["id"];
+    record_required.extend(required);
+    let record = json!({
+        "type": "object",
+        "properties": record_properties,
+        "required": record_required,
+    });
+
+    let name = schema_name(schema);
+    [(name.clone(), record), (format!("{}Input", name), input)]
+}
+
+fn envelope(data: Value) -> Value {
+    json!({
+        "type": "object",
+        "properties": {
+            "status": { "type": "string" },
+            "message": { "type": "string" },
+            "data": data,
+        },
+    })
+}
+
+fn operation(
+    schema: &ResourceSchema,
+    action: ResourceAction,
+    summary: String,
+    data: Option<Value>,
+) -> Value {
+    let mut operation = json!({
+        "tags": [schema.name],
+        "summary": summary,
+        "responses": {},
+    });
+    let ok_status = if action == ResourceAction::Create {
+        "201"
+    } else {
+        "200"
+    };
+    operation["responses"][ok_status] = match data {
+        Some(data) => json!({
+            "description": "OK",
+            "content": { "application/json": { "schema": envelope(data) } },
+        }),
+        None => json!({ "description": "OK" }),
+    };
+    if matches!(action, ResourceAction::Create | ResourceAction::Update) {
+        operation["requestBody"] = json!({
+            "required": true,
+            "content": { "application/json": { "schema": {
+                "$ref": format!("#/components/schemas/{}Input", schema_name(schema))
+            } } },
+        });
+        operation["responses"]["400"] = json!({ "description": "Validation failed" });
+    }
+    if matches!(
+        action,
+        ResourceAction::Read | ResourceAction::Update | ResourceAction::Delete
+    ) {
+        operation["parameters"] = json!([{
+            "name": "id",
+            "in": "path",
+            "required": true,
+            "schema": { "type": "integer", "format": "int64" },
+        }]);
+        operation["responses"]["404"] = json!({ "description": "Not found" });
+    }
+    if action == ResourceAction::List {
+        operation["parameters"] = json!([
+            { "name": "page", "in": "query", "schema": { "type": "integer", "minimum": 1 } },
+            { "name": "limit", "in": "query", "schema": { "type": "integer", "minimum": 1, "maximum": 100 } },
+        ]);
+    }
+    match schema.permissions.access(action) {
+        Access::Public => {}
+        access => {
+            operation["security"] = json!([{ "bearerAuth": [] }, { "apiKeyAuth": [] }]);
+            operation["responses"]["401"] = json!({ "description": "Authentication required" });
+            if access == Access::Admin {
+                operation["responses"]["403"] =
+                    json!({ "description": "Admin privileges required" });
+            }
+        }
+    }
+    operation
+}
+
+// Entri paths dan components.schemas untuk setiap resource
+pub fn resource_openapi(
+    schemas: &[Arc<ResourceSchema>],
+) -> (Map<String, Value>, Map<String, Value>) {
+    let mut paths = Map::new();
+    let mut components = Map::new();
+
+    for schema in schemas {
+        let record = json!({ "$ref": format!("#/components/schemas/{}", schema_name(schema)) });
+        let name = &schema.name;
+        paths.insert(
+            schema.path(),
+            json!({
+                "get": operation(schema, ResourceAction::List, format!("List {}", name),
+                    Some(json!({ "type": "array", "items": record }))),
+                "post": operation(schema, ResourceAction::Create, format!("Create {}", name),
+                    Some(record.clone())),
+            }),
+        );
+        paths.insert(
+            format!("{}/{{id}}", schema.path()),
+            json!({
+                "get": operation(schema, ResourceAction::Read, format!("Get {}", name),
+                    Some(record.clone())),
+                "put": operation(schema, ResourceAction::Update, format!("Replace {}", name),
+                    Some(record.clone())),
+                "delete": operation(schema, ResourceAction::Delete, format!("Delete {}", name), None),
+            }),
+        );
+        components.extend(component_schemas(schema));
+    }
+    (paths, components)
+}
+
+// Dipakai juga oleh dokumen OpenAPI utama
+pub fn security_schemes() -> Value {
+    json!({
+        "bearerAuth": { "type": "http", "scheme": "bearer", "bearerFormat": "JWT" },
+        "apiKeyAuth": { "type": "apiKey", "in": "header", "name": "X-Api-Key" },
+    })
+}
+
+// Dokumen OpenAPI 3.1 yang hanya berisi resource dari file schema
+pub fn resource_openapi_document(schemas: &[Arc<ResourceSchema>]) -> Value {
+    let (paths, schemas) = resource_openapi(schemas);
+    json!({
+        "openapi": "3.1.0",
+        "info": { "title": "Generated resources", "version": env!("CARGO_PKG_VERSION") },
+        "paths": paths,
+        "components": {
+            "schemas": schemas,
+            "securitySchemes": security_schemes(),
+        },
+    })
+}
@@ -0,0 +1,467 @@
+use crate::{
+    model::resource::{FieldType, ResourceField, ResourceSchema},
+    utils::{
+        adapter::sql_span,
+        observer::Event,
+        singleton::{init_app_state, AppState},
+        validation_chain::ValidationChain,
+    },
+};
+use chrono::{DateTime, NaiveDateTime, Utc};
+use sqlx::{
+    mysql::{MySqlArguments, MySqlRow},
+    query::Query,
+    MySql, Row,
+};
+use std::sync::Arc;
+use tokio::sync::OnceCell;
+use tracing::Instrument;
+
+static APP_STATE: OnceCell<Arc<AppState>> = OnceCell::const_new();
+
+// Nilai field yang sudah dicek tipenya, siap di-bind ke query
+#[derive(Debug, Clone, PartialEq)]
+enum SqlValue {
+    Null,
+    Text(String),
+    Integer(i64),
+    Number(f64),
+    Boolean(bool),
+    Datetime(NaiveDateTime),
+}
+
+impl SqlValue {
+    // Representasi string untuk ValidationChain
+    fn as_text(&self) -> String {
+        match self {
+            SqlValue::Null => String::new(),
+            SqlValue::Text(value) => value.clone(),
+            SqlValue::Integer(value) => value.to_string(),
+            SqlValue::Number(value) => value.to_string(),
+            SqlValue::Boolean(value) => value.to_string(),
+            SqlValue::Datetime(value) => value.and_utc().to_rfc3339(),
+        }
+    }
+}
+
+fn bind_value<'q>(
+    query: Query<'q, MySql, MySqlArguments>,
+    value: &SqlValue,
+) -> Query<'q, MySql, MySqlArguments> {
+    match value {
+        SqlValue::Null => query.bind(None::<String>),
+        SqlValue::Text(value) => query.bind(value.clone()),
+        SqlValue::Integer(value) => query.bind(*value),
+        SqlValue::Number(value) => query.bind(*value),
+        SqlValue::Boolean(value) => query.bind(*value),
+        SqlValue::Datetime(value) => query.bind(*value),
+    }
+}
+
+fn parse_value(field: &ResourceField, value: &serde_json::Value) -> Result<SqlValue, String> {
+    if value.is_null() {
+        return Ok(SqlValue::Null);
+    }
+    let invalid = |expected: &str| format!("{} must be {}", field.name, expected);
+
+    match field.field_type {
+        FieldType::String | FieldType::Text => value
+            .as_str()
+            .map(|v| SqlValue::Text(v.to_string()))
+            .ok_or_else(|| invalid("a string")),
+        FieldType::Integer => value
+            .as_i64()
+            .map(SqlValue::Integer)
+            .ok_or_else(|| invalid("an integer")),
+        FieldType::Number => value
+            .as_f64()
+            .map(SqlValue::Number)
+            .ok_or_else(|| invalid("a number")),
+        FieldType::Boolean => value
+            .as_bool()
+            .map(SqlValue::Boolean)
+            .ok_or_else(|| invalid("a boolean")),
+        // Disimpan sebagai UTC
+        FieldType::Datetime => value
+            .as_str()
+            .and_then(|v| DateTime::parse_from_rfc3339(v).ok())
+            .map(|v| SqlValue::Datetime(v.naive_utc()))
+            .ok_or_else(|| invalid("an RFC 3339 datetime")),
+    }
+}
+
+// Body harus object berisi field dari schema; field yang tidak dikirim bernilai NULL
+fn parse_body<'s>(
+    schema: &'s ResourceSchema,
+    body: &serde_json::Value,
+) -> Result<Vec<(&'s ResourceField, SqlValue)>, String> {
+    let object = body
+        .as_object()
+        .ok_or_else(|| "Request body must be a JSON object".to_string())?;
+    if let Some(key) = object.keys().find(|key| schema.field(key).is_none()) {
+        return Err(format!("Unknown field '{}'", key));
+    }
+
+    let mut errors = Vec::new();
+    let mut values = Vec::new();
+    for field in &schema.fields {
+        match parse_value(
+            field,
+            object.get(&field.name).unwrap_or(&serde_json::Value::Null),
+        ) {
+            Ok(value) => values.push((field, value)),
+            Err(err) => errors.push(err),
+        }
+    }
+
+    if errors.is_empty() {
+        Ok(values)
+    } else {
+        Err(errors.join("; "))
+    }
+}
+
+// Rule dari schema dijalankan lewat ValidationChain. Cek unique hanya saat create,
+// saat update bentrokan ditangkap oleh UNIQUE index.
+async fn validate(
+    schema: &ResourceSchema,
+    values: &[(&ResourceField, SqlValue)],
+    creating: bool,
+) -> Result<(), String> {
+    let mut validation_chain = ValidationChain::new();
+    for (field, _) in values {
+        for (rule, value) in field.rules() {
+            if rule != "unique" || creating {
+                validation_chain.add_rule(&field.name, rule, value);
+            }
+        }
+        if creating && schema.is_unique(&field.name) && !field.has_rule("unique") {
+            validation_chain.add_rule(&field.name, "unique", None);
+        }
+    }
+
+    let texts: Vec<(&str, String)> = values
+        .iter()
+        // Field opsional yang kosong tidak divalidasi, kecuali "required"
+        .filter(|(field, value)| *value != SqlValue::Null || field.is_required())
+        .map(|(field, value)| (field.name.as_str(), value.as_text()))
+        .collect();
+    let fields_values: Vec<(&str, &str)> = texts
+        .iter()
+        .map(|(field, value)| (*field, value.as_str()))
+        .collect();
+
+    validation_chain
+        .validate_fields_recursive(&fields_values, schema.table())
+        .await
+}
+
+fn row_to_json(schema: &ResourceSchema, row: &MySqlRow) -> Result<serde_json::Value, String> {
+    let decode_err = |err: sqlx::Error| err.to_string();
+    let mut object = serde_json::Map::new();
+    object.insert(
+        "id".to_string(),
+        row.try_get::<i64, _>("id").map_err(decode_err)?.into(),
+    );
+
+    for field in &schema.fields {
+        let name = field.name.as_str();
+        let value = match field.field_type {
+            FieldType::String | FieldType::Text => {
+                serde_json::json!(row.try_get::<Option<String>, _>(name).map_err(decode_err)?)
+            }
+            FieldType::Integer => {
+                serde_json::json!(row.try_get::<Option<i64>, _>(name).map_err(decode_err)?)
+            }
+            FieldType::Number => {
+                serde_json::json!(row.try_get::<Option<f64>, _>(name).map_err(decode_err)?)
+            }
+            FieldType::Boolean => {
+                serde_json::json!(row.try_get::<Option<bool>, _>(name).map_err(decode_err)?)
+            }
+            FieldType::Datetime => serde_json::json!(row
+                .try_get::<Option<NaiveDateTime>, _>(name)
+                .map_err(decode_err)?
+                .map(|value| value.and_utc())),
+        };
+        object.insert(field.name.clone(), value);
+    }
+
+    for column in ["created_at", "updated_at"] {
+        let value = row
+            .try_get::<Option<DateTime<Utc>>, _>(column)
+            .map_err(decode_err)?;
+        object.insert(column.to_string(), serde_json::json!(value));
+    }
+    Ok(serde_json::Value::Object(object))
+}
+
+fn unique_violation(schema: &ResourceSchema) -> impl Fn(sqlx::Error) -> String + '_ {
+    move |err| match err {
+        sqlx::Error::Database(db_err) if db_err.is_unique_violation() => {
+            let columns: Vec<&str> = schema
+                .fields
+                .iter()
+                .filter(|field| schema.is_unique(&field.name))
+                .map(|field| field.name.as_str())
+                .collect();
+            format!("{} is already taken", columns.join(" or "))
+        }
+        err => err.to_string(),
+    }
+}
+
+fn resource_event(schema: &ResourceSchema, action: &str, id: i64) -> Event {
+    Event::new(
+        &format!("{}.{}", schema.name, action),
+        None,
+        serde_json::json!({ "resource": schema.name, "id": id }),
+    )
+}
+
+pub struct ResourcePage {
+    pub items: Vec<serde_json::Value>,
+    pub page: usize,
+    pub limit: usize,
+    pub has_more: bool,
+}
+
+#[tracing::instrument(skip(schema), fields(resource = %schema.name))]
+pub async fn list_resources_service(
+    schema: &ResourceSchema,
+    page: Option<usize>,
+    limit: Option<usize>,
+) -> Result<ResourcePage, String> {
+    let app_state: Arc<AppState> = APP_STATE.get_or_init(init_app_state).await.clone();
+    let page = page.unwrap_or(1).max(1);
+    let limit = limit.unwrap_or(20).clamp(1, 100);
+    let query = app_state.query_builder.select_where(
+        schema.table(),
+        &[],
+        false,
+        "id ASC",
+        limit + 1,
+        (page - 1) * limit,
+    );
+    let rows = sqlx::query(&query)
+        .fetch_all(&app_state.db)
+        .instrument(sql_span(&query))
+        .await
+        .map_err(|err: sqlx::Error| err.to_string())?;
+
+    let has_more = rows.len() > limit;
+    let items = rows
+        .iter()
+        .take(limit)
+        .map(|row| row_to_json(schema, row))
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(ResourcePage {
+        items,
+        page,
+        limit,
+        has_more,
+    })
+}
+
+#[tracing::instrument(skip(schema), fields(resource = %schema.name))]
+pub async fn get_resource_service(
+    schema: &ResourceSchema,
+    id: u64,
+) -> Result<Option<serde_json::Value>, String> {
+    let app_state: Arc<AppState> = APP_STATE.get_or_init(init_app_state).await.clone();
+    let query = app_state.query_builder.select_by_id(schema.table(), id);
+    let row = sqlx::query(&query)
+        .fetch_optional(&app_state.db)
+        .instrument(sql_span(&query))
+        .await
+        .map_err(|err: sqlx::Error| err.to_string())?;
+
+    row.map(|row| row_to_json(schema, &row)).transpose()
+}
+
+#[tracing::instrument(skip(schema, body), fields(resource = %schema.name))]
+pub async fn create_resource_service(
+    schema: &ResourceSchema,
+    body: &serde_json::Value,
+) -> Result<serde_json::Value, String> {
+    let values = parse_body(schema, body)?;
+    validate(schema, &values, true).await?;
+
+    let app_state: Arc<AppState> = APP_STATE.get_or_init(init_app_state).await.clone();
+    let fields: Vec<&str> = values
+        .iter()
+        .map(|(field, _)| field.name.as_str())
+        .collect();
+    let query = app_state.query_builder.insert_bind(schema.table(), &fields);
+    let mut insert_query = sqlx::query(&query);
+    for (_, value) in &values {
+        insert_query = bind_value(insert_query, value);
+    }
+    let res = insert_query
+        .execute(&app_state.db)
+        .instrument(sql_span(&query))
+        .await
+        .map_err(unique_violation(schema))?;
+
+    let id = res.last_insert_id();
+    app_state
+        .observable
+        .notify_event(resource_event(schema, "created", id as i64));
+
+    get_resource_service(schema, id)
+        .await?
+        .ok_or_else(|| format!("{} {} disappeared after insert", schema.name, id))
+}
+
+// Mengganti seluruh field (PUT), None bila id tidak ada
+#[tracing::instrument(skip(schema, body), fields(resource = %schema.name))]
+pub async fn update_resource_service(
+    schema: &ResourceSchema,
+    id: u64,
+    body: &serde_json::Value,
+) -> Result<Option<serde_json::Value>, String> {
+    let values = parse_body(schema, body)?;
+    if get_resource_service(schema, id).await?.is_none() {
+        return Ok(None);
+    }
+    validate(schema, &values, false).await?;
+
+    let app_state: Arc<AppState> = APP_STATE.get_or_init(init_app_state).await.clone();
+    let fields: Vec<&str> = values
+        .iter()
+        .map(|(field, _)| field.name.as_str())
+        .collect();
+    let query = app_state
+        .query_builder
+        .update_bind(schema.table(), id, &fields);
+    let mut update_query = sqlx::query(&query);
+    for (_, value) in &values {
+        update_query = bind_value(update_query, value);
+    }
+    update_query
+        .execute(&app_state.db)
+        .instrument(sql_span(&query))
+        .await
+        .map_err(unique_violation(schema))?;
+
+    app_state
+        .observable
+        .notify_event(resource_event(schema, "updated", id as i64));
+
+    get_resource_service(schema, id).await
+}
+
+// false bila id tidak ada
+#[tracing::instrument(skip(schema), fields(resource = %schema.name))]
+pub async fn delete_resource_service(schema: &ResourceSchema, id: u64) -> Result<bool, String> {
+    let app_state: Arc<AppState> = APP_STATE.get_or_init(init_app_state).await.clone();
+    let query = app_state.query_builder.delete(schema.table(), id);
+    let res = sqlx::query(&query)
+        .execute(&app_state.db)
+        .instrument(sql_span(&query))
+        .await
+        .map_err(|err: sqlx::Error| err.to_string())?;
+
+    if res.rows_affected() == 0 {
+        return Ok(false);
+    }
+    app_state
+        .observable
+        .notify_event(resource_event(schema, "deleted", id as i64));
+    Ok(true)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn schema() -> ResourceSchema {
+        ResourceSchema::parse(
+            "order.yaml",
+            "name: order\nfields:\n  - {name: key, type: string, rules: [required]}\n  - {name: amount, type: number}\n  - {name: count, type: integer}\n  - {name: paid, type: boolean}\n  - {name: due, type: datetime}\n",
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn parse_value_checks_types() {
+        let schema = schema();
+        let field = |name| schema.field(name).unwrap();
+
+        assert_eq!(
+            parse_value(field("key"), &serde_json::json!("it's")),
+            Ok(SqlValue::Text("it's".to_string()))
+        );
+        assert_eq!(
+            parse_value(field("count"), &serde_json::json!(3)),
+            Ok(SqlValue::Integer(3))
+        );
+        assert_eq!(
+            parse_value(field("amount"), &serde_json::json!(2)),
+            Ok(SqlValue::Number(2.0))
+        );
+        assert_eq!(
+            parse_value(field("paid"), &serde_json::Value::Null),
+            Ok(SqlValue::Null)
+        );
+        // Zona waktu dikonversi ke UTC
+        assert_eq!(
+            parse_value(
+                field("due"),
+                &serde_json::json!("2026-01-02T10:00:00+07:00")
+            ),
+            Ok(SqlValue::Datetime(
+                DateTime::parse_from_rfc3339("2026-01-02T03:00:00Z")
+                    .unwrap()
+                    .naive_utc()
+            ))
+        );
+
+        assert_eq!(
+            parse_value(field("count"), &serde_json::json!(1.5)),
+            Err("count must be an integer".to_string())
+        );
+        assert_eq!(
+            parse_value(field("paid"), &serde_json::json!("yes")),
+            Err("paid must be a boolean".to_string())
+        );
+        assert_eq!(
+            parse_value(field("due"), &serde_json::json!("besok")),
+            Err("due must be an RFC 3339 datetime".to_string())
+        );
+    }
+
+    #[test]
+    fn parse_body_fills_missing_fields_and_collects_errors() {
+        let schema = schema();
+
+        let values = parse_body(&schema, &serde_json::json!({ "key": "a", "count": 1 })).unwrap();
+        let values: Vec<_> = values
+            .into_iter()
+            .map(|(field, value)| (field.name.as_str(), value))
+            .collect();
+        assert_eq!(
+            values,
+            vec![
+                ("key", SqlValue::Text("a".to_string())),
+                ("amount", SqlValue::Null),
+                ("count", SqlValue::Integer(1)),
+                ("paid", SqlValue::Null),
+                ("due", SqlValue::Null),
+            ]
+        );
+
+        assert_eq!(
+            parse_body(&schema, &serde_json::json!({ "key": 1, "paid": 0 })).unwrap_err(),
+            "key must be a string; paid must be a boolean"
+        );
+        assert_eq!(
+            parse_body(&schema, &serde_json::json!({ "nope": 1 })).unwrap_err(),
+            "Unknown field 'nope'"
+        );
+        assert_eq!(
+            parse_body(&schema, &serde_json::json!([1])).unwrap_err(),
+            "Request body must be a JSON object"
+        );
+    }
+}
@@ -0,0 +1 @@
+pub mod simulation_service;
\ No newline at end of file
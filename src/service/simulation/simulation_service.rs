@@ -0,0 +1,441 @@
+use crate::{
+    model::{model::UserModel, schema::CreateUpdateUserSchema},
+    service::user::user_repository::{
+        Insert, PageQuery, SortValue, Update, UserRepository, UserSearch, MIN_PHONE_DIGITS,
+    },
+    utils::{
+        factory::new_mysql_query_builder,
+        hashing::hash_password,
+        idempotency::MemoryIdempotencyStore,
+        lockout::MemoryThrottleStore,
+        simulation::{
+            enable_simulation, reset_simulation_calls, simulation_config, SimulationConfig,
+        },
+        singleton::{install_app_state, new_app_state, AppState, Stores},
+    },
+};
+use axum::async_trait;
+use chrono::{DateTime, Duration, TimeZone, Utc};
+use rand::{rngs::StdRng, Rng, SeedableRng};
+use sqlx::mysql::MySqlPoolOptions;
+use std::{
+    collections::BTreeMap,
+    sync::{Arc, Mutex},
+};
+use tokio::sync::OnceCell;
+
+// Password semua pengguna hasil seed, termasuk "admin"
+pub const SEED_PASSWORD: &str = "Password123!";
+
+const FIRST_NAMES: [&str; 16] = [
+    "budi", "siti", "agus", "dewi", "andi", "rina", "joko", "ayu", "eko", "putri", "hendra",
+    "lestari", "rudi", "maya", "fajar", "indah",
+];
+const LAST_NAMES: [&str; 8] = [
+    "santoso", "wijaya", "pratama", "saputra", "hidayat", "kusuma", "nugroho", "halim",
+];
+
+// Isi tabel users selama mode --simulate
+struct MemoryStore {
+    users: BTreeMap<i32, UserModel>,
+    next_id: i32,
+}
+
+// Hash dihitung sekali saja, hashing password sengaja lambat
+static SEED_PASSWORD_HASH: OnceCell<String> = OnceCell::const_new();
+static STORE: Mutex<Option<MemoryStore>> = Mutex::new(None);
+
+// Data yang sama untuk seed yang sama, termasuk waktu pembuatannya
+fn seed_store(config: &SimulationConfig, password_hash: &str) -> MemoryStore {
+    let mut rng = StdRng::seed_from_u64(config.seed);
+    let base = Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap();
+    let mut users = BTreeMap::new();
+
+    for id in 1..=config.users.max(1) as i32 {
+        let first = FIRST_NAMES[rng.gen_range(0..FIRST_NAMES.len())];
+        let last = LAST_NAMES[rng.gen_range(0..LAST_NAMES.len())];
+        let created_at =
+            base + Duration::days(id as i64) + Duration::minutes(rng.gen_range(0..1440));
+        let (username, email, role) = if id == 1 {
+            (
+                "admin".to_string(),
+                "admin@example.com".to_string(),
+                "admin",
+            )
+        } else {
+            (
+                format!("{}{}", first, id),
+                format!("{}.{}{}@example.com", first, last, id),
+                "user",
+            )
+        };
+        let verified = rng.gen_bool(0.7);
+
+        users.insert(
+            id,
+            UserModel {
+                id,
+                username,
+                email,
+                phone: format!("+628{:09}", rng.gen_range(0..1_000_000_000u64)),
+                password: password_hash.to_string(),
+                role: role.to_string(),
+                token_version: 0,
+                version: 1,
+                email_verified_at: verified.then_some(created_at),
+                phone_verified_at: None,
+                totp_secret: None,
+                totp_enabled_at: None,
+                totp_last_step: None,
+                deleted_at: None,
+                created_at: Some(created_at),
+                updated_at: Some(created_at),
+            },
+        );
+    }
+
+    MemoryStore {
+        next_id: users.len() as i32 + 1,
+        users,
+    }
+}
+
+async fn with_store<T>(f: impl FnOnce(&mut MemoryStore) -> T) -> Result<T, String> {
+    let password_hash = SEED_PASSWORD_HASH
+        .get_or_try_init(|| hash_password(SEED_PASSWORD))
+        .await?;
+    let mut store = STORE.lock().unwrap();
+    let store = store.get_or_insert_with(|| seed_store(simulation_config(), password_hash));
+    Ok(f(store))
+}
+
+fn taken_error(
+    store: &MemoryStore,
+    username: &str,
+    email: &str,
+    phone: &str,
+    except: Option<i32>,
+) -> Option<String> {
+    let live = store
+        .users
+        .values()
+        .filter(|user| user.deleted_at.is_none() && Some(user.id) != except);
+    for user in live {
+        if user.username == username {
+            return Some("username is already taken".to_string());
+        }
+        if user.email == email {
+            return Some("email is already taken".to_string());
+        }
+        if user.phone == phone {
+            return Some("phone is already taken".to_string());
+        }
+    }
+    None
+}
+
+fn insert_user(store: &mut MemoryStore, body: &CreateUpdateUserSchema, password: &str) -> Insert {
+    if let Some(err) = taken_error(store, &body.username, &body.email, &body.phone, None) {
+        return Insert::Taken(err);
+    }
+    let id = store.next_id;
+    store.next_id += 1;
+    let now = Utc::now();
+    store.users.insert(
+        id,
+        UserModel {
+            id,
+            username: body.username.clone(),
+            email: body.email.clone(),
+            phone: body.phone.clone(),
+            password: password.to_string(),
+            role: "user".to_string(),
+            token_version: 0,
+            version: 1,
+            email_verified_at: None,
+            phone_verified_at: None,
+            totp_secret: None,
+            totp_enabled_at: None,
+            totp_last_step: None,
+            deleted_at: None,
+            created_at: Some(now),
+            updated_at: Some(now),
+        },
+    );
+    Insert::Created(id)
+}
+
+// Kembali ke data seed dan mengosongkan hitungan pemanggilan skenario
+pub async fn reset_simulation_service() -> Result<usize, String> {
+    let password_hash = SEED_PASSWORD_HASH
+        .get_or_try_init(|| hash_password(SEED_PASSWORD))
+        .await?;
+    let store = seed_store(simulation_config(), password_hash);
+    let count = store.users.len();
+    *STORE.lock().unwrap() = Some(store);
+    reset_simulation_calls();
+    Ok(count)
+}
+
+// Pengganti MySqlUserRepository, batasan unique ditiru dengan taken_error
+pub struct MemoryUserRepository;
+
+#[async_trait]
+impl UserRepository for MemoryUserRepository {
+    async fn all(&self, include_deleted: bool) -> Result<Vec<UserModel>, String> {
+        with_store(|store| {
+            store
+                .users
+                .values()
+                .filter(|user| include_deleted || user.deleted_at.is_none())
+                .cloned()
+                .collect()
+        })
+        .await
+    }
+
+    async fn page(&self, page: &PageQuery<'_>) -> Result<Vec<UserModel>, String> {
+        let mut users = self.all(page.include_deleted).await?;
+        let key = |user: &UserModel| (SortValue::of(user, page.sort.column), user.id as i64);
+        users.sort_by(|a, b| {
+            let order = key(a)
+                .partial_cmp(&key(b))
+                .unwrap_or(std::cmp::Ordering::Equal);
+            if page.sort.descending {
+                order.reverse()
+            } else {
+                order
+            }
+        });
+
+        let users = users.into_iter().filter(|user| match &page.after {
+            Some(after) if page.sort.descending => key(user) < *after,
+            Some(after) => key(user) > *after,
+            None => true,
+        });
+        let offset = if page.after.is_some() { 0 } else { page.offset };
+        Ok(users.skip(offset).take(page.limit).collect())
+    }
+
+    async fn find(&self, id: i32, include_deleted: bool) -> Result<Option<UserModel>, String> {
+        with_store(|store| {
+            store
+                .users
+                .get(&id)
+                .filter(|user| include_deleted || user.deleted_at.is_none())
+                .cloned()
+        })
+        .await
+    }
+
+    async fn find_by_field(&self, field: &str, value: &str) -> Result<Option<UserModel>, String> {
+        with_store(|store| {
+            store
+                .users
+                .values()
+                .filter(|user| user.deleted_at.is_none())
+                .find(|user| match field {
+                    "username" => user.username == value,
+                    "email" => user.email == value,
+                    "phone" => user.phone == value,
+                    _ => false,
+                })
+                .cloned()
+        })
+        .await
+    }
+
+    // Sama dengan LIKE di backend tanpa FULLTEXT: kata kunci utuh, trigram atau angka
+    async fn search(&self, search: &UserSearch) -> Result<Vec<UserModel>, String> {
+        let q = search.q.to_lowercase();
+        let needles: Vec<&str> = std::iter::once(q.as_str())
+            .chain(search.trigrams.iter().map(String::as_str))
+            .collect();
+        let digits =
+            (search.phone_digits.len() >= MIN_PHONE_DIGITS).then_some(&search.phone_digits);
+
+        with_store(|store| {
+            store
+                .users
+                .values()
+                .filter(|user| user.deleted_at.is_none())
+                .filter(|user| {
+                    let username = user.username.to_lowercase();
+                    let email = user.email.to_lowercase();
+                    let phone: String = user.phone.chars().filter(char::is_ascii_digit).collect();
+                    needles
+                        .iter()
+                        .any(|needle| username.contains(needle) || email.contains(needle))
+                        || digits.is_some_and(|digits| phone.contains(digits.as_str()))
+                })
+                .take(search.limit)
+                .cloned()
+                .collect()
+        })
+        .await
+    }
+
+    async fn insert(
+        &self,
+        body: &CreateUpdateUserSchema,
+        password: &str,
+    ) -> Result<Insert, String> {
+        with_store(|store| insert_user(store, body, password)).await
+    }
+
+    async fn insert_all(
+        &self,
+        users: &[(&CreateUpdateUserSchema, &str)],
+    ) -> Result<Vec<i32>, (Option<usize>, String)> {
+        with_store(|store| {
+            let mut ids = Vec::with_capacity(users.len());
+            for (index, (body, password)) in users.iter().enumerate() {
+                match insert_user(store, body, password) {
+                    Insert::Created(id) => ids.push(id),
+                    Insert::Taken(err) => {
+                        // Seperti rollback transaksi
+                        for id in &ids {
+                            store.users.remove(id);
+                        }
+                        return Err((Some(index), err));
+                    }
+                }
+            }
+            Ok(ids)
+        })
+        .await
+        .map_err(|err| (None, err))?
+    }
+
+    async fn update_if_version(
+        &self,
+        id: i32,
+        version: i32,
+        body: &CreateUpdateUserSchema,
+        password: &str,
+    ) -> Result<Update, String> {
+        with_store(|store| {
+            if let Some(err) =
+                taken_error(store, &body.username, &body.email, &body.phone, Some(id))
+            {
+                return Update::Taken(err);
+            }
+            let Some(user) = store
+                .users
+                .get_mut(&id)
+                .filter(|user| user.deleted_at.is_none() && user.version == version)
+            else {
+                return Update::Stale;
+            };
+
+            user.username = body.username.clone();
+            user.email = body.email.clone();
+            user.phone = body.phone.clone();
+            user.password = password.to_string();
+            user.version += 1;
+            user.updated_at = Some(Utc::now());
+            Update::Done
+        })
+        .await
+    }
+
+    async fn set_password(&self, id: i32, password: &str) -> Result<(), String> {
+        with_store(|store| {
+            if let Some(user) = store.users.get_mut(&id) {
+                user.password = password.to_string();
+            }
+        })
+        .await
+    }
+
+    async fn reset_verification(&self, id: i32, column: &str) -> Result<(), String> {
+        with_store(|store| {
+            if let Some(user) = store.users.get_mut(&id) {
+                match column {
+                    "email_verified_at" => user.email_verified_at = None,
+                    "phone_verified_at" => user.phone_verified_at = None,
+                    _ => {}
+                }
+            }
+        })
+        .await
+    }
+
+    async fn soft_delete_if_version(&self, id: i32, version: i32) -> Result<bool, String> {
+        with_store(|store| {
+            let Some(user) = store
+                .users
+                .get_mut(&id)
+                .filter(|user| user.deleted_at.is_none() && user.version == version)
+            else {
+                return false;
+            };
+
+            user.version += 1;
+            user.deleted_at = Some(Utc::now());
+            true
+        })
+        .await
+    }
+
+    async fn restore(&self, id: i32) -> Result<(), String> {
+        with_store(|store| {
+            let Some(user) = store.users.get(&id) else {
+                return Ok(());
+            };
+            if taken_error(store, &user.username, &user.email, &user.phone, Some(id)).is_some() {
+                return Err(
+                    "Cannot restore user, username, email or phone is already taken".to_string(),
+                );
+            }
+            if let Some(user) = store.users.get_mut(&id) {
+                user.deleted_at = None;
+            }
+            Ok(())
+        })
+        .await?
+    }
+
+    async fn deleted_before(&self, cutoff: DateTime<Utc>) -> Result<Vec<UserModel>, String> {
+        with_store(|store| {
+            store
+                .users
+                .values()
+                .filter(|user| user.deleted_at.is_some_and(|at| at < cutoff))
+                .cloned()
+                .collect()
+        })
+        .await
+    }
+
+    async fn delete(&self, id: i32) -> Result<(), String> {
+        with_store(|store| {
+            store.users.remove(&id);
+        })
+        .await
+    }
+}
+
+// Pool tidak pernah tersambung: semua route yang dipasang di mode --simulate memakai
+// penyimpanan di memori, query yang lolos ke pool akan gagal, bukan menyentuh database
+fn simulation_app_state() -> AppState {
+    let db = MySqlPoolOptions::new()
+        .max_connections(1)
+        .acquire_timeout(std::time::Duration::from_secs(1))
+        .connect_lazy("mysql://simulation@127.0.0.1:1/simulation")
+        .expect("valid simulation database url");
+    let stores = Stores {
+        users: Arc::new(MemoryUserRepository),
+        throttles: Arc::new(MemoryThrottleStore::default()),
+        idempotency: Arc::new(MemoryIdempotencyStore::default()),
+    };
+
+    new_app_state(db, new_mysql_query_builder(), stores)
+}
+
+// Dipanggil sebelum router mode --simulate dibuat, controller dan service yang sama
+// dengan mode biasa lalu membaca data dari memori
+pub fn init_simulation_app_state() -> Arc<AppState> {
+    enable_simulation();
+    install_app_state(simulation_app_state)
+}
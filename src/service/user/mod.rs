@@ -1,2 +1,6 @@
+pub mod user_export_service;
+pub mod user_import_service;
+pub mod user_repository;
+pub mod user_search_service;
 pub mod user_service;
 pub mod user_validator;
\ No newline at end of file
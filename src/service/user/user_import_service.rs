@@ -0,0 +1,576 @@
+use crate::{
+    model::schema::{CreateUpdateUserSchema, ImportUsersQuery},
+    service::user::{
+        user_repository::Insert, user_service::user_created_event,
+        user_validator::create_validation,
+    },
+    utils::{
+        context::RequestContext,
+        hashing::hash_password,
+        observer::Event,
+        singleton::{init_app_state, AppState},
+        token::generate_token,
+    },
+};
+use chrono::{DateTime, Utc};
+use futures_util::StreamExt;
+use serde::Serialize;
+use std::{
+    collections::HashMap,
+    sync::{Arc, LazyLock, Mutex},
+};
+use tokio::sync::OnceCell;
+
+static APP_STATE: OnceCell<Arc<AppState>> = OnceCell::const_new();
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ImportFormat {
+    Csv,
+    Ndjson,
+}
+
+impl ImportFormat {
+    // ?format= lebih diutamakan daripada Content-Type
+    pub fn detect(format: Option<&str>, content_type: Option<&str>) -> Result<Self, String> {
+        let value = format.or(content_type).unwrap_or_default().to_lowercase();
+        if value.contains("csv") {
+            Ok(ImportFormat::Csv)
+        } else if value.contains("ndjson") || value.contains("jsonl") {
+            Ok(ImportFormat::Ndjson)
+        } else {
+            Err("Unsupported import format, use text/csv or application/x-ndjson".to_string())
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ImportMode {
+    // Semua baris dibuat dalam satu transaksi, atau tidak sama sekali
+    Atomic,
+    // Baris yang valid tetap dibuat walau ada baris lain yang gagal
+    BestEffort,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct ImportOptions {
+    pub format: ImportFormat,
+    pub mode: ImportMode,
+    pub dry_run: bool,
+}
+
+impl ImportOptions {
+    pub fn from_query(
+        query: &ImportUsersQuery,
+        content_type: Option<&str>,
+    ) -> Result<Self, String> {
+        let mode = match query.mode.as_deref() {
+            None | Some("atomic") => ImportMode::Atomic,
+            Some("best_effort") => ImportMode::BestEffort,
+            Some(mode) => {
+                return Err(format!(
+                    "Unknown import mode '{}', use atomic or best_effort",
+                    mode
+                ))
+            }
+        };
+
+        Ok(ImportOptions {
+            format: ImportFormat::detect(query.format.as_deref(), content_type)?,
+            mode,
+            dry_run: query.dry_run.unwrap_or(false),
+        })
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RowStatus {
+    Created,
+    // Lolos validasi pada dry run
+    Valid,
+    Invalid,
+    // Valid, tetapi tidak dibuat karena mode atomic dan ada baris lain yang gagal
+    Skipped,
+    Failed,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct RowResult {
+    // Nomor baris data, dimulai dari 1 (tanpa header CSV)
+    pub row: usize,
+    pub status: RowStatus,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub username: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub id: Option<i32>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub errors: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ImportReport {
+    pub format: ImportFormat,
+    pub mode: ImportMode,
+    pub dry_run: bool,
+    pub total: usize,
+    pub created: usize,
+    pub valid: usize,
+    pub invalid: usize,
+    pub skipped: usize,
+    pub failed: usize,
+    pub rows: Vec<RowResult>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum JobStatus {
+    Running,
+    Completed,
+    Failed,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ImportJob {
+    pub id: String,
+    pub status: JobStatus,
+    pub total_rows: usize,
+    pub processed_rows: usize,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub report: Option<ImportReport>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    pub created_at: DateTime<Utc>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub finished_at: Option<DateTime<Utc>>,
+}
+
+// Status job hanya disimpan di memori instance yang menjalankannya
+static IMPORT_JOBS: LazyLock<Mutex<HashMap<String, ImportJob>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
+fn env_or(key: &str, default: usize) -> usize {
+    std::env::var(key)
+        .ok()
+        .and_then(|v| v.parse().ok())
+        .unwrap_or(default)
+}
+
+// File dengan baris lebih banyak dari ini dijalankan sebagai background job
+pub fn sync_max_rows() -> usize {
+    env_or("IMPORT_SYNC_MAX_ROWS", 100)
+}
+
+type ParsedRow = (usize, Result<CreateUpdateUserSchema, String>);
+
+pub fn parse_rows(format: ImportFormat, body: &str) -> Result<Vec<ParsedRow>, String> {
+    match format {
+        ImportFormat::Csv => {
+            let mut reader = csv::ReaderBuilder::new()
+                .trim(csv::Trim::All)
+                .from_reader(body.as_bytes());
+            let headers = reader.headers().map_err(|err| err.to_string())?.clone();
+            if let Some(missing) = ["username", "email", "phone", "password"]
+                .iter()
+                .find(|column| !headers.iter().any(|header| header == **column))
+            {
+                return Err(format!("CSV header is missing the '{}' column", missing));
+            }
+
+            Ok(reader
+                .deserialize::<CreateUpdateUserSchema>()
+                .enumerate()
+                .map(|(index, row)| (index + 1, row.map_err(|err| err.to_string())))
+                .collect())
+        }
+        ImportFormat::Ndjson => Ok(body
+            .lines()
+            .filter(|line| !line.trim().is_empty())
+            .enumerate()
+            .map(|(index, line)| {
+                let row = serde_json::from_str::<CreateUpdateUserSchema>(line)
+                    .map_err(|err| err.to_string());
+                (index + 1, row)
+            })
+            .collect()),
+    }
+}
+
+// Baris yang memakai username, email atau phone yang sama dengan baris sebelumnya
+fn duplicate_errors(rows: &[ParsedRow]) -> HashMap<usize, Vec<String>> {
+    let mut seen: HashMap<(&str, String), usize> = HashMap::new();
+    let mut errors: HashMap<usize, Vec<String>> = HashMap::new();
+    for (row, parsed) in rows {
+        let Ok(body) = parsed else { continue };
+        for (field, value) in [
+            ("username", &body.username),
+            ("email", &body.email),
+            ("phone", &body.phone),
+        ] {
+            match seen.get(&(field, value.to_lowercase())) {
+                Some(first) => errors
+                    .entry(*row)
+                    .or_default()
+                    .push(format!("{} duplicates row {}", field, first)),
+                None => {
+                    seen.insert((field, value.to_lowercase()), *row);
+                }
+            }
+        }
+    }
+    errors
+}
+
+fn set_progress(job_id: Option<&str>, processed: usize) {
+    if let Some(job_id) = job_id {
+        if let Some(job) = IMPORT_JOBS.lock().unwrap().get_mut(job_id) {
+            job.processed_rows = processed;
+        }
+    }
+}
+
+struct Candidate {
+    row: usize,
+    body: CreateUpdateUserSchema,
+    password: String,
+}
+
+fn summarize(rows: Vec<RowResult>, options: ImportOptions) -> ImportReport {
+    let count = |status: RowStatus| rows.iter().filter(|row| row.status == status).count();
+    ImportReport {
+        format: options.format,
+        mode: options.mode,
+        dry_run: options.dry_run,
+        total: rows.len(),
+        created: count(RowStatus::Created),
+        valid: count(RowStatus::Valid),
+        invalid: count(RowStatus::Invalid),
+        skipped: count(RowStatus::Skipped),
+        failed: count(RowStatus::Failed),
+        rows,
+    }
+}
+
+// Validasi lalu hash password satu baris, password tidak di-hash pada dry run
+async fn check_row(
+    row: usize,
+    parsed: Result<CreateUpdateUserSchema, String>,
+    duplicate: Option<Vec<String>>,
+    dry_run: bool,
+) -> Result<Candidate, RowResult> {
+    let invalid = |username: Option<&str>, errors: Vec<String>| RowResult {
+        row,
+        status: RowStatus::Invalid,
+        username: username.map(str::to_string),
+        id: None,
+        errors,
+    };
+    let body = parsed.map_err(|err| invalid(None, vec![err]))?;
+    if let Some(errors) = duplicate {
+        return Err(invalid(Some(&body.username), errors));
+    }
+    if let Err(err) = create_validation(&body).await {
+        return Err(invalid(Some(&body.username), vec![err]));
+    }
+
+    let password = if dry_run {
+        String::new()
+    } else {
+        hash_password(&body.password)
+            .await
+            .map_err(|err| invalid(Some(&body.username), vec![err]))?
+    };
+
+    Ok(Candidate {
+        row,
+        body,
+        password,
+    })
+}
+
+fn candidate_result(
+    candidate: &Candidate,
+    status: RowStatus,
+    id: Option<i32>,
+    errors: Vec<String>,
+) -> RowResult {
+    RowResult {
+        row: candidate.row,
+        status,
+        username: Some(candidate.body.username.clone()),
+        id,
+        errors,
+    }
+}
+
+// Semua baris dalam satu transaksi, Err berisi baris yang gagal (0 bila bukan baris)
+async fn insert_atomic(
+    data: &AppState,
+    candidates: &[Candidate],
+) -> Result<Vec<i32>, (usize, String)> {
+    let users: Vec<_> = candidates
+        .iter()
+        .map(|candidate| (&candidate.body, candidate.password.as_str()))
+        .collect();
+    data.users.insert_all(&users).await.map_err(|(index, err)| {
+        let row = index.map_or(0, |index| candidates[index].row);
+        (row, err)
+    })
+}
+
+#[tracing::instrument(skip(rows))]
+pub async fn import_users_service(
+    rows: Vec<ParsedRow>,
+    options: ImportOptions,
+    job_id: Option<&str>,
+) -> Result<ImportReport, String> {
+    let app_state: Arc<AppState> = APP_STATE.get_or_init(init_app_state).await.clone();
+    let data: &AppState = &app_state;
+    let mut duplicates = duplicate_errors(&rows);
+
+    // Validasi dan hashing berjalan paralel, dibatasi IMPORT_CONCURRENCY
+    let concurrency = env_or("IMPORT_CONCURRENCY", 4).max(1);
+    let mut checked = futures_util::stream::iter(rows)
+        .map(|(row, parsed)| check_row(row, parsed, duplicates.remove(&row), options.dry_run))
+        .buffered(concurrency);
+
+    let mut results: Vec<RowResult> = Vec::new();
+    let mut candidates: Vec<Candidate> = Vec::new();
+    while let Some(checked_row) = checked.next().await {
+        match checked_row {
+            Ok(candidate) => candidates.push(candidate),
+            Err(invalid) => results.push(invalid),
+        }
+        set_progress(job_id, results.len() + candidates.len());
+    }
+    drop(checked);
+
+    let mut created: Vec<(i32, &Candidate)> = Vec::new();
+    if options.dry_run {
+        results.extend(
+            candidates
+                .iter()
+                .map(|candidate| candidate_result(candidate, RowStatus::Valid, None, Vec::new())),
+        );
+    } else if options.mode == ImportMode::Atomic && !results.is_empty() {
+        results.extend(
+            candidates
+                .iter()
+                .map(|candidate| candidate_result(candidate, RowStatus::Skipped, None, Vec::new())),
+        );
+    } else if options.mode == ImportMode::Atomic {
+        match insert_atomic(data, &candidates).await {
+            Ok(ids) => created.extend(ids.into_iter().zip(&candidates)),
+            Err((failed_row, err)) => {
+                results.extend(candidates.iter().map(|candidate| {
+                    if candidate.row == failed_row {
+                        candidate_result(candidate, RowStatus::Failed, None, vec![err.clone()])
+                    } else {
+                        candidate_result(candidate, RowStatus::Skipped, None, Vec::new())
+                    }
+                }));
+            }
+        }
+    } else {
+        for candidate in &candidates {
+            match data
+                .users
+                .insert(&candidate.body, &candidate.password)
+                .await
+            {
+                Ok(Insert::Created(id)) => created.push((id, candidate)),
+                Ok(Insert::Taken(err)) | Err(err) => results.push(candidate_result(
+                    candidate,
+                    RowStatus::Failed,
+                    None,
+                    vec![err],
+                )),
+            }
+        }
+    }
+
+    for (id, candidate) in &created {
+        data.observable
+            .notify_event(user_created_event(*id, &candidate.body));
+        results.push(candidate_result(
+            candidate,
+            RowStatus::Created,
+            Some(*id),
+            Vec::new(),
+        ));
+    }
+    results.sort_by_key(|result| result.row);
+    let report = summarize(results, options);
+
+    if !options.dry_run {
+        data.observable.notify_event(Event::new(
+            "user.imported",
+            None,
+            serde_json::json!({
+                "format": report.format,
+                "mode": report.mode,
+                "total": report.total,
+                "created": report.created,
+                "invalid": report.invalid,
+                "failed": report.failed
+            }),
+        ));
+    }
+
+    Ok(report)
+}
+
+// Menjalankan import di background, status dapat dipantau lewat get_import_job_service
+pub fn start_import_job(rows: Vec<ParsedRow>, options: ImportOptions) -> ImportJob {
+    let now = Utc::now();
+    let job = ImportJob {
+        id: generate_token()[..32].to_string(),
+        status: JobStatus::Running,
+        total_rows: rows.len(),
+        processed_rows: 0,
+        report: None,
+        error: None,
+        created_at: now,
+        finished_at: None,
+    };
+
+    {
+        let mut jobs = IMPORT_JOBS.lock().unwrap();
+        // Job yang sudah selesai lebih dari sehari dibuang
+        jobs.retain(|_, job| {
+            job.finished_at
+                .is_none_or(|at| at > now - chrono::Duration::days(1))
+        });
+        jobs.insert(job.id.clone(), job.clone());
+    }
+
+    let job_id = job.id.clone();
+    let ctx = RequestContext::current().unwrap_or_default();
+    tokio::spawn(ctx.scope(async move {
+        let res = import_users_service(rows, options, Some(&job_id)).await;
+        let mut jobs = IMPORT_JOBS.lock().unwrap();
+        if let Some(job) = jobs.get_mut(&job_id) {
+            job.finished_at = Some(Utc::now());
+            match res {
+                Ok(report) => {
+                    job.status = JobStatus::Completed;
+                    job.processed_rows = report.total;
+                    job.report = Some(report);
+                }
+                Err(err) => {
+                    tracing::error!(job_id = %job_id, "User import failed: {}", err);
+                    job.status = JobStatus::Failed;
+                    job.error = Some(err);
+                }
+            }
+        }
+    }));
+
+    job
+}
+
+pub fn get_import_job_service(job_id: &str) -> Result<ImportJob, String> {
+    IMPORT_JOBS
+        .lock()
+        .unwrap()
+        .get(job_id)
+        .cloned()
+        .ok_or_else(|| format!("Import job {} does not exist", job_id))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn usernames(rows: &[ParsedRow]) -> Vec<(usize, Option<&str>)> {
+        rows.iter()
+            .map(|(row, parsed)| {
+                (
+                    *row,
+                    parsed.as_ref().ok().map(|body| body.username.as_str()),
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    fn parse_csv_rows() {
+        let body = "email,username,phone,password\n\
+            a@example.com, alice ,0811,secret1\n\
+            b@example.com,bob\n";
+        let rows = parse_rows(ImportFormat::Csv, body).unwrap();
+        // Urutan kolom bebas, baris yang rusak tetap punya nomor
+        assert_eq!(usernames(&rows), vec![(1, Some("alice")), (2, None)]);
+        assert!(rows[1].1.is_err());
+
+        let err = parse_rows(ImportFormat::Csv, "username,email,password\n").unwrap_err();
+        assert_eq!(err, "CSV header is missing the 'phone' column");
+    }
+
+    #[test]
+    fn parse_ndjson_rows() {
+        let body = r#"{"username":"alice","email":"a@example.com","phone":"0811","password":"x"}
+
+{"username":"bob"}
+"#;
+        let rows = parse_rows(ImportFormat::Ndjson, body).unwrap();
+        // Baris kosong dilewati
+        assert_eq!(usernames(&rows), vec![(1, Some("alice")), (2, None)]);
+    }
+
+    #[test]
+    fn duplicates_point_at_the_first_row() {
+        let row = |username: &str, email: &str, phone: &str| CreateUpdateUserSchema {
+            username: username.to_string(),
+            email: email.to_string(),
+            phone: phone.to_string(),
+            password: "secret".to_string(),
+        };
+        let rows = vec![
+            (1, Ok(row("alice", "a@example.com", "0811"))),
+            (2, Err("invalid".to_string())),
+            (3, Ok(row("Alice", "b@example.com", "0812"))),
+            (4, Ok(row("carol", "A@EXAMPLE.com", "0812"))),
+        ];
+        let errors = duplicate_errors(&rows);
+
+        assert_eq!(errors.len(), 2);
+        assert_eq!(errors[&3], vec!["username duplicates row 1"]);
+        assert_eq!(
+            errors[&4],
+            vec!["email duplicates row 1", "phone duplicates row 3"]
+        );
+    }
+
+    #[tokio::test]
+    async fn atomic_insert_rolls_back_taken_rows() {
+        use crate::service::simulation::simulation_service::init_simulation_app_state;
+
+        let data = init_simulation_app_state();
+        let admin = data.users.find(1, false).await.unwrap().unwrap();
+        let candidate = |row: usize, username: &str, email: &str, phone: &str| Candidate {
+            row,
+            body: CreateUpdateUserSchema {
+                username: username.to_string(),
+                email: email.to_string(),
+                phone: phone.to_string(),
+                password: "Password123!".to_string(),
+            },
+            password: "hash".to_string(),
+        };
+        let candidates = [
+            candidate(1, "o'brien", "o'brien@example.com", "+6281200000001"),
+            candidate(2, "importtaken", &admin.email, "+6281200000002"),
+        ];
+
+        let err = insert_atomic(&data, &candidates).await.unwrap_err();
+        assert_eq!(err, (2, "email is already taken".to_string()));
+        let found = data.users.find_by_field("username", "o'brien").await;
+        assert!(found.unwrap().is_none());
+
+        let ids = insert_atomic(&data, &candidates[..1]).await.unwrap();
+        let user = data.users.find(ids[0], false).await.unwrap().unwrap();
+        assert_eq!(user.email, "o'brien@example.com");
+    }
+}
@@ -0,0 +1,481 @@
+use crate::{
+    model::{model::UserModel, schema::CreateUpdateUserSchema},
+    utils::{
+        adapter::sql_span,
+        cursor::Sort,
+        factory::{QueryBuilder, SearchParam},
+    },
+};
+use axum::async_trait;
+use chrono::{DateTime, Utc};
+use sqlx::mysql::MySqlPool;
+use std::sync::Arc;
+use tracing::Instrument;
+
+// Nilai kolom sort, dibandingkan sesuai tipe kolomnya
+#[derive(Debug, Clone, PartialEq, PartialOrd)]
+pub enum SortValue {
+    Int(i64),
+    Text(String),
+    Time(Option<DateTime<Utc>>),
+}
+
+impl SortValue {
+    pub fn from_cursor(column: &str, value: &serde_json::Value) -> Result<Self, String> {
+        let invalid = || "Invalid pagination cursor".to_string();
+        Ok(match column {
+            "id" => SortValue::Int(value.as_i64().ok_or_else(invalid)?),
+            "created_at" | "updated_at" => {
+                let value = value.as_str().ok_or_else(invalid)?;
+                let at = DateTime::parse_from_rfc3339(value).map_err(|_| invalid())?;
+                SortValue::Time(Some(at.with_timezone(&Utc)))
+            }
+            _ => SortValue::Text(value.as_str().ok_or_else(invalid)?.to_string()),
+        })
+    }
+
+    pub fn of(user: &UserModel, column: &str) -> Self {
+        match column {
+            "id" => SortValue::Int(user.id as i64),
+            "username" => SortValue::Text(user.username.clone()),
+            "email" => SortValue::Text(user.email.clone()),
+            "created_at" => SortValue::Time(user.created_at),
+            _ => SortValue::Time(user.updated_at),
+        }
+    }
+}
+
+const TAKEN: &str = "username, email or phone is already taken";
+
+pub enum Insert {
+    Created(i32),
+    // Username, email atau phone sudah dipakai pengguna aktif lain
+    Taken(String),
+}
+
+pub enum Update {
+    Done,
+    // version sudah berubah atau pengguna sudah dihapus
+    Stale,
+    // Username, email atau phone sudah dipakai pengguna aktif lain
+    Taken(String),
+}
+
+pub struct PageQuery<'a> {
+    pub include_deleted: bool,
+    // Arah yang sudah dibalik bila membaca mundur
+    pub sort: &'a Sort,
+    // Keyset: hanya baris setelah (nilai sort, id) ini, offset diabaikan
+    pub after: Option<(SortValue, i64)>,
+    pub offset: usize,
+    pub limit: usize,
+}
+
+// Token lebih pendek dari ini diabaikan FULLTEXT (innodb_ft_min_token_size)
+const MIN_FULLTEXT_TOKEN: usize = 3;
+pub const MIN_PHONE_DIGITS: usize = 3;
+
+// Kata kunci pencarian yang sudah diurai oleh search service
+pub struct UserSearch {
+    pub q: String,
+    // Kata dalam huruf kecil
+    pub terms: Vec<String>,
+    pub phone_digits: String,
+    // Potongan 3 huruf dari kata kunci, untuk kandidat yang salah ketik
+    pub trigrams: Vec<String>,
+    pub limit: usize,
+}
+
+fn fulltext_expression(terms: &[String]) -> String {
+    terms
+        .iter()
+        .filter(|term| term.chars().count() >= MIN_FULLTEXT_TOKEN)
+        .map(|term| format!("+{}*", term))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+fn escape_like(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('%', "\\%")
+        .replace('_', "\\_")
+}
+
+impl UserSearch {
+    // Nilai untuk parameter ? dari select_search
+    fn value(&self, param: SearchParam) -> Option<String> {
+        match param {
+            SearchParam::FullText => Some(fulltext_expression(&self.terms)),
+            SearchParam::TextPrefix => Some(format!("{}%", escape_like(&self.q))),
+            SearchParam::TextContains => Some(format!("%{}%", escape_like(&self.q))),
+            SearchParam::Trigram(n) => Some(format!("%{}%", escape_like(&self.trigrams[n]))),
+            SearchParam::Digits => (self.phone_digits.len() >= MIN_PHONE_DIGITS)
+                .then(|| format!("%{}%", self.phone_digits)),
+        }
+    }
+}
+
+// Akses tabel users; mode --simulate memakai implementasi di memori
+#[async_trait]
+pub trait UserRepository: Send + Sync {
+    async fn all(&self, include_deleted: bool) -> Result<Vec<UserModel>, String>;
+    async fn page(&self, page: &PageQuery<'_>) -> Result<Vec<UserModel>, String>;
+    async fn find(&self, id: i32, include_deleted: bool) -> Result<Option<UserModel>, String>;
+    // Hanya pengguna aktif, mis. login dengan username atau email
+    async fn find_by_field(&self, field: &str, value: &str) -> Result<Option<UserModel>, String>;
+    // Kandidat pencarian pengguna aktif, belum disaring dan diberi skor
+    async fn search(&self, search: &UserSearch) -> Result<Vec<UserModel>, String>;
+    async fn insert(&self, body: &CreateUpdateUserSchema, password: &str)
+        -> Result<Insert, String>;
+    // Semua dibuat atau tidak sama sekali; Err berisi indeks pengguna yang gagal,
+    // None bila gagal di luar baris mana pun
+    async fn insert_all(
+        &self,
+        users: &[(&CreateUpdateUserSchema, &str)],
+    ) -> Result<Vec<i32>, (Option<usize>, String)>;
+    async fn update_if_version(
+        &self,
+        id: i32,
+        version: i32,
+        body: &CreateUpdateUserSchema,
+        password: &str,
+    ) -> Result<Update, String>;
+    async fn set_password(&self, id: i32, password: &str) -> Result<(), String>;
+    // column: email_verified_at atau phone_verified_at
+    async fn reset_verification(&self, id: i32, column: &str) -> Result<(), String>;
+    async fn soft_delete_if_version(&self, id: i32, version: i32) -> Result<bool, String>;
+    async fn restore(&self, id: i32) -> Result<(), String>;
+    async fn deleted_before(&self, cutoff: DateTime<Utc>) -> Result<Vec<UserModel>, String>;
+    async fn delete(&self, id: i32) -> Result<(), String>;
+}
+
+pub struct MySqlUserRepository {
+    db: MySqlPool,
+    query_builder: Arc<dyn QueryBuilder>,
+}
+
+impl MySqlUserRepository {
+    pub fn new(db: MySqlPool, query_builder: Arc<dyn QueryBuilder>) -> Self {
+        MySqlUserRepository { db, query_builder }
+    }
+
+    async fn fetch_optional(&self, query: &str) -> Result<Option<UserModel>, String> {
+        sqlx::query_as::<_, UserModel>(query)
+            .fetch_optional(&self.db)
+            .instrument(sql_span(query))
+            .await
+            .map_err(|err: sqlx::Error| err.to_string())
+    }
+
+    fn insert_query(&self) -> String {
+        self.query_builder
+            .insert_bind("users", &["username", "email", "phone", "password"])
+    }
+
+    async fn execute(&self, query: &str) -> Result<u64, String> {
+        sqlx::query(query)
+            .execute(&self.db)
+            .instrument(sql_span(query))
+            .await
+            .map(|res| res.rows_affected())
+            .map_err(|err: sqlx::Error| err.to_string())
+    }
+}
+
+// Juga dipakai di dalam transaksi insert_all
+async fn insert_user(
+    executor: impl sqlx::MySqlExecutor<'_>,
+    query: &str,
+    body: &CreateUpdateUserSchema,
+    password: &str,
+) -> Result<Insert, String> {
+    let res = sqlx::query(query)
+        .bind(&body.username)
+        .bind(&body.email)
+        .bind(&body.phone)
+        .bind(password)
+        .execute(executor)
+        .instrument(sql_span(query))
+        .await;
+    match res {
+        Ok(res) => Ok(Insert::Created(res.last_insert_id() as i32)),
+        Err(sqlx::Error::Database(db_err)) if db_err.is_unique_violation() => {
+            Ok(Insert::Taken(TAKEN.to_string()))
+        }
+        Err(err) => Err(err.to_string()),
+    }
+}
+
+#[async_trait]
+impl UserRepository for MySqlUserRepository {
+    async fn all(&self, include_deleted: bool) -> Result<Vec<UserModel>, String> {
+        let query = if include_deleted {
+            self.query_builder.select_all_with_deleted("users")
+        } else {
+            self.query_builder.select_all("users")
+        };
+        sqlx::query_as::<_, UserModel>(&query)
+            .fetch_all(&self.db)
+            .instrument(sql_span(&query))
+            .await
+            .map_err(|err: sqlx::Error| err.to_string())
+    }
+
+    async fn page(&self, page: &PageQuery<'_>) -> Result<Vec<UserModel>, String> {
+        let query = match &page.after {
+            Some(_) => self.query_builder.select_keyset(
+                "users",
+                &[],
+                page.include_deleted,
+                page.sort,
+                true,
+                page.limit,
+            ),
+            None => {
+                let direction = if page.sort.descending { "DESC" } else { "ASC" };
+                self.query_builder.select_where(
+                    "users",
+                    &[],
+                    page.include_deleted,
+                    &format!(
+                        "{col} {dir}, id {dir}",
+                        col = page.sort.column,
+                        dir = direction
+                    ),
+                    page.limit,
+                    page.offset,
+                )
+            }
+        };
+
+        let mut users_query = sqlx::query_as::<_, UserModel>(&query);
+        if let Some((value, id)) = &page.after {
+            users_query = match value {
+                SortValue::Int(value) => users_query.bind(*value),
+                SortValue::Text(value) => users_query.bind(value.clone()),
+                SortValue::Time(value) => users_query.bind(*value),
+            };
+            users_query = users_query.bind(*id);
+        }
+        users_query
+            .fetch_all(&self.db)
+            .instrument(sql_span(&query))
+            .await
+            .map_err(|err: sqlx::Error| err.to_string())
+    }
+
+    async fn find(&self, id: i32, include_deleted: bool) -> Result<Option<UserModel>, String> {
+        let query = if include_deleted {
+            self.query_builder
+                .select_by_id_with_deleted("users", id as u64)
+        } else {
+            self.query_builder.select_by_id("users", id as u64)
+        };
+        self.fetch_optional(&query).await
+    }
+
+    async fn find_by_field(&self, field: &str, value: &str) -> Result<Option<UserModel>, String> {
+        let query = self.query_builder.select_by_field("users", field);
+        sqlx::query_as::<_, UserModel>(&query)
+            .bind(value)
+            .fetch_optional(&self.db)
+            .instrument(sql_span(&query))
+            .await
+            .map_err(|err: sqlx::Error| err.to_string())
+    }
+
+    async fn search(&self, search: &UserSearch) -> Result<Vec<UserModel>, String> {
+        let query = self.query_builder.select_search(
+            "users",
+            &["username", "email"],
+            "phone_digits",
+            search.trigrams.len(),
+            search.limit,
+        );
+        let mut users = sqlx::query_as::<_, UserModel>(&query.sql);
+        for param in &query.params {
+            users = users.bind(search.value(*param));
+        }
+        users
+            .fetch_all(&self.db)
+            .instrument(sql_span(&query.sql))
+            .await
+            .map_err(|err: sqlx::Error| err.to_string())
+    }
+
+    async fn insert(
+        &self,
+        body: &CreateUpdateUserSchema,
+        password: &str,
+    ) -> Result<Insert, String> {
+        let query = self.insert_query();
+        insert_user(&self.db, &query, body, password).await
+    }
+
+    async fn insert_all(
+        &self,
+        users: &[(&CreateUpdateUserSchema, &str)],
+    ) -> Result<Vec<i32>, (Option<usize>, String)> {
+        let query = self.insert_query();
+        let mut tx = self
+            .db
+            .begin()
+            .await
+            .map_err(|err| (None, err.to_string()))?;
+        let mut ids = Vec::with_capacity(users.len());
+        for (index, (body, password)) in users.iter().enumerate() {
+            match insert_user(&mut *tx, &query, body, password).await {
+                Ok(Insert::Created(id)) => ids.push(id),
+                Ok(Insert::Taken(err)) | Err(err) => return Err((Some(index), err)),
+            }
+        }
+        tx.commit().await.map_err(|err| (None, err.to_string()))?;
+
+        Ok(ids)
+    }
+
+    async fn update_if_version(
+        &self,
+        id: i32,
+        version: i32,
+        body: &CreateUpdateUserSchema,
+        password: &str,
+    ) -> Result<Update, String> {
+        let query = self.query_builder.update_if_version(
+            "users",
+            id as u64,
+            &["username", "email", "phone", "password"],
+        );
+        let res = sqlx::query(&query)
+            .bind(&body.username)
+            .bind(&body.email)
+            .bind(&body.phone)
+            .bind(password)
+            .bind(version)
+            .execute(&self.db)
+            .instrument(sql_span(&query))
+            .await;
+        match res {
+            Ok(res) if res.rows_affected() > 0 => Ok(Update::Done),
+            Ok(_) => Ok(Update::Stale),
+            Err(sqlx::Error::Database(db_err)) if db_err.is_unique_violation() => {
+                Ok(Update::Taken(TAKEN.to_string()))
+            }
+            Err(err) => Err(err.to_string()),
+        }
+    }
+
+    async fn set_password(&self, id: i32, password: &str) -> Result<(), String> {
+        let query = self
+            .query_builder
+            .update_bind("users", id as u64, &["password"]);
+        sqlx::query(&query)
+            .bind(password)
+            .execute(&self.db)
+            .instrument(sql_span(&query))
+            .await
+            .map_err(|err: sqlx::Error| err.to_string())?;
+
+        Ok(())
+    }
+
+    async fn reset_verification(&self, id: i32, column: &str) -> Result<(), String> {
+        let query = format!("UPDATE users SET {} = NULL WHERE id = ?;", column);
+        sqlx::query(&query)
+            .bind(id)
+            .execute(&self.db)
+            .instrument(sql_span(&query))
+            .await
+            .map_err(|err: sqlx::Error| err.to_string())?;
+
+        Ok(())
+    }
+
+    async fn soft_delete_if_version(&self, id: i32, version: i32) -> Result<bool, String> {
+        let query = self
+            .query_builder
+            .soft_delete_if_version("users", id as u64, version);
+        Ok(self.execute(&query).await? > 0)
+    }
+
+    async fn restore(&self, id: i32) -> Result<(), String> {
+        let query = self.query_builder.restore("users", id as u64);
+        sqlx::query(&query)
+            .execute(&self.db)
+            .instrument(sql_span(&query))
+            .await
+            .map_err(|err: sqlx::Error| match err {
+                // Username, email atau phone sudah dipakai pengguna aktif lain
+                sqlx::Error::Database(db_err) if db_err.is_unique_violation() => {
+                    "Cannot restore user, username, email or phone is already taken".to_string()
+                }
+                err => err.to_string(),
+            })?;
+
+        Ok(())
+    }
+
+    async fn deleted_before(&self, cutoff: DateTime<Utc>) -> Result<Vec<UserModel>, String> {
+        let query = self.query_builder.select_deleted_before("users");
+        sqlx::query_as::<_, UserModel>(&query)
+            .bind(cutoff)
+            .fetch_all(&self.db)
+            .instrument(sql_span(&query))
+            .await
+            .map_err(|err: sqlx::Error| err.to_string())
+    }
+
+    async fn delete(&self, id: i32) -> Result<(), String> {
+        let query = self.query_builder.delete("users", id as u64);
+        self.execute(&query).await.map(|_| ())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn search(q: &str, terms: &[&str], phone_digits: &str) -> UserSearch {
+        UserSearch {
+            q: q.to_string(),
+            terms: terms.iter().map(|term| term.to_string()).collect(),
+            phone_digits: phone_digits.to_string(),
+            trigrams: vec!["bud".to_string()],
+            limit: 20,
+        }
+    }
+
+    #[test]
+    fn search_values_escape_like_and_skip_short_tokens() {
+        let search = search("bu_di 50%", &["bu", "di", "50"], "50");
+        assert_eq!(search.value(SearchParam::FullText), Some(String::new()));
+        assert_eq!(
+            search.value(SearchParam::TextPrefix),
+            Some("bu\\_di 50\\%%".to_string())
+        );
+        assert_eq!(
+            search.value(SearchParam::TextContains),
+            Some("%bu\\_di 50\\%%".to_string())
+        );
+        assert_eq!(
+            search.value(SearchParam::Trigram(0)),
+            Some("%bud%".to_string())
+        );
+        // Terlalu sedikit angka untuk dicocokkan dengan nomor telepon
+        assert_eq!(search.value(SearchParam::Digits), None);
+    }
+
+    #[test]
+    fn search_values_for_words_and_phone() {
+        let search = search("budi gmail 0812", &["budi", "gmail", "0812"], "0812");
+        assert_eq!(
+            search.value(SearchParam::FullText),
+            Some("+budi* +gmail* +0812*".to_string())
+        );
+        assert_eq!(
+            search.value(SearchParam::Digits),
+            Some("%0812%".to_string())
+        );
+    }
+}
@@ -0,0 +1,363 @@
+use crate::{
+    model::model::UserModel,
+    service::user::user_repository::{UserSearch, MIN_PHONE_DIGITS},
+    utils::singleton::{init_app_state, AppState},
+};
+use serde::Serialize;
+use std::{collections::BTreeMap, sync::Arc};
+use tokio::sync::OnceCell;
+
+static APP_STATE: OnceCell<Arc<AppState>> = OnceCell::const_new();
+
+// Kata sependek ini harus cocok persis, lebih panjang boleh salah ketik
+const MIN_FUZZY_TERM: usize = 4;
+// Setiap trigram menambah satu LIKE per kolom pada query kandidat
+const MAX_TRIGRAMS: usize = 8;
+// Kandidat diambil lebih banyak dari limit karena kandidat trigram baru disaring setelah diberi skor
+const CANDIDATES_PER_HIT: usize = 5;
+const MAX_CANDIDATES: usize = 250;
+
+#[derive(Debug, Serialize)]
+pub struct SearchHit {
+    pub user: serde_json::Value,
+    pub score: u32,
+    // Nilai field dengan bagian yang cocok dibungkus <mark>, sudah di-escape
+    pub highlights: BTreeMap<&'static str, String>,
+}
+
+fn digits(value: &str) -> String {
+    value.chars().filter(char::is_ascii_digit).collect()
+}
+
+fn terms(q: &str) -> Vec<String> {
+    q.split(|c: char| !c.is_alphanumeric())
+        .filter(|term| !term.is_empty())
+        .map(str::to_lowercase)
+        .collect()
+}
+
+// Potongan 3 huruf dari kata yang boleh salah ketik, mis. "budy" -> "bud", "udy"
+fn trigrams(terms: &[String]) -> Vec<String> {
+    let mut trigrams: Vec<String> = Vec::new();
+    for term in terms {
+        let chars: Vec<char> = term.chars().collect();
+        if chars.len() < MIN_FUZZY_TERM {
+            continue;
+        }
+        for window in chars.windows(3) {
+            let trigram: String = window.iter().collect();
+            if !trigrams.contains(&trigram) {
+                trigrams.push(trigram);
+            }
+        }
+    }
+    trigrams.truncate(MAX_TRIGRAMS);
+    trigrams
+}
+
+// Jumlah huruf yang boleh salah sesuai panjang kata
+fn max_typos(term: &str) -> usize {
+    match term.chars().count() {
+        n if n < MIN_FUZZY_TERM => 0,
+        n if n < 8 => 1,
+        _ => 2,
+    }
+}
+
+// Levenshtein: sisip, hapus atau ganti satu huruf
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+    for (i, a) in a.chars().enumerate() {
+        let mut current = vec![i + 1];
+        for (j, b) in b.iter().enumerate() {
+            let substitute = previous[j] + usize::from(a != *b);
+            current.push(substitute.min(previous[j + 1] + 1).min(current[j] + 1));
+        }
+        previous = current;
+    }
+    previous[b.len()]
+}
+
+// Rentang byte kata di value yang mirip dengan salah satu term, termasuk awalan kata
+// (mis. "budy" untuk "budiman"); kata yang sudah cocok persis tidak dihitung lagi
+fn fuzzy_matches(value: &str, terms: &[String]) -> Vec<(usize, usize)> {
+    let lower = value.to_lowercase();
+    if lower.len() != value.len() {
+        return Vec::new();
+    }
+
+    let mut ranges = Vec::new();
+    for word in lower.split(|c: char| !c.is_alphanumeric()) {
+        if word.is_empty() {
+            continue;
+        }
+        let start = word.as_ptr() as usize - lower.as_ptr() as usize;
+        let similar = terms.iter().any(|term| {
+            let allowed = max_typos(term);
+            let prefix: String = word.chars().take(term.chars().count()).collect();
+            let distance = edit_distance(word, term).min(edit_distance(&prefix, term));
+            allowed > 0 && distance > 0 && distance <= allowed
+        });
+        if similar {
+            ranges.push((start, start + word.len()));
+        }
+    }
+    ranges
+}
+
+fn escape_html(value: &str) -> String {
+    value
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&#39;")
+}
+
+// Rentang byte (awal, akhir) di value yang cocok dengan salah satu term
+fn text_matches(value: &str, terms: &[String]) -> Vec<(usize, usize)> {
+    let lower = value.to_lowercase();
+    // Huruf yang panjang byte-nya berubah saat lowercase membuat offset tidak valid
+    if lower.len() != value.len() {
+        return Vec::new();
+    }
+
+    let mut ranges = Vec::new();
+    for term in terms {
+        let mut from = 0;
+        while let Some(at) = lower[from..].find(term.as_str()) {
+            let start = from + at;
+            ranges.push((start, start + term.len()));
+            from = start + term.len();
+        }
+    }
+    ranges
+}
+
+// Angka yang cocok dipetakan kembali ke posisinya di nomor yang masih berformat
+fn phone_matches(phone: &str, needle: &str) -> Vec<(usize, usize)> {
+    if needle.len() < MIN_PHONE_DIGITS {
+        return Vec::new();
+    }
+    let positions: Vec<(usize, char)> = phone
+        .char_indices()
+        .filter(|(_, c)| c.is_ascii_digit())
+        .collect();
+    let haystack: String = positions.iter().map(|(_, c)| c).collect();
+
+    match haystack.find(needle) {
+        Some(at) => {
+            let start = positions[at].0;
+            let end = positions[at + needle.len() - 1].0 + 1;
+            vec![(start, end)]
+        }
+        None => Vec::new(),
+    }
+}
+
+fn highlight(value: &str, mut ranges: Vec<(usize, usize)>) -> String {
+    ranges.sort();
+    let mut merged: Vec<(usize, usize)> = Vec::new();
+    for (start, end) in ranges {
+        match merged.last_mut() {
+            Some(last) if start <= last.1 => last.1 = last.1.max(end),
+            _ => merged.push((start, end)),
+        }
+    }
+
+    let mut out = String::new();
+    let mut from = 0;
+    for (start, end) in merged {
+        out.push_str(&escape_html(&value[from..start]));
+        out.push_str("<mark>");
+        out.push_str(&escape_html(&value[start..end]));
+        out.push_str("</mark>");
+        from = end;
+    }
+    out.push_str(&escape_html(&value[from..]));
+    out
+}
+
+// Sama persis > awalan > mengandung > mirip, dikali bobot field
+fn field_score(value: &str, ranges: &[(usize, usize)], weight: u32) -> u32 {
+    ranges
+        .iter()
+        .map(|(start, end)| {
+            let points = if *start == 0 && *end == value.len() {
+                10
+            } else if *start == 0 {
+                5
+            } else {
+                2
+            };
+            points * weight
+        })
+        .sum()
+}
+
+fn score_hit(user: UserModel, terms: &[String], phone_digits: &str) -> SearchHit {
+    let fields = [
+        ("username", &user.username, 3),
+        ("email", &user.email, 2),
+        ("phone", &user.phone, 2),
+    ];
+
+    let mut score = 0;
+    let mut highlights = BTreeMap::new();
+    for (name, value, weight) in fields {
+        let (ranges, fuzzy) = match name {
+            "phone" => (phone_matches(value, phone_digits), Vec::new()),
+            _ => (text_matches(value, terms), fuzzy_matches(value, terms)),
+        };
+        if ranges.is_empty() && fuzzy.is_empty() {
+            continue;
+        }
+        score += field_score(value, &ranges, weight) + fuzzy.len() as u32 * weight;
+        highlights.insert(name, highlight(value, [ranges, fuzzy].concat()));
+    }
+
+    let mut user = serde_json::to_value(&user).unwrap_or_default();
+    if let Some(user) = user.as_object_mut() {
+        user.remove("password");
+    }
+
+    SearchHit {
+        user,
+        score,
+        highlights,
+    }
+}
+
+#[tracing::instrument]
+pub async fn search_users_service(q: &str, limit: usize) -> Result<Vec<SearchHit>, String> {
+    let q = q.trim();
+    if q.chars().count() < 2 {
+        return Err("Search query must be at least 2 characters".to_string());
+    }
+
+    let app_state: Arc<AppState> = APP_STATE.get_or_init(init_app_state).await.clone();
+    let limit = limit.clamp(1, 50);
+    let terms = terms(q);
+    let search = UserSearch {
+        q: q.to_string(),
+        trigrams: trigrams(&terms),
+        terms,
+        phone_digits: digits(q),
+        limit: (limit * CANDIDATES_PER_HIT).min(MAX_CANDIDATES),
+    };
+    let users = app_state.users.search(&search).await?;
+
+    // Urutan dari repository (relevansi FULLTEXT) dipertahankan untuk skor yang sama
+    let mut hits: Vec<SearchHit> = users
+        .into_iter()
+        .map(|user| score_hit(user, &search.terms, &search.phone_digits))
+        .filter(|hit| hit.score > 0)
+        .collect();
+    hits.sort_by_key(|hit| std::cmp::Reverse(hit.score));
+    hits.truncate(limit);
+
+    Ok(hits)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn user(username: &str, email: &str, phone: &str) -> UserModel {
+        UserModel {
+            id: 1,
+            username: username.to_string(),
+            email: email.to_string(),
+            phone: phone.to_string(),
+            password: "hash".to_string(),
+            role: "user".to_string(),
+            token_version: 0,
+            version: 1,
+            email_verified_at: None,
+            phone_verified_at: None,
+            totp_secret: None,
+            totp_enabled_at: None,
+            totp_last_step: None,
+            deleted_at: None,
+            created_at: None,
+            updated_at: None,
+        }
+    }
+
+    #[test]
+    fn trigrams_skip_short_terms() {
+        assert_eq!(
+            trigrams(&terms("budy 0812")),
+            vec!["bud", "udy", "081", "812"]
+        );
+        assert!(trigrams(&terms("bdi ok")).is_empty());
+        assert_eq!(trigrams(&terms("abcdefghijklmnop")).len(), MAX_TRIGRAMS);
+    }
+
+    #[test]
+    fn edit_distance_counts_single_edits() {
+        assert_eq!(edit_distance("budi", "budi"), 0);
+        assert_eq!(edit_distance("budy", "budi"), 1);
+        assert_eq!(edit_distance("bdi", "budi"), 1);
+        assert_eq!(edit_distance("budii", "budi"), 1);
+        assert_eq!(edit_distance("santso", "santoso"), 1);
+        assert_eq!(edit_distance("", "abc"), 3);
+    }
+
+    #[test]
+    fn fuzzy_matches_words_and_prefixes() {
+        let budy = terms("budy");
+        assert_eq!(fuzzy_matches("budi", &budy), vec![(0, 4)]);
+        assert_eq!(fuzzy_matches("budiman.santoso", &budy), vec![(0, 7)]);
+        assert_eq!(fuzzy_matches("andi—budi", &budy), vec![(7, 11)]);
+        // Kata yang cocok persis sudah dihitung oleh text_matches
+        assert!(fuzzy_matches("budy", &budy).is_empty());
+        // Kata pendek harus cocok persis
+        assert!(fuzzy_matches("ani", &terms("ano")).is_empty());
+        assert!(fuzzy_matches("siti", &budy).is_empty());
+    }
+
+    #[test]
+    fn phone_matches_ignore_formatting() {
+        assert_eq!(phone_matches("+62 812-345", "812345"), vec![(4, 11)]);
+        assert!(phone_matches("+62 812-345", "81").is_empty());
+    }
+
+    #[test]
+    fn highlight_escapes_and_merges_ranges() {
+        assert_eq!(
+            highlight("<b>budi", vec![(3, 5), (4, 7)]),
+            "&lt;b&gt;<mark>budi</mark>"
+        );
+    }
+
+    #[test]
+    fn exact_ranks_above_prefix_above_typo() {
+        let terms = terms("budi");
+        let exact = score_hit(user("budi", "a@example.com", "+62"), &terms, "");
+        let prefix = score_hit(user("budiman", "b@example.com", "+62"), &terms, "");
+        let typo = score_hit(user("budy", "c@example.com", "+62"), &terms, "");
+        let other = score_hit(user("siti", "d@example.com", "+62"), &terms, "");
+
+        assert!(exact.score > prefix.score);
+        assert!(prefix.score > typo.score);
+        assert!(typo.score > 0);
+        assert_eq!(typo.highlights["username"], "<mark>budy</mark>");
+        assert_eq!(other.score, 0);
+        assert!(exact.user.get("password").is_none());
+    }
+
+    #[tokio::test]
+    async fn search_tolerates_typos() {
+        crate::service::simulation::simulation_service::init_simulation_app_state();
+
+        let hits = search_users_service("admim", 10).await.unwrap();
+        let admin = hits
+            .iter()
+            .find(|hit| hit.user["username"] == "admin")
+            .expect("admin should be found despite the typo");
+        assert_eq!(admin.highlights["username"], "<mark>admin</mark>");
+        assert!(hits.iter().all(|hit| hit.score > 0));
+    }
+}
@@ -1,7 +1,22 @@
 use crate::{
-    model::{model::UserModel, schema::CreateUpdateUserSchema},
-    service::user::user_validator::{create_validation, update_validation},
-    utils::singleton::{init_app_state, AppState},
+    model::{
+        model::UserModel,
+        schema::{CreateUpdateUserSchema, FilterOptions, PatchUserSchema},
+    },
+    service::user::{
+        user_repository::{Insert, PageQuery, SortValue, Update},
+        user_validator::{create_validation, patch_validation, update_validation},
+    },
+    utils::{
+        audit::diff_fields,
+        cursor::{Cursor, Direction, Sort},
+        etag::EntityTags,
+        hashing::hash_password,
+        lockout::{self, ThrottleKey},
+        observer::Event,
+        presence::PresenceStatus,
+        singleton::{init_app_state, AppState},
+    },
 };
 use axum::{extract::State, Json};
 use std::sync::Arc;
@@ -9,109 +24,450 @@ use tokio::sync::OnceCell;
 
 static APP_STATE: OnceCell<Arc<AppState>> = OnceCell::const_new();
 
-pub async fn get_all_user_service() -> Result<Vec<UserModel>, String> {
+pub enum WriteOutcome {
+    // Version pengguna setelah perubahan
+    Done(i32),
+    // If-Match tidak cocok atau data diubah request lain, berisi version terkini
+    VersionMismatch(i32),
+    // Username, email atau phone sudah dipakai pengguna lain
+    Taken(String),
+}
+
+const NO_USER_FIELDS: [(&str, Option<&str>); 4] = [
+    ("username", None),
+    ("email", None),
+    ("phone", None),
+    ("password", None),
+];
+
+fn user_fields(user: &UserModel) -> [(&str, Option<&str>); 4] {
+    [
+        ("username", Some(user.username.as_str())),
+        ("email", Some(user.email.as_str())),
+        ("phone", Some(user.phone.as_str())),
+        ("password", Some(user.password.as_str())),
+    ]
+}
+
+fn schema_fields(body: &CreateUpdateUserSchema) -> [(&str, Option<&str>); 4] {
+    [
+        ("username", Some(body.username.as_str())),
+        ("email", Some(body.email.as_str())),
+        ("phone", Some(body.phone.as_str())),
+        ("password", Some(body.password.as_str())),
+    ]
+}
+
+#[tracing::instrument]
+pub async fn get_all_user_service(include_deleted: bool) -> Result<Vec<UserModel>, String> {
     let app_state: Arc<AppState> = APP_STATE.get_or_init(init_app_state).await.clone();
     let data: State<Arc<AppState>> = State(app_state);
-    let query = data.query_builder.select_all("users");
-    let res = sqlx::query_as::<_, UserModel>(&query)
-        .fetch_all(&data.db)
-        .await
-        .map_err(|err: sqlx::Error| err.to_string());
 
-    res
+    data.users.all(include_deleted).await
 }
 
-pub async fn get_user_by_id_service(id: i32) -> Result<UserModel, String> {
+pub struct UserPage {
+    pub users: Vec<UserModel>,
+    pub limit: usize,
+    pub sort: Sort,
+    pub next_cursor: Option<String>,
+    pub prev_cursor: Option<String>,
+}
+
+fn sort_value(user: &UserModel, column: &str) -> serde_json::Value {
+    match column {
+        "id" => serde_json::json!(user.id),
+        "username" => serde_json::json!(user.username),
+        "email" => serde_json::json!(user.email),
+        "created_at" => serde_json::json!(user.created_at),
+        _ => serde_json::json!(user.updated_at),
+    }
+}
+
+// Offset (page) atau keyset (cursor); keduanya mengembalikan cursor ke halaman sekitarnya
+#[tracing::instrument]
+pub async fn get_users_page_service(opts: &FilterOptions) -> Result<UserPage, String> {
     let app_state: Arc<AppState> = APP_STATE.get_or_init(init_app_state).await.clone();
     let data: State<Arc<AppState>> = State(app_state);
-    let query = data.query_builder.select_by_id("users", id as u64);
-    let res = sqlx::query_as::<_, UserModel>(&query)
-        .fetch_one(&data.db)
-        .await
-        .map_err(|err: sqlx::Error| err.to_string());
 
-    if let Err(_) = res {
-        return Err(format!("User with id {} does not exist", id));
+    let limit = opts.limit.unwrap_or(20).clamp(1, 100);
+    let cursor = opts.cursor.as_deref().map(Cursor::decode).transpose()?;
+    let sort = match &cursor {
+        Some(cursor) => cursor.sort()?,
+        None => Sort::parse(opts.sort.as_deref())?,
+    };
+    let include_deleted = opts.include_deleted.unwrap_or(false);
+
+    let page = opts
+        .page
+        .filter(|_| cursor.is_none())
+        .map(|page| page.max(1));
+    let backwards = cursor
+        .as_ref()
+        .is_some_and(|cursor| cursor.direction == Direction::Prev);
+    let after = match &cursor {
+        Some(cursor) => Some((
+            SortValue::from_cursor(sort.column, &cursor.value)?,
+            cursor.id,
+        )),
+        None => None,
+    };
+    let mut users = data
+        .users
+        .page(&PageQuery {
+            include_deleted,
+            sort: &Sort {
+                descending: sort.descending != backwards,
+                ..sort.clone()
+            },
+            after,
+            offset: page.map_or(0, |page| (page - 1) * limit),
+            limit: limit + 1,
+        })
+        .await?;
+
+    let has_more = users.len() > limit;
+    users.truncate(limit);
+    if backwards {
+        users.reverse();
     }
 
-    res
+    let cursor_at = |user: Option<&UserModel>, direction: Direction| {
+        user.map(|user| {
+            Cursor {
+                sort: sort.as_param(),
+                value: sort_value(user, sort.column),
+                id: user.id as i64,
+                direction,
+            }
+            .encode()
+        })
+    };
+    // Mundur: halaman berikutnya pasti ada, halaman sebelumnya bila masih ada sisa
+    let (has_next, has_prev) = if backwards {
+        (true, has_more)
+    } else {
+        (
+            has_more,
+            cursor.is_some() || page.is_some_and(|page| page > 1),
+        )
+    };
+    let next_cursor = if has_next {
+        cursor_at(users.last(), Direction::Next)
+    } else {
+        None
+    };
+    let prev_cursor = if has_prev {
+        cursor_at(users.first(), Direction::Prev)
+    } else {
+        None
+    };
+
+    Ok(UserPage {
+        users,
+        limit,
+        sort,
+        next_cursor,
+        prev_cursor,
+    })
 }
 
-pub async fn create_user_service(
-    Json(body): Json<CreateUpdateUserSchema>,
-) -> Result<sqlx::mysql::MySqlQueryResult, String> {
+#[tracing::instrument]
+pub async fn get_user_by_id_service(id: i32) -> Result<UserModel, String> {
+    let app_state: Arc<AppState> = APP_STATE.get_or_init(init_app_state).await.clone();
+    let data: State<Arc<AppState>> = State(app_state);
+
+    match data.users.find(id, false).await {
+        Ok(Some(user)) => Ok(user),
+        _ => Err(format!("User with id {} does not exist", id)),
+    }
+}
+
+#[tracing::instrument(skip_all, fields(username = %body.username))]
+pub async fn create_user_service(Json(body): Json<CreateUpdateUserSchema>) -> Result<i32, String> {
     create_validation(&body).await?;
+    match insert_user_service(&body).await? {
+        Insert::Created(id) => Ok(id),
+        Insert::Taken(err) => Err(err),
+    }
+}
+
+// Tanpa validasi, untuk pemanggil yang sudah memvalidasi body sendiri. Taken bila
+// pengguna lain mendapat username, email atau phone yang sama setelah validasi
+pub async fn insert_user_service(body: &CreateUpdateUserSchema) -> Result<Insert, String> {
     let app_state: Arc<AppState> = APP_STATE.get_or_init(init_app_state).await.clone();
     let data: State<Arc<AppState>> = State(app_state);
-    let password = bcrypt::hash(body.password.to_string(), 10).unwrap();
-    let query = data.query_builder.insert(
-        "users",
-        &["username", "email", "phone", "password"],
-        &[&body.username, &body.email, &body.phone, &password],
-    );
-    let res = sqlx::query(&query)
-        .execute(&data.db)
-        .await
-        .map_err(|err: sqlx::Error| err.to_string());
+    let password = hash_password(&body.password).await?;
+    let inserted = data.users.insert(body, &password).await?;
 
-    res
+    if let Insert::Created(id) = inserted {
+        data.observable.notify_event(user_created_event(id, body));
+    }
+
+    Ok(inserted)
 }
 
+// Juga dipakai oleh import massal
+pub fn user_created_event(id: i32, body: &CreateUpdateUserSchema) -> Event {
+    Event::new(
+        "user.created",
+        Some(id),
+        serde_json::json!({ "username": body.username }),
+    )
+    .with_changes(diff_fields(&NO_USER_FIELDS, &schema_fields(body)))
+}
+
+#[tracing::instrument(skip(body))]
 pub async fn update_user_service(
     id: i32,
+    if_match: Option<EntityTags>,
     Json(body): Json<CreateUpdateUserSchema>,
-) -> Result<sqlx::mysql::MySqlQueryResult, String> {
+) -> Result<WriteOutcome, String> {
+    let app_state: Arc<AppState> = APP_STATE.get_or_init(init_app_state).await.clone();
+    let data: State<Arc<AppState>> = State(app_state);
+    let before = match data.users.find(id, false).await? {
+        Some(user) => user,
+        None => return Err(format!("User with id {} does not exist", id)),
+    };
+    if if_match.is_some_and(|tags| !tags.matches(before.version, false)) {
+        return Ok(WriteOutcome::VersionMismatch(before.version));
+    }
+
+    update_validation(id, &body).await?;
+
+    let password = hash_password(&body.password).await?;
+    save_user(&data, before, &body, &password).await
+}
+
+// PATCH: hanya field yang dikirim yang diubah, password lama tetap dipakai bila tidak dikirim
+#[tracing::instrument(skip(body))]
+pub async fn patch_user_service(
+    id: i32,
+    if_match: Option<EntityTags>,
+    body: PatchUserSchema,
+) -> Result<WriteOutcome, String> {
+    let app_state: Arc<AppState> = APP_STATE.get_or_init(init_app_state).await.clone();
+    let data: State<Arc<AppState>> = State(app_state);
+    let before = match data.users.find(id, false).await? {
+        Some(user) => user,
+        None => return Err(format!("User with id {} does not exist", id)),
+    };
+    if if_match.is_some_and(|tags| !tags.matches(before.version, false)) {
+        return Ok(WriteOutcome::VersionMismatch(before.version));
+    }
+
+    patch_validation(id, &body).await?;
+
+    let password = match &body.password {
+        Some(password) => hash_password(password).await?,
+        None => before.password.clone(),
+    };
+    let body = CreateUpdateUserSchema {
+        username: body.username.unwrap_or_else(|| before.username.clone()),
+        email: body.email.unwrap_or_else(|| before.email.clone()),
+        phone: body.phone.unwrap_or_else(|| before.phone.clone()),
+        // Tidak disimpan, yang disimpan adalah hash di atas
+        password: String::new(),
+    };
+    save_user(&data, before, &body, &password).await
+}
+
+// Dipakai PUT dan PATCH; password adalah hash yang disimpan
+async fn save_user(
+    data: &AppState,
+    before: UserModel,
+    body: &CreateUpdateUserSchema,
+    password: &str,
+) -> Result<WriteOutcome, String> {
+    let id = before.id;
+    // Gagal bila ada request lain yang mengubah pengguna sejak dibaca di atas
+    match data
+        .users
+        .update_if_version(id, before.version, body, password)
+        .await?
+    {
+        Update::Done => {}
+        Update::Stale => {
+            return current_version(data, id)
+                .await
+                .map(WriteOutcome::VersionMismatch)
+        }
+        Update::Taken(err) => return Ok(WriteOutcome::Taken(err)),
+    }
+
+    // Email atau nomor telepon baru harus diverifikasi ulang
+    if before.email != body.email {
+        data.users
+            .reset_verification(id, "email_verified_at")
+            .await?;
+    }
+    if before.phone != body.phone {
+        data.users
+            .reset_verification(id, "phone_verified_at")
+            .await?;
+    }
+
+    data.observable.notify_event(
+        Event::new(
+            "user.updated",
+            Some(id),
+            serde_json::json!({ "username": body.username }),
+        )
+        .with_changes(diff_fields(
+            &user_fields(&before),
+            &[
+                ("username", Some(body.username.as_str())),
+                ("email", Some(body.email.as_str())),
+                ("phone", Some(body.phone.as_str())),
+                // PUT selalu menyimpan hash baru; PATCH tanpa password memakai hash lama
+                ("password", Some(password)),
+            ],
+        )),
+    );
+
+    Ok(WriteOutcome::Done(before.version + 1))
+}
+
+async fn current_version(data: &AppState, id: i32) -> Result<i32, String> {
+    data.users
+        .find(id, false)
+        .await?
+        .map(|user| user.version)
+        .ok_or_else(|| format!("User with id {} does not exist", id))
+}
+
+#[tracing::instrument]
+pub async fn delete_user_by_id_service(
+    id: i32,
+    if_match: Option<EntityTags>,
+) -> Result<WriteOutcome, String> {
+    let app_state: Arc<AppState> = APP_STATE.get_or_init(init_app_state).await.clone();
+    let data: State<Arc<AppState>> = State(app_state);
+    let before = match data.users.find(id, false).await? {
+        Some(user) => user,
+        None => return Err(format!("User with id {} does not exist", id)),
+    };
+    if if_match.is_some_and(|tags| !tags.matches(before.version, false)) {
+        return Ok(WriteOutcome::VersionMismatch(before.version));
+    }
+
+    // Soft delete, baris dihapus permanen oleh purge setelah masa retensi
+    let deleted = data
+        .users
+        .soft_delete_if_version(id, before.version)
+        .await?;
+    if !deleted {
+        return current_version(&data, id)
+            .await
+            .map(WriteOutcome::VersionMismatch);
+    }
+
+    data.observable.notify_event(
+        Event::new("user.deleted", Some(id), serde_json::json!({}))
+            .with_changes(diff_fields(&user_fields(&before), &NO_USER_FIELDS)),
+    );
+
+    Ok(WriteOutcome::Done(before.version + 1))
+}
+
+#[tracing::instrument]
+pub async fn restore_user_service(id: i32) -> Result<UserModel, String> {
     let app_state: Arc<AppState> = APP_STATE.get_or_init(init_app_state).await.clone();
     let data: State<Arc<AppState>> = State(app_state);
-    let check_user_query = data.query_builder.select_by_id("users", id as u64);
-    let user_exists = sqlx::query_as::<_, UserModel>(&check_user_query)
-        .fetch_optional(&data.db)
-        .await
-        .map_err(|err: sqlx::Error| err.to_string())?;
-
-    if user_exists.is_none() {
-        return Err(format!("User with id {} does not exist", id));
-    }
-
-    update_validation(&body).await?;
-
-    let password = bcrypt::hash(body.password.to_string(), 10).unwrap();
-    let query = data.query_builder.update(
-        "users",
-        id as u64,
-        &[
-            ("username", &body.username),
-            ("email", &body.email),
-            ("phone", &body.phone),
-            ("password", &password),
-        ],
+    let user = data
+        .users
+        .find(id, true)
+        .await?
+        .ok_or_else(|| format!("User with id {} does not exist", id))?;
+    if user.deleted_at.is_none() {
+        return Err(format!("User with id {} is not deleted", id));
+    }
+
+    data.users.restore(id).await?;
+
+    data.observable.notify_event(
+        Event::new(
+            "user.restored",
+            Some(id),
+            serde_json::json!({ "username": user.username }),
+        )
+        .with_changes(diff_fields(&NO_USER_FIELDS, &user_fields(&user))),
     );
-    let res = sqlx::query(&query)
-        .execute(&data.db)
-        .await
-        .map_err(|err: sqlx::Error| err.to_string());
 
-    res
+    get_user_by_id_service(id).await
 }
 
-pub async fn delete_user_by_id_service(id: i32) -> Result<sqlx::mysql::MySqlQueryResult, String> {
+fn retention_days() -> i64 {
+    std::env::var("USER_RETENTION_DAYS")
+        .ok()
+        .and_then(|v| v.parse().ok())
+        .unwrap_or(30)
+}
+
+// Hapus permanen pengguna yang di-soft delete lebih lama dari USER_RETENTION_DAYS
+#[tracing::instrument]
+pub async fn purge_deleted_users_service() -> Result<usize, String> {
     let app_state: Arc<AppState> = APP_STATE.get_or_init(init_app_state).await.clone();
     let data: State<Arc<AppState>> = State(app_state);
-    let check_user_query = data.query_builder.select_by_id("users", id as u64);
-    let user_exists = sqlx::query_as::<_, UserModel>(&check_user_query)
-        .fetch_optional(&data.db)
-        .await
-        .map_err(|err: sqlx::Error| err.to_string())?;
+    let cutoff = chrono::Utc::now() - chrono::Duration::days(retention_days());
+    let users = data.users.deleted_before(cutoff).await?;
+
+    for user in &users {
+        data.users.delete(user.id).await?;
+
+        data.observable.notify_event(Event::new(
+            "user.purged",
+            Some(user.id),
+            serde_json::json!({ "deleted_at": user.deleted_at }),
+        ));
+    }
 
-    if user_exists.is_none() {
-        return Err(format!("User with id {} does not exist", id));
+    Ok(users.len())
+}
+
+// Dijalankan di background dari main, interval USER_PURGE_INTERVAL_MINUTES
+pub async fn purge_deleted_users_job() {
+    let minutes = std::env::var("USER_PURGE_INTERVAL_MINUTES")
+        .ok()
+        .and_then(|v| v.parse().ok())
+        .unwrap_or(60);
+    let mut interval = tokio::time::interval(std::time::Duration::from_secs(minutes * 60));
+
+    loop {
+        interval.tick().await;
+        match purge_deleted_users_service().await {
+            Ok(0) => {}
+            Ok(count) => tracing::info!(count, "Purged soft-deleted users"),
+            Err(err) => tracing::error!("Failed to purge soft-deleted users: {}", err),
+        }
     }
+}
+
+// Membuka kunci login akun, mengembalikan false bila akun tidak sedang ditahan
+#[tracing::instrument]
+pub async fn unlock_user_service(id: i32) -> Result<bool, String> {
+    get_user_by_id_service(id).await?;
+    let app_state: Arc<AppState> = APP_STATE.get_or_init(init_app_state).await.clone();
+    let throttles = app_state.throttles.as_ref();
+    let unlocked = lockout::reset(throttles, &ThrottleKey::account(Some(id), "")).await?;
+    let mfa_unlocked = lockout::reset(throttles, &ThrottleKey::mfa(id)).await?;
+    let unlocked = unlocked || mfa_unlocked;
 
-    let query = data.query_builder.delete("users", id as u64);
-    let res = sqlx::query(&query)
-        .execute(&data.db)
-        .await
-        .map_err(|err: sqlx::Error| err.to_string());
+    if unlocked {
+        app_state.observable.notify_event(Event::new(
+            "user.unlocked",
+            Some(id),
+            serde_json::json!({}),
+        ));
+    }
+
+    Ok(unlocked)
+}
+
+#[tracing::instrument]
+pub async fn get_user_presence_service(id: i32) -> Result<PresenceStatus, String> {
+    get_user_by_id_service(id).await?;
+    let app_state: Arc<AppState> = APP_STATE.get_or_init(init_app_state).await.clone();
 
-    res
+    Ok(app_state.presence.status(id))
 }
@@ -1,18 +1,11 @@
 use crate::{
-    model::schema::CreateUpdateUserSchema,
+    model::schema::{CreateUpdateUserSchema, PatchUserSchema},
     utils::validation_chain::ValidationChain,
 };
 
-pub async fn create_validation(body: &CreateUpdateUserSchema) -> Result<(), String> {
-
-    let fields_values = [
-        ("username", body.username.as_str()),
-        ("email", body.email.as_str()),
-        ("password", body.password.as_str()),
-        ("phone", body.phone.as_str()),
-    ];
-
-    let mut validation_chain = ValidationChain::new(); // Add 'mut' here
+// Rule body pengguna, juga sumber constraint CreateUpdateUserSchema di dokumen OpenAPI
+pub fn user_validation_chain() -> ValidationChain {
+    let mut validation_chain = ValidationChain::new();
     validation_chain
         .add_rule("username", "required", None)
         .add_rule("username", "min_length", Some("3"))
@@ -21,7 +14,6 @@ pub async fn create_validation(body: &CreateUpdateUserSchema) -> Result<(), Stri
         .add_rule("email", "min_length", Some("5"))
         .add_rule("email", "max_length", Some("50"))
         .add_rule("email", "email", None)
-        .add_rule("email", "unique", None)
         .add_rule("password", "required", None)
         .add_rule("password", "min_length", Some("8"))
         .add_rule("password", "contains", None)
@@ -30,11 +22,42 @@ pub async fn create_validation(body: &CreateUpdateUserSchema) -> Result<(), Stri
         .add_rule("phone", "max_length", Some("15"))
         .add_rule("phone", "phone", None);
     validation_chain
+}
+
+pub fn password_validation_chain() -> ValidationChain {
+    let mut validation_chain = ValidationChain::new();
+    validation_chain
+        .add_rule("password", "required", None)
+        .add_rule("password", "min_length", Some("8"))
+        .add_rule("password", "contains", None);
+    validation_chain
+}
+
+// Kolom dengan UNIQUE index di tabel users; except: id pengguna yang sedang diubah
+fn unique_validation_chain(except: Option<i32>) -> ValidationChain {
+    let except = except.map(|id| id.to_string());
+    let mut validation_chain = user_validation_chain();
+    for field in ["username", "email", "phone"] {
+        validation_chain.add_rule(field, "unique", except.as_deref());
+    }
+    validation_chain
+}
+
+pub async fn create_validation(body: &CreateUpdateUserSchema) -> Result<(), String> {
+
+    let fields_values = [
+        ("username", body.username.as_str()),
+        ("email", body.email.as_str()),
+        ("password", body.password.as_str()),
+        ("phone", body.phone.as_str()),
+    ];
+
+    unique_validation_chain(None)
         .validate_fields_recursive(&fields_values, "users")
         .await
 }
 
-pub async fn update_validation(body: &CreateUpdateUserSchema) -> Result<(), String> {
+pub async fn update_validation(id: i32, body: &CreateUpdateUserSchema) -> Result<(), String> {
 
     let fields_values = [
         ("username", body.username.as_str()),
@@ -43,23 +66,35 @@ pub async fn update_validation(body: &CreateUpdateUserSchema) -> Result<(), Stri
         ("phone", body.phone.as_str()),
     ];
 
-    let mut validation_chain = ValidationChain::new(); // Add 'mut' here
-    validation_chain
-        .add_rule("username", "required", None)
-        .add_rule("username", "min_length", Some("3"))
-        .add_rule("username", "max_length", Some("20"))
-        .add_rule("email", "required", None)
-        .add_rule("email", "min_length", Some("5"))
-        .add_rule("email", "max_length", Some("50"))
-        .add_rule("email", "email", None)
-        .add_rule("password", "required", None)
-        .add_rule("password", "min_length", Some("8"))
-        .add_rule("password", "contains", None)
-        .add_rule("phone", "required", None)
-        .add_rule("phone", "min_length", Some("10"))
-        .add_rule("phone", "max_length", Some("15"))
-        .add_rule("phone", "phone", None);
-    validation_chain
+    unique_validation_chain(Some(id))
+        .validate_fields_recursive(&fields_values, "users")
+        .await
+}
+
+// Hanya field yang dikirim yang divalidasi
+pub async fn patch_validation(id: i32, body: &PatchUserSchema) -> Result<(), String> {
+    let fields_values: Vec<(&str, &str)> = [
+        ("username", &body.username),
+        ("email", &body.email),
+        ("password", &body.password),
+        ("phone", &body.phone),
+    ]
+    .into_iter()
+    .filter_map(|(field, value)| value.as_deref().map(|value| (field, value)))
+    .collect();
+    if fields_values.is_empty() {
+        return Err("At least one of username, email, phone or password is required".to_string());
+    }
+
+    unique_validation_chain(Some(id))
+        .validate_fields_recursive(&fields_values, "users")
+        .await
+}
+
+pub async fn password_validation(password: &str) -> Result<(), String> {
+    let fields_values = [("password", password)];
+
+    password_validation_chain()
         .validate_fields_recursive(&fields_values, "users")
         .await
 }
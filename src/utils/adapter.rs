@@ -9,14 +9,47 @@ pub async fn connection() -> Result<MySqlPool, sqlx::Error> {
         .await
     {
         Ok(pool) => {
-            println!("✅ Connection to the database is successful!");
+            tracing::info!("✅ Connection to the database is successful!");
             pool
         }
         Err(err) => {
-            println!("❌ Failed to connect to the database: {:?}", err);
+            tracing::error!("❌ Failed to connect to the database: {:?}", err);
             std::process::exit(1);
         }
     };
 
     Ok(pool)
-}
\ No newline at end of file
+}
+
+fn statement_table(statement: &str) -> Option<&str> {
+    let words: Vec<&str> = statement.split_whitespace().collect();
+    words
+        .windows(2)
+        .find(|pair| ["FROM", "INTO", "UPDATE"].contains(&pair[0].to_uppercase().as_str()))
+        .map(|pair| pair[1].trim_end_matches(';'))
+}
+
+// Child span for a single SQL statement
+pub fn sql_span(statement: &str) -> tracing::Span {
+    let operation = statement
+        .split_whitespace()
+        .next()
+        .unwrap_or_default()
+        .to_uppercase();
+    let span = tracing::info_span!(
+        "sql",
+        db.system = "mysql",
+        db.operation = %operation,
+        db.sql.table = tracing::field::Empty,
+        db.statement = tracing::field::Empty,
+    );
+    if let Some(table) = statement_table(statement) {
+        span.record("db.sql.table", table);
+    }
+    // insert/update menyisipkan nilai sebagai '...' (hash password, email, telepon),
+    // statement seperti itu hanya dicatat operasi dan tabelnya
+    if !statement.contains('\'') {
+        span.record("db.statement", statement);
+    }
+    span
+}
@@ -0,0 +1,77 @@
+use sqlx::mysql::MySqlPool;
+
+use crate::utils::observer::{Event, Observer};
+
+// Observer yang menyimpan setiap perubahan data pengguna ke tabel audit_log
+pub struct AuditObserver {
+    db: MySqlPool,
+}
+
+impl AuditObserver {
+    pub fn new(db: MySqlPool) -> Self {
+        AuditObserver { db }
+    }
+}
+
+impl Observer for AuditObserver {
+    fn update(&self, _message: &str) {}
+
+    fn on_event(&self, event: &Event) {
+        // Only mutations are audited
+        if !event.topic.starts_with("user.") {
+            return;
+        }
+
+        let db = self.db.clone();
+        let event = event.clone();
+        tokio::spawn(async move {
+            let ctx = event.context.unwrap_or_default();
+            let res = sqlx::query(
+                "INSERT INTO audit_log (actor_id, actor, action, target_user_id, changes, ip, user_agent) \
+                 VALUES (?, ?, ?, ?, ?, ?, ?);",
+            )
+            .bind(ctx.actor.as_ref().map(|actor| actor.id))
+            .bind(ctx.actor.as_ref().map(|actor| actor.username.clone()))
+            .bind(&event.topic)
+            .bind(event.user_id)
+            .bind(event.changes.map(sqlx::types::Json))
+            .bind(ctx.ip)
+            .bind(ctx.user_agent)
+            .execute(&db)
+            .await;
+
+            if let Err(err) = res {
+                tracing::error!(error = ?err, "❌ Failed to write audit log");
+            }
+        });
+    }
+}
+
+// Diff per field, password tidak pernah disimpan
+pub fn diff_fields(
+    before: &[(&str, Option<&str>)],
+    after: &[(&str, Option<&str>)],
+) -> serde_json::Value {
+    let mut changes = serde_json::Map::new();
+    for (field, old) in before {
+        let new = after
+            .iter()
+            .find(|(name, _)| name == field)
+            .and_then(|(_, value)| *value);
+        if *old == new {
+            continue;
+        }
+
+        let redact = |value: Option<&str>| match value {
+            Some(_) if *field == "password" => serde_json::json!("[REDACTED]"),
+            Some(value) => serde_json::json!(value),
+            None => serde_json::Value::Null,
+        };
+        changes.insert(
+            field.to_string(),
+            serde_json::json!({ "before": redact(*old), "after": redact(new) }),
+        );
+    }
+
+    serde_json::Value::Object(changes)
+}
@@ -0,0 +1,375 @@
+use axum::{
+    async_trait,
+    extract::FromRequestParts,
+    http::{header::AUTHORIZATION, request::Parts, HeaderMap, StatusCode},
+    Json,
+};
+use jsonwebtoken::{decode, encode, DecodingKey, EncodingKey, Header, Validation};
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use tokio::sync::OnceCell;
+
+use tracing::Instrument;
+
+use crate::{
+    model::model::{ApiKeyModel, UserModel},
+    utils::{
+        adapter::sql_span,
+        context::RequestContext,
+        simulation::{simulation_config, simulation_enabled},
+        singleton::{init_app_state, AppState},
+        token::hash_token,
+    },
+};
+
+static APP_STATE: OnceCell<Arc<AppState>> = OnceCell::const_new();
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Claims {
+    pub sub: i32,
+    pub username: String,
+    pub role: String,
+    // users.token_version, dinaikkan untuk mencabut semua token lama
+    pub ver: i32,
+    pub iat: i64,
+    pub exp: i64,
+}
+
+// Token sementara antara password dan kode 2FA. Tidak punya username/role
+// sehingga tidak bisa di-decode sebagai Claims biasa.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct MfaClaims {
+    pub sub: i32,
+    pub ver: i32,
+    pub typ: String,
+    pub iat: i64,
+    pub exp: i64,
+}
+
+const MFA_TOKEN_TYPE: &str = "mfa_pending";
+
+// Scope yang dapat diberikan ke API key; "admin" mencakup semuanya
+pub const API_KEY_SCOPES: [&str; 2] = ["admin", "audit:read"];
+
+// Pengguna yang sudah terautentikasi lewat bearer token atau X-Api-Key
+#[derive(Debug, Clone)]
+pub struct AuthUser {
+    pub id: i32,
+    pub username: String,
+    pub role: String,
+    pub token_version: i32,
+    // Diisi oleh authenticate dari users.email_verified_at
+    pub email_verified: bool,
+    // Some bila masuk lewat API key, dibatasi oleh scope key tersebut
+    pub api_key_id: Option<i64>,
+    pub scopes: Option<Vec<String>>,
+}
+
+impl AuthUser {
+    pub fn has_scope(&self, scope: &str) -> bool {
+        match &self.scopes {
+            None => true,
+            Some(scopes) => scopes.iter().any(|s| s == scope || s == "admin"),
+        }
+    }
+
+    // Admin yang, bila lewat API key, juga memegang scope tersebut
+    pub fn is_admin_for(&self, scope: &str) -> bool {
+        self.role == "admin" && self.has_scope(scope)
+    }
+
+    pub fn is_admin(&self) -> bool {
+        self.is_admin_for("admin")
+    }
+}
+
+// Pengguna terautentikasi yang lolos VERIFICATION_POLICY=restrict
+#[derive(Debug, Clone)]
+pub struct VerifiedUser(pub AuthUser);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum VerificationPolicy {
+    // Verifikasi opsional
+    Off,
+    // Login ditolak sampai email terverifikasi
+    Login,
+    // Login boleh, tetapi endpoint yang memakai VerifiedUser ditolak
+    Restrict,
+}
+
+// VERIFICATION_POLICY=off|login|restrict
+pub fn verification_policy() -> VerificationPolicy {
+    dotenv::dotenv().ok();
+    match std::env::var("VERIFICATION_POLICY").as_deref() {
+        Ok("login") => VerificationPolicy::Login,
+        Ok("restrict") => VerificationPolicy::Restrict,
+        _ => VerificationPolicy::Off,
+    }
+}
+
+pub fn jwt_secret() -> String {
+    if simulation_enabled() {
+        return simulation_config().jwt_secret.clone();
+    }
+    dotenv::dotenv().ok();
+    std::env::var("JWT_SECRET").expect("JWT_SECRET must set")
+}
+
+fn jwt_expires_in_minutes() -> i64 {
+    std::env::var("JWT_EXPIRES_IN_MINUTES")
+        .ok()
+        .and_then(|v| v.parse().ok())
+        .unwrap_or(60)
+}
+
+pub fn create_token(user: &UserModel) -> Result<(String, i64), String> {
+    let now = chrono::Utc::now().timestamp();
+    let expires_in = jwt_expires_in_minutes() * 60;
+    let claims = Claims {
+        sub: user.id,
+        username: user.username.clone(),
+        role: user.role.clone(),
+        ver: user.token_version,
+        iat: now,
+        exp: now + expires_in,
+    };
+    let token = encode(
+        &Header::default(),
+        &claims,
+        &EncodingKey::from_secret(jwt_secret().as_bytes()),
+    )
+    .map_err(|err| err.to_string())?;
+
+    Ok((token, expires_in))
+}
+
+fn mfa_token_ttl_seconds() -> i64 {
+    std::env::var("MFA_TOKEN_TTL_SECONDS")
+        .ok()
+        .and_then(|v| v.parse().ok())
+        .unwrap_or(300)
+}
+
+pub fn create_mfa_token(user: &UserModel) -> Result<(String, i64), String> {
+    let now = chrono::Utc::now().timestamp();
+    let expires_in = mfa_token_ttl_seconds();
+    let claims = MfaClaims {
+        sub: user.id,
+        ver: user.token_version,
+        typ: MFA_TOKEN_TYPE.to_string(),
+        iat: now,
+        exp: now + expires_in,
+    };
+    let token = encode(
+        &Header::default(),
+        &claims,
+        &EncodingKey::from_secret(jwt_secret().as_bytes()),
+    )
+    .map_err(|err| err.to_string())?;
+
+    Ok((token, expires_in))
+}
+
+pub fn verify_mfa_token(token: &str) -> Result<MfaClaims, String> {
+    let data = decode::<MfaClaims>(
+        token,
+        &DecodingKey::from_secret(jwt_secret().as_bytes()),
+        &Validation::default(),
+    )
+    .map_err(|_| "Invalid or expired MFA token".to_string())?;
+    if data.claims.typ != MFA_TOKEN_TYPE {
+        return Err("Invalid or expired MFA token".to_string());
+    }
+
+    Ok(data.claims)
+}
+
+pub fn verify_token(token: &str) -> Result<AuthUser, String> {
+    let data = decode::<Claims>(
+        token,
+        &DecodingKey::from_secret(jwt_secret().as_bytes()),
+        &Validation::default(),
+    )
+    .map_err(|_| "Invalid or expired token".to_string())?;
+
+    Ok(AuthUser {
+        id: data.claims.sub,
+        username: data.claims.username,
+        role: data.claims.role,
+        token_version: data.claims.ver,
+        email_verified: false,
+        api_key_id: None,
+        scopes: None,
+    })
+}
+
+// Like verify_token, but also rejects tokens revoked by a password reset
+pub async fn authenticate(token: &str) -> Result<AuthUser, String> {
+    let user = verify_token(token)?;
+    let app_state: Arc<AppState> = APP_STATE.get_or_init(init_app_state).await.clone();
+    let current = app_state.users.find(user.id, false).await?;
+
+    match current {
+        // Role dari database, jadi perubahan role langsung berlaku tanpa menunggu token baru
+        Some(current) if current.token_version == user.token_version => Ok(AuthUser {
+            username: current.username,
+            role: current.role,
+            email_verified: current.email_verified_at.is_some(),
+            ..user
+        }),
+        _ => Err("Invalid or expired token".to_string()),
+    }
+}
+
+// Key berbentuk simu_<prefix>_<secret>, yang disimpan hanya prefix dan hash-nya
+pub async fn authenticate_api_key(key: &str) -> Result<AuthUser, String> {
+    let invalid = || "Invalid or expired API key".to_string();
+    let app_state: Arc<AppState> = APP_STATE.get_or_init(init_app_state).await.clone();
+    let query = app_state
+        .query_builder
+        .select_by_field("api_keys", "key_hash");
+    let api_key = sqlx::query_as::<_, ApiKeyModel>(&query)
+        .bind(hash_token(key.trim()))
+        .fetch_optional(&app_state.db)
+        .instrument(sql_span(&query))
+        .await
+        .map_err(|err: sqlx::Error| err.to_string())?
+        .ok_or_else(invalid)?;
+
+    let now = chrono::Utc::now();
+    if api_key.revoked_at.is_some() || api_key.expires_at.is_some_and(|at| at <= now) {
+        return Err(invalid());
+    }
+
+    let owner = app_state
+        .users
+        .find(api_key.user_id, false)
+        .await?
+        .ok_or_else(invalid)?;
+
+    let touch_query = "UPDATE api_keys SET last_used_at = ? WHERE id = ?;";
+    sqlx::query(touch_query)
+        .bind(now)
+        .bind(api_key.id)
+        .execute(&app_state.db)
+        .instrument(sql_span(touch_query))
+        .await
+        .map_err(|err: sqlx::Error| err.to_string())?;
+
+    Ok(AuthUser {
+        id: owner.id,
+        username: owner.username,
+        role: owner.role,
+        token_version: owner.token_version,
+        email_verified: owner.email_verified_at.is_some(),
+        api_key_id: Some(api_key.id),
+        scopes: Some(api_key.scopes.0),
+    })
+}
+
+pub fn api_key(headers: &HeaderMap) -> Option<&str> {
+    headers
+        .get("x-api-key")
+        .and_then(|value| value.to_str().ok())
+        .map(str::trim)
+        .filter(|value| !value.is_empty())
+}
+
+// "simu_0a1b2c3d_..." -> "0a1b2c3d", tanpa memeriksa apakah key tersebut ada
+pub fn api_key_prefix(key: &str) -> Option<&str> {
+    let (prefix, secret) = key.strip_prefix("simu_")?.split_once('_')?;
+    let valid = prefix.len() == 8 && prefix.chars().all(|c| c.is_ascii_hexdigit());
+    (valid && !secret.is_empty()).then_some(prefix)
+}
+
+pub fn bearer_token(headers: &HeaderMap) -> Option<&str> {
+    headers
+        .get(AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Bearer "))
+        .map(str::trim)
+}
+
+fn error_response(status: StatusCode, message: &str) -> (StatusCode, Json<serde_json::Value>) {
+    let json_response = serde_json::json!({
+        "status": "error",
+        "message": message
+    });
+
+    (status, Json(json_response))
+}
+
+pub fn forbidden() -> (StatusCode, Json<serde_json::Value>) {
+    error_response(StatusCode::FORBIDDEN, "Admin privileges required")
+}
+
+pub fn unverified() -> (StatusCode, Json<serde_json::Value>) {
+    error_response(StatusCode::FORBIDDEN, "Email address is not verified")
+}
+
+pub fn require_verified(user: &AuthUser) -> Result<(), (StatusCode, Json<serde_json::Value>)> {
+    if verification_policy() == VerificationPolicy::Restrict && !user.email_verified {
+        return Err(unverified());
+    }
+    Ok(())
+}
+
+#[async_trait]
+impl<S> FromRequestParts<S> for AuthUser
+where
+    S: Send + Sync,
+{
+    type Rejection = (StatusCode, Json<serde_json::Value>);
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+        let res = match (bearer_token(&parts.headers), api_key(&parts.headers)) {
+            (Some(token), _) => authenticate(token).await,
+            // rate_limit sudah memverifikasi key ini untuk request yang sama
+            (None, Some(key)) => match RequestContext::current()
+                .and_then(|ctx| ctx.actor)
+                .filter(|actor| actor.api_key_id.is_some())
+            {
+                Some(actor) => Ok(actor),
+                None => authenticate_api_key(key).await,
+            },
+            (None, None) => Err("Missing bearer token or API key".to_string()),
+        };
+
+        res.map_err(|err| error_response(StatusCode::UNAUTHORIZED, &err))
+    }
+}
+
+#[async_trait]
+impl<S> FromRequestParts<S> for VerifiedUser
+where
+    S: Send + Sync,
+{
+    type Rejection = (StatusCode, Json<serde_json::Value>);
+
+    async fn from_request_parts(parts: &mut Parts, state: &S) -> Result<Self, Self::Rejection> {
+        let user = AuthUser::from_request_parts(parts, state).await?;
+        require_verified(&user)?;
+        Ok(VerifiedUser(user))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn authenticate_uses_current_role() {
+        let data = crate::service::simulation::simulation_service::init_simulation_app_state();
+        let mut user = data.users.find(2, false).await.unwrap().unwrap();
+        assert_eq!(user.role, "user");
+
+        // Token dengan role lama (mis. sebelum diturunkan dari admin)
+        user.role = "admin".to_string();
+        let (token, _) = create_token(&user).unwrap();
+        let auth = authenticate(&token).await.unwrap();
+
+        assert_eq!(auth.id, 2);
+        assert_eq!(auth.role, "user");
+        assert!(!auth.is_admin());
+    }
+}
@@ -0,0 +1,223 @@
+use std::{
+    net::{IpAddr, SocketAddr},
+    sync::LazyLock,
+};
+
+use axum::{
+    extract::{ConnectInfo, Request},
+    http::{header::USER_AGENT, HeaderMap},
+    middleware::Next,
+    response::Response,
+};
+
+use crate::utils::auth::{bearer_token, verify_token, AuthUser};
+
+// Informasi request yang ikut dibawa oleh event, mis. untuk audit log
+#[derive(Debug, Clone, Default)]
+pub struct RequestContext {
+    pub request_id: Option<String>,
+    pub ip: Option<String>,
+    pub user_agent: Option<String>,
+    pub actor: Option<AuthUser>,
+}
+
+tokio::task_local! {
+    static REQUEST_CONTEXT: RequestContext;
+}
+
+impl RequestContext {
+    // None outside of a request, e.g. in a WebSocket task
+    pub fn current() -> Option<RequestContext> {
+        REQUEST_CONTEXT.try_with(|ctx| ctx.clone()).ok()
+    }
+
+    // Identitas pemanggil untuk rate limit dan idempotency: API key, pengguna, lalu IP
+    pub fn client_id(&self) -> String {
+        match (&self.actor, &self.ip) {
+            (Some(actor), _) => match actor.api_key_id {
+                Some(id) => format!("key:{}", id),
+                None => format!("user:{}", actor.id),
+            },
+            (None, Some(ip)) => format!("ip:{}", ip),
+            (None, None) => "anonymous".to_string(),
+        }
+    }
+
+    // Menjalankan future dengan context ini, mis. background job yang dimulai dari request
+    pub async fn scope<F: std::future::Future>(self, f: F) -> F::Output {
+        REQUEST_CONTEXT.scope(self, f).await
+    }
+}
+
+// Alamat atau jaringan proxy, mis. "10.0.0.1" atau "10.0.0.0/8"
+#[derive(Debug, Clone, PartialEq)]
+struct TrustedProxy {
+    network: IpAddr,
+    prefix: u32,
+}
+
+impl TrustedProxy {
+    fn parse(value: &str) -> Option<Self> {
+        let (addr, prefix) = match value.split_once('/') {
+            Some((addr, prefix)) => (addr, Some(prefix)),
+            None => (value, None),
+        };
+        let network: IpAddr = addr.trim().parse().ok()?;
+        let max = if network.is_ipv4() { 32 } else { 128 };
+        let prefix = match prefix {
+            Some(prefix) => prefix.trim().parse().ok().filter(|p| *p <= max)?,
+            None => max,
+        };
+        Some(TrustedProxy { network, prefix })
+    }
+
+    fn contains(&self, ip: IpAddr) -> bool {
+        let ip = match ip {
+            IpAddr::V6(v6) => v6.to_ipv4_mapped().map(IpAddr::V4).unwrap_or(ip),
+            ip => ip,
+        };
+        match (self.network, ip) {
+            (IpAddr::V4(net), IpAddr::V4(ip)) => {
+                let mask = u32::MAX.checked_shl(32 - self.prefix).unwrap_or(0);
+                u32::from(net) & mask == u32::from(ip) & mask
+            }
+            (IpAddr::V6(net), IpAddr::V6(ip)) => {
+                let mask = u128::MAX.checked_shl(128 - self.prefix).unwrap_or(0);
+                u128::from(net) & mask == u128::from(ip) & mask
+            }
+            _ => false,
+        }
+    }
+}
+
+// TRUSTED_PROXIES dipisah koma, kosong berarti X-Forwarded-For diabaikan
+static TRUSTED_PROXIES: LazyLock<Vec<TrustedProxy>> = LazyLock::new(|| {
+    dotenv::dotenv().ok();
+    std::env::var("TRUSTED_PROXIES")
+        .unwrap_or_default()
+        .split(',')
+        .map(str::trim)
+        .filter(|value| !value.is_empty())
+        .filter_map(|value| {
+            let proxy = TrustedProxy::parse(value);
+            if proxy.is_none() {
+                tracing::warn!("Ignoring invalid TRUSTED_PROXIES entry {}", value);
+            }
+            proxy
+        })
+        .collect()
+});
+
+// X-Forwarded-For hanya dipercaya bila koneksi datang dari proxy yang dikenal, lalu
+// dibaca dari kanan sampai alamat pertama yang bukan proxy
+fn client_ip_from(
+    headers: &HeaderMap,
+    remote: Option<SocketAddr>,
+    trusted: &[TrustedProxy],
+) -> Option<String> {
+    let is_trusted = |ip: IpAddr| trusted.iter().any(|proxy| proxy.contains(ip));
+    let remote = remote.map(|addr| addr.ip())?;
+    if !is_trusted(remote) {
+        return Some(remote.to_string());
+    }
+
+    let forwarded: Vec<IpAddr> = headers
+        .get_all("x-forwarded-for")
+        .iter()
+        .filter_map(|value| value.to_str().ok())
+        .flat_map(|value| value.split(','))
+        .filter_map(|value| value.trim().parse().ok())
+        .collect();
+    let client = forwarded
+        .iter()
+        .rev()
+        .find(|ip| !is_trusted(**ip))
+        .or(forwarded.first())
+        .copied()
+        .unwrap_or(remote);
+
+    Some(client.to_string())
+}
+
+fn client_ip(headers: &HeaderMap, remote: Option<SocketAddr>) -> Option<String> {
+    client_ip_from(headers, remote, &TRUSTED_PROXIES)
+}
+
+pub async fn request_context(req: Request, next: Next) -> Response {
+    let remote = req
+        .extensions()
+        .get::<ConnectInfo<SocketAddr>>()
+        .map(|ConnectInfo(addr)| *addr);
+    let headers = req.headers();
+
+    let ctx = RequestContext {
+        // Set by SetRequestIdLayer before this middleware runs
+        request_id: headers
+            .get("x-request-id")
+            .and_then(|value| value.to_str().ok())
+            .map(str::to_string),
+        ip: client_ip(headers, remote),
+        user_agent: headers
+            .get(USER_AGENT)
+            .and_then(|value| value.to_str().ok())
+            .map(str::to_string),
+        // Authentication is enforced by the handlers, this only records who is calling
+        actor: bearer_token(headers).and_then(|token| verify_token(token).ok()),
+    };
+
+    REQUEST_CONTEXT.scope(ctx, next.run(req)).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn headers(xff: &str) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        headers.insert("x-forwarded-for", xff.parse().unwrap());
+        headers
+    }
+
+    fn remote(ip: &str) -> Option<SocketAddr> {
+        Some(SocketAddr::new(ip.parse().unwrap(), 40000))
+    }
+
+    #[test]
+    fn forwarded_for_is_ignored_from_untrusted_peer() {
+        let trusted = [TrustedProxy::parse("10.0.0.0/8").unwrap()];
+        let ip = client_ip_from(&headers("1.2.3.4"), remote("203.0.113.9"), &trusted);
+        assert_eq!(ip.as_deref(), Some("203.0.113.9"));
+
+        let ip = client_ip_from(&headers("1.2.3.4"), remote("10.0.0.2"), &[]);
+        assert_eq!(ip.as_deref(), Some("10.0.0.2"));
+    }
+
+    #[test]
+    fn forwarded_for_is_read_from_the_right() {
+        let trusted = [
+            TrustedProxy::parse("10.0.0.0/8").unwrap(),
+            TrustedProxy::parse("192.168.1.5").unwrap(),
+        ];
+        // Nilai paling kiri bisa dikarang oleh klien
+        let ip = client_ip_from(
+            &headers("1.2.3.4, 198.51.100.7, 192.168.1.5"),
+            remote("10.0.0.2"),
+            &trusted,
+        );
+        assert_eq!(ip.as_deref(), Some("198.51.100.7"));
+
+        let ip = client_ip_from(&HeaderMap::new(), remote("10.0.0.2"), &trusted);
+        assert_eq!(ip.as_deref(), Some("10.0.0.2"));
+    }
+
+    #[test]
+    fn trusted_proxy_parsing() {
+        assert!(TrustedProxy::parse("10.0.0.0/33").is_none());
+        assert!(TrustedProxy::parse("proxy.local").is_none());
+        let v6 = TrustedProxy::parse("fd00::/8").unwrap();
+        assert!(v6.contains("fd12::1".parse().unwrap()));
+        assert!(!v6.contains("10.0.0.1".parse().unwrap()));
+        let v4 = TrustedProxy::parse("127.0.0.1").unwrap();
+        assert!(v4.contains("::ffff:127.0.0.1".parse().unwrap()));
+    }
+}
@@ -0,0 +1,45 @@
+use aes_gcm::{
+    aead::{Aead, AeadCore, KeyInit, OsRng},
+    Aes256Gcm, Key, Nonce,
+};
+
+// Nonce AES-GCM 96 bit
+const NONCE_LEN: usize = 12;
+
+// SECRET_ENCRYPTION_KEY berisi 32 byte dalam hex (64 karakter)
+fn cipher() -> Result<Aes256Gcm, String> {
+    dotenv::dotenv().ok();
+    let key = std::env::var("SECRET_ENCRYPTION_KEY")
+        .map_err(|_| "SECRET_ENCRYPTION_KEY must set".to_string())?;
+    let key = hex::decode(key.trim()).map_err(|err| err.to_string())?;
+    if key.len() != 32 {
+        return Err("SECRET_ENCRYPTION_KEY must be 32 bytes of hex".to_string());
+    }
+
+    Ok(Aes256Gcm::new(Key::<Aes256Gcm>::from_slice(&key)))
+}
+
+// Hasil berupa hex(nonce || ciphertext)
+pub fn encrypt(plaintext: &str) -> Result<String, String> {
+    let nonce = Aes256Gcm::generate_nonce(&mut OsRng);
+    let ciphertext = cipher()?
+        .encrypt(&nonce, plaintext.as_bytes())
+        .map_err(|err| err.to_string())?;
+
+    let mut out = nonce.to_vec();
+    out.extend_from_slice(&ciphertext);
+    Ok(hex::encode(out))
+}
+
+pub fn decrypt(value: &str) -> Result<String, String> {
+    let bytes = hex::decode(value).map_err(|err| err.to_string())?;
+    if bytes.len() <= NONCE_LEN {
+        return Err("Invalid encrypted value".to_string());
+    }
+
+    let (nonce, ciphertext) = bytes.split_at(NONCE_LEN);
+    let plaintext = cipher()?
+        .decrypt(Nonce::from_slice(nonce), ciphertext)
+        .map_err(|_| "Invalid encrypted value".to_string())?;
+    String::from_utf8(plaintext).map_err(|err| err.to_string())
+}
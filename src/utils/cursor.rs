@@ -0,0 +1,161 @@
+use serde::{Deserialize, Serialize};
+
+// Kolom yang boleh dipakai untuk sort, id selalu menjadi penentu urutan kedua
+pub const SORT_COLUMNS: [&str; 5] = ["id", "username", "email", "created_at", "updated_at"];
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Direction {
+    Next,
+    Prev,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Sort {
+    pub column: &'static str,
+    pub descending: bool,
+}
+
+impl Sort {
+    // "created_at" naik, "-created_at" turun
+    pub fn parse(value: Option<&str>) -> Result<Self, String> {
+        let value = value.unwrap_or("created_at").trim();
+        let (name, descending) = match value.strip_prefix('-') {
+            Some(name) => (name, true),
+            None => (value, false),
+        };
+        let column = SORT_COLUMNS
+            .iter()
+            .find(|column| **column == name)
+            .copied()
+            .ok_or_else(|| {
+                format!(
+                    "Unknown sort '{}', allowed: {}",
+                    name,
+                    SORT_COLUMNS.join(", ")
+                )
+            })?;
+
+        Ok(Sort { column, descending })
+    }
+
+    pub fn as_param(&self) -> String {
+        if self.descending {
+            format!("-{}", self.column)
+        } else {
+            self.column.to_string()
+        }
+    }
+}
+
+// Isi cursor: posisi baris terakhir yang sudah dilihat klien
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Cursor {
+    pub sort: String,
+    pub value: serde_json::Value,
+    pub id: i64,
+    pub direction: Direction,
+}
+
+impl Cursor {
+    // Opaque bagi klien, hanya hex dari JSON
+    pub fn encode(&self) -> String {
+        hex::encode(serde_json::to_vec(self).unwrap_or_default())
+    }
+
+    pub fn decode(value: &str) -> Result<Self, String> {
+        let invalid = || "Invalid pagination cursor".to_string();
+        let bytes = hex::decode(value.trim()).map_err(|_| invalid())?;
+        serde_json::from_slice(&bytes).map_err(|_| invalid())
+    }
+
+    pub fn sort(&self) -> Result<Sort, String> {
+        Sort::parse(Some(&self.sort))
+    }
+}
+
+// Header Link (RFC 8288) untuk halaman berikut dan sebelumnya
+pub fn link_header(
+    path: &str,
+    params: &[(&str, String)],
+    next: Option<&str>,
+    prev: Option<&str>,
+) -> Option<String> {
+    let link = |cursor: &str, rel: &str| {
+        let mut query: Vec<String> = params
+            .iter()
+            .map(|(key, value)| format!("{}={}", key, value))
+            .collect();
+        query.push(format!("cursor={}", cursor));
+        format!("<{}?{}>; rel=\"{}\"", path, query.join("&"), rel)
+    };
+
+    let links: Vec<String> = next
+        .map(|cursor| link(cursor, "next"))
+        .into_iter()
+        .chain(prev.map(|cursor| link(cursor, "prev")))
+        .collect();
+    (!links.is_empty()).then(|| links.join(", "))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sort_parse() {
+        assert_eq!(
+            Sort::parse(None),
+            Ok(Sort {
+                column: "created_at",
+                descending: false
+            })
+        );
+        let sort = Sort::parse(Some(" -username ")).unwrap();
+        assert_eq!(sort.column, "username");
+        assert!(sort.descending);
+        assert_eq!(sort.as_param(), "-username");
+        assert!(Sort::parse(Some("password")).is_err());
+        assert!(Sort::parse(Some("--id")).is_err());
+    }
+
+    #[test]
+    fn cursor_round_trip() {
+        let cursor = Cursor {
+            sort: "-created_at".to_string(),
+            value: serde_json::json!("2024-06-01T00:00:00Z"),
+            id: 42,
+            direction: Direction::Prev,
+        };
+        let encoded = cursor.encode();
+        assert!(encoded.chars().all(|c| c.is_ascii_hexdigit()));
+        assert_eq!(Cursor::decode(&encoded), Ok(cursor.clone()));
+        assert_eq!(
+            cursor.sort(),
+            Ok(Sort {
+                column: "created_at",
+                descending: true
+            })
+        );
+
+        assert!(Cursor::decode("not-hex").is_err());
+        assert!(Cursor::decode(&hex::encode("{}")).is_err());
+    }
+
+    #[test]
+    fn link_header_rels() {
+        let params = [("limit", "20".to_string()), ("sort", "-id".to_string())];
+        assert_eq!(link_header("/api/v1/user", &params, None, None), None);
+        assert_eq!(
+            link_header("/api/v1/user", &params, Some("ab"), Some("cd")).as_deref(),
+            Some(
+                "</api/v1/user?limit=20&sort=-id&cursor=ab>; rel=\"next\", \
+                 </api/v1/user?limit=20&sort=-id&cursor=cd>; rel=\"prev\""
+            )
+        );
+        assert_eq!(
+            link_header("/api/v2/user", &[], None, Some("cd")).as_deref(),
+            Some("</api/v2/user?cursor=cd>; rel=\"prev\"")
+        );
+    }
+}
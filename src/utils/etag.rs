@@ -0,0 +1,103 @@
+use axum::http::{HeaderMap, HeaderName, HeaderValue};
+
+// ETag diturunkan dari kolom version, mis. "3"
+pub fn etag(version: i32) -> HeaderValue {
+    HeaderValue::from_str(&format!("\"{}\"", version)).unwrap()
+}
+
+// Isi header If-Match / If-None-Match: "*" atau daftar ETag
+#[derive(Debug, Clone, PartialEq)]
+pub enum EntityTags {
+    Any,
+    List(Vec<String>),
+}
+
+impl EntityTags {
+    pub fn from_headers(headers: &HeaderMap, name: HeaderName) -> Option<Self> {
+        let values: Vec<&str> = headers
+            .get_all(name)
+            .iter()
+            .filter_map(|value| value.to_str().ok())
+            .collect();
+        if values.is_empty() {
+            return None;
+        }
+        if values.iter().any(|value| value.trim() == "*") {
+            return Some(EntityTags::Any);
+        }
+
+        let tags = values
+            .iter()
+            .flat_map(|value| value.split(','))
+            .map(|tag| tag.trim().to_string())
+            .filter(|tag| !tag.is_empty())
+            .collect();
+        Some(EntityTags::List(tags))
+    }
+
+    // If-Match memakai perbandingan strong, If-None-Match perbandingan weak
+    pub fn matches(&self, version: i32, weak: bool) -> bool {
+        let current = format!("\"{}\"", version);
+        match self {
+            EntityTags::Any => true,
+            EntityTags::List(tags) => tags.iter().any(|tag| match tag.strip_prefix("W/") {
+                Some(tag) => weak && *tag == current,
+                None => *tag == current,
+            }),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::http::header::{IF_MATCH, IF_NONE_MATCH};
+
+    fn headers(name: HeaderName, values: &[&str]) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        for value in values {
+            headers.append(name.clone(), value.parse().unwrap());
+        }
+        headers
+    }
+
+    #[test]
+    fn from_headers() {
+        assert_eq!(EntityTags::from_headers(&HeaderMap::new(), IF_MATCH), None);
+        assert_eq!(
+            EntityTags::from_headers(&headers(IF_MATCH, &[" * "]), IF_MATCH),
+            Some(EntityTags::Any)
+        );
+        // Beberapa header dan daftar dipisah koma digabung
+        assert_eq!(
+            EntityTags::from_headers(
+                &headers(IF_NONE_MATCH, &["\"1\", W/\"2\"", "\"3\","]),
+                IF_NONE_MATCH
+            ),
+            Some(EntityTags::List(vec![
+                "\"1\"".to_string(),
+                "W/\"2\"".to_string(),
+                "\"3\"".to_string(),
+            ]))
+        );
+        assert_eq!(
+            EntityTags::from_headers(&headers(IF_MATCH, &["\"1\""]), IF_NONE_MATCH),
+            None
+        );
+    }
+
+    #[test]
+    fn matches() {
+        assert!(EntityTags::Any.matches(7, false));
+
+        let tags = EntityTags::List(vec!["\"1\"".to_string(), "W/\"2\"".to_string()]);
+        assert!(tags.matches(1, false));
+        assert!(tags.matches(1, true));
+        // ETag weak hanya cocok pada perbandingan weak
+        assert!(!tags.matches(2, false));
+        assert!(tags.matches(2, true));
+        assert!(!tags.matches(3, true));
+        assert!(!EntityTags::List(vec!["1".to_string()]).matches(1, true));
+        assert_eq!(etag(4), HeaderValue::from_static("\"4\""));
+    }
+}
@@ -0,0 +1,37 @@
+use tokio::sync::broadcast;
+
+use crate::utils::observer::{Event, Observer};
+
+// Observer yang meneruskan event ke subscriber realtime (WebSocket)
+pub struct EventHub {
+    sender: broadcast::Sender<Event>,
+}
+
+impl EventHub {
+    pub fn new(capacity: usize) -> Self {
+        let (sender, _) = broadcast::channel(capacity);
+        EventHub { sender }
+    }
+
+    pub fn subscribe(&self) -> broadcast::Receiver<Event> {
+        self.sender.subscribe()
+    }
+}
+
+impl Observer for EventHub {
+    // Plain messages have no topic, only structured events are broadcast
+    fn update(&self, _message: &str) {}
+
+    fn on_event(&self, event: &Event) {
+        // No receivers is not an error, nobody is listening yet
+        let _ = self.sender.send(event.clone());
+    }
+}
+
+// "user.*" matches every topic starting with "user.", "*" matches everything
+pub fn topic_matches(pattern: &str, topic: &str) -> bool {
+    match pattern.strip_suffix('*') {
+        Some(prefix) => topic.starts_with(prefix),
+        None => pattern == topic,
+    }
+}
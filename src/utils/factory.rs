@@ -1,51 +1,464 @@
 use std::sync::Arc;
 
+use crate::utils::cursor::Sort;
+
+// Query select mengabaikan baris yang sudah di-soft delete pada tabel
+// yang punya kolom deleted_at; gunakan varian *_with_deleted atau
+// include_deleted untuk melihatnya.
 pub trait QueryBuilder: Send + Sync {
-    fn insert(&self, table: &str, fields: &[&str], values: &[&str]) -> String;
     fn select_all(&self, table: &str) -> String;
+    fn select_all_with_deleted(&self, table: &str) -> String;
     fn select_by_id(&self, table: &str, id: u64) -> String;
+    fn select_by_id_with_deleted(&self, table: &str, id: u64) -> String;
+    fn select_by_field(&self, table: &str, field: &str) -> String;
+    fn select_where(
+        &self,
+        table: &str,
+        conditions: &[&str],
+        include_deleted: bool,
+        order_by: &str,
+        limit: usize,
+        offset: usize,
+    ) -> String;
+    // Keyset pagination diurutkan (sort.column, id); bila after_cursor, hanya baris
+    // setelah cursor dengan parameter ? berurutan: nilai column, id
+    fn select_keyset(
+        &self,
+        table: &str,
+        conditions: &[&str],
+        include_deleted: bool,
+        sort: &Sort,
+        after_cursor: bool,
+        limit: usize,
+    ) -> String;
+    // Nilai dikirim lewat parameter ? sesuai urutan fields
+    fn insert_bind(&self, table: &str, fields: &[&str]) -> String;
     fn update(&self, table: &str, id: u64, fields: &[(&str, &str)]) -> String;
+    fn update_bind(&self, table: &str, id: u64, fields: &[&str]) -> String;
+    // Hanya berhasil bila version masih sama, lalu version dinaikkan; parameter ?
+    // berurutan: nilai fields, lalu version
+    fn update_if_version(&self, table: &str, id: u64, fields: &[&str]) -> String;
     fn delete(&self, table: &str, id: u64) -> String;
+    fn soft_delete_if_version(&self, table: &str, id: u64, version: i32) -> String;
+    fn restore(&self, table: &str, id: u64) -> String;
+    // Baris yang di-soft delete sebelum batas waktu (parameter ?)
+    fn select_deleted_before(&self, table: &str) -> String;
+    // Implementasi default memakai LIKE sehingga juga berjalan di database tanpa FULLTEXT;
+    // trigrams adalah jumlah potongan 3 huruf untuk kandidat yang salah ketik
+    fn select_search(
+        &self,
+        table: &str,
+        text_columns: &[&str],
+        digits_column: &str,
+        trigrams: usize,
+        limit: usize,
+    ) -> SearchQuery {
+        let mut conditions: Vec<String> = text_columns
+            .iter()
+            .map(|column| format!("{} LIKE ?", quote_identifier(column)))
+            .collect();
+        let mut params = vec![SearchParam::TextContains; text_columns.len()];
+        trigram_conditions(text_columns, trigrams, &mut conditions, &mut params);
+        conditions.push(format!("{} LIKE ?", quote_identifier(digits_column)));
+        params.push(SearchParam::Digits);
+
+        let search = format!("({})", conditions.join(" OR "));
+        SearchQuery {
+            sql: format!(
+                "SELECT * FROM {}{} ORDER BY id LIMIT {};",
+                quote_identifier(table),
+                where_clause(table, &[&search], false),
+                limit
+            ),
+            params,
+        }
+    }
+}
+
+// Nilai untuk setiap parameter ? pada query pencarian, sesuai urutannya
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SearchParam {
+    // Ekspresi FULLTEXT boolean mode, mis. "+john* +gmail*"
+    FullText,
+    // "teks%"
+    TextPrefix,
+    // "%teks%"
+    TextContains,
+    // "%abc%" untuk potongan 3 huruf ke-n dari kata kunci
+    Trigram(usize),
+    // "%angka%", NULL bila kata kunci tidak berisi cukup angka
+    Digits,
+}
+
+pub struct SearchQuery {
+    pub sql: String,
+    pub params: Vec<SearchParam>,
+}
+
+fn trigram_conditions(
+    text_columns: &[&str],
+    trigrams: usize,
+    conditions: &mut Vec<String>,
+    params: &mut Vec<SearchParam>,
+) {
+    for trigram in 0..trigrams {
+        for column in text_columns {
+            conditions.push(format!("{} LIKE ?", quote_identifier(column)));
+            params.push(SearchParam::Trigram(trigram));
+        }
+    }
 }
 
 struct MySQLQueryBuilder;
 
+// Tabel yang punya kolom deleted_at
+const SOFT_DELETE_TABLES: [&str; 1] = ["users"];
+
+fn live_condition(table: &str) -> Option<&'static str> {
+    SOFT_DELETE_TABLES
+        .contains(&table)
+        .then_some("deleted_at IS NULL")
+}
+
+fn where_clause(table: &str, conditions: &[&str], include_deleted: bool) -> String {
+    let mut conditions: Vec<&str> = conditions.to_vec();
+    if let Some(live) = live_condition(table).filter(|_| !include_deleted) {
+        conditions.push(live);
+    }
+
+    if conditions.is_empty() {
+        String::new()
+    } else {
+        format!(" WHERE {}", conditions.join(" AND "))
+    }
+}
+
+// Nama tabel/kolom dari file schema resource, mis. `order`
+pub fn quote_identifier(name: &str) -> String {
+    format!("`{}`", name.replace('`', "``"))
+}
+
 pub fn new_mysql_query_builder() -> Arc<dyn QueryBuilder> {
     Arc::new(MySQLQueryBuilder)
 }
 
 impl QueryBuilder for MySQLQueryBuilder {
     fn select_all(&self, table: &str) -> String {
-        format!("SELECT * FROM {};", table)
+        match live_condition(table) {
+            Some(live) => format!("SELECT * FROM {} WHERE {};", quote_identifier(table), live),
+            None => self.select_all_with_deleted(table),
+        }
+    }
+
+    fn select_all_with_deleted(&self, table: &str) -> String {
+        format!("SELECT * FROM {};", quote_identifier(table))
     }
 
     fn select_by_id(&self, table: &str, id: u64) -> String {
-        format!("SELECT * FROM {} WHERE id = {};", table, id)
+        match live_condition(table) {
+            Some(live) => format!(
+                "SELECT * FROM {} WHERE id = {} AND {};",
+                quote_identifier(table),
+                id,
+                live
+            ),
+            None => self.select_by_id_with_deleted(table, id),
+        }
+    }
+
+    fn select_by_id_with_deleted(&self, table: &str, id: u64) -> String {
+        format!(
+            "SELECT * FROM {} WHERE id = {};",
+            quote_identifier(table),
+            id
+        )
+    }
+
+    fn select_by_field(&self, table: &str, field: &str) -> String {
+        let condition = format!("{} = ?", quote_identifier(field));
+        format!(
+            "SELECT * FROM {}{};",
+            quote_identifier(table),
+            where_clause(table, &[&condition], false)
+        )
+    }
+
+    fn select_where(
+        &self,
+        table: &str,
+        conditions: &[&str],
+        include_deleted: bool,
+        order_by: &str,
+        limit: usize,
+        offset: usize,
+    ) -> String {
+        format!(
+            "SELECT * FROM {}{} ORDER BY {} LIMIT {} OFFSET {};",
+            quote_identifier(table),
+            where_clause(table, conditions, include_deleted),
+            order_by,
+            limit,
+            offset
+        )
+    }
+
+    fn select_keyset(
+        &self,
+        table: &str,
+        conditions: &[&str],
+        include_deleted: bool,
+        sort: &Sort,
+        after_cursor: bool,
+        limit: usize,
+    ) -> String {
+        let column = quote_identifier(sort.column);
+        let (op, direction) = if sort.descending {
+            ("<", "DESC")
+        } else {
+            (">", "ASC")
+        };
+        // Row comparison agar index (column, id) bisa dipakai sebagai range scan
+        let keyset = format!("({}, id) {} (?, ?)", column, op);
+        let mut conditions: Vec<&str> = conditions.to_vec();
+        if after_cursor {
+            conditions.push(&keyset);
+        }
+
+        format!(
+            "SELECT * FROM {}{} ORDER BY {col} {dir}, id {dir} LIMIT {};",
+            quote_identifier(table),
+            where_clause(table, &conditions, include_deleted),
+            limit,
+            col = column,
+            dir = direction
+        )
+    }
+
+    fn insert_bind(&self, table: &str, fields: &[&str]) -> String {
+        let fields: Vec<String> = fields.iter().map(|field| quote_identifier(field)).collect();
+        format!(
+            "INSERT INTO {} ({}) VALUES ({});",
+            quote_identifier(table),
+            fields.join(", "),
+            vec!["?"; fields.len()].join(", ")
+        )
     }
 
-    fn insert(&self, table: &str, fields: &[&str], values: &[&str]) -> String {
-        let fields_str = fields.join(", ");
-        let values_str = values
+    fn update(&self, table: &str, id: u64, fields: &[(&str, &str)]) -> String {
+        let set_str = fields
             .iter()
-            .map(|v| format!("'{}'", v))
+            .map(|(field, value)| format!("{} = '{}'", quote_identifier(field), value))
             .collect::<Vec<String>>()
             .join(", ");
         format!(
-            "INSERT INTO {} ({}) VALUES ({});",
-            table, fields_str, values_str
+            "UPDATE {} SET {} WHERE id = {};",
+            quote_identifier(table),
+            set_str,
+            id
         )
     }
 
-    fn update(&self, table: &str, id: u64, fields: &[(&str, &str)]) -> String {
+    fn update_bind(&self, table: &str, id: u64, fields: &[&str]) -> String {
+        let set_str = fields
+            .iter()
+            .map(|field| format!("{} = ?", quote_identifier(field)))
+            .collect::<Vec<String>>()
+            .join(", ");
+        format!(
+            "UPDATE {} SET {} WHERE id = {};",
+            quote_identifier(table),
+            set_str,
+            id
+        )
+    }
+
+    fn update_if_version(&self, table: &str, id: u64, fields: &[&str]) -> String {
         let set_str = fields
             .iter()
-            .map(|(field, value)| format!("{} = '{}'", field, value))
+            .map(|field| format!("{} = ?", quote_identifier(field)))
             .collect::<Vec<String>>()
             .join(", ");
-        format!("UPDATE {} SET {} WHERE id = {};", table, set_str, id)
+        format!(
+            "UPDATE {} SET {}, version = version + 1 WHERE id = {} AND version = ?;",
+            quote_identifier(table),
+            set_str,
+            id
+        )
     }
 
     fn delete(&self, table: &str, id: u64) -> String {
-        format!("DELETE FROM {} WHERE id = {};", table, id)
+        format!("DELETE FROM {} WHERE id = {};", quote_identifier(table), id)
+    }
+
+    fn soft_delete_if_version(&self, table: &str, id: u64, version: i32) -> String {
+        format!(
+            "UPDATE {} SET deleted_at = CURRENT_TIMESTAMP, version = version + 1 WHERE id = {} AND version = {} AND deleted_at IS NULL;",
+            quote_identifier(table), id, version
+        )
+    }
+
+    fn restore(&self, table: &str, id: u64) -> String {
+        format!(
+            "UPDATE {} SET deleted_at = NULL WHERE id = {} AND deleted_at IS NOT NULL;",
+            quote_identifier(table),
+            id
+        )
+    }
+
+    // FULLTEXT untuk kata (prefix), LIKE prefix per kolom, LIKE per trigram dan LIKE pada
+    // kolom angka; kandidat dari trigram tidak punya relevansi FULLTEXT sehingga di belakang
+    fn select_search(
+        &self,
+        table: &str,
+        text_columns: &[&str],
+        digits_column: &str,
+        trigrams: usize,
+        limit: usize,
+    ) -> SearchQuery {
+        let columns: Vec<String> = text_columns
+            .iter()
+            .map(|column| quote_identifier(column))
+            .collect();
+        let relevance = format!("MATCH({}) AGAINST (? IN BOOLEAN MODE)", columns.join(", "));
+        let mut conditions = vec![relevance.clone()];
+        conditions.extend(columns.iter().map(|column| format!("{} LIKE ?", column)));
+        let mut params = vec![SearchParam::FullText];
+        params.extend(vec![SearchParam::TextPrefix; text_columns.len()]);
+        trigram_conditions(text_columns, trigrams, &mut conditions, &mut params);
+        conditions.push(format!("{} LIKE ?", quote_identifier(digits_column)));
+        params.extend([SearchParam::Digits, SearchParam::FullText]);
+
+        let search = format!("({})", conditions.join(" OR "));
+        SearchQuery {
+            sql: format!(
+                "SELECT * FROM {}{} ORDER BY {} DESC, id LIMIT {};",
+                quote_identifier(table),
+                where_clause(table, &[&search], false),
+                relevance,
+                limit
+            ),
+            params,
+        }
+    }
+
+    fn select_deleted_before(&self, table: &str) -> String {
+        format!(
+            "SELECT * FROM {} WHERE deleted_at IS NOT NULL AND deleted_at < ?;",
+            quote_identifier(table)
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn soft_deleted_rows_are_hidden_unless_included() {
+        let builder = MySQLQueryBuilder;
+        assert_eq!(
+            builder.select_where("users", &["role = ?"], false, "id ASC", 10, 0),
+            "SELECT * FROM `users` WHERE role = ? AND deleted_at IS NULL ORDER BY id ASC LIMIT 10 OFFSET 0;"
+        );
+        assert_eq!(
+            builder.select_where("users", &[], true, "id ASC", 10, 20),
+            "SELECT * FROM `users` ORDER BY id ASC LIMIT 10 OFFSET 20;"
+        );
+        assert_eq!(
+            builder.select_where("audit_log", &[], false, "id ASC", 10, 0),
+            "SELECT * FROM `audit_log` ORDER BY id ASC LIMIT 10 OFFSET 0;"
+        );
+    }
+
+    #[test]
+    fn bind_queries_quote_identifiers() {
+        let builder = MySQLQueryBuilder;
+        assert_eq!(
+            builder.insert_bind("order", &["key", "status"]),
+            "INSERT INTO `order` (`key`, `status`) VALUES (?, ?);"
+        );
+        assert_eq!(
+            builder.update_bind("order", 3, &["key"]),
+            "UPDATE `order` SET `key` = ? WHERE id = 3;"
+        );
+    }
+
+    #[test]
+    fn reserved_words_are_quoted() {
+        let builder = MySQLQueryBuilder;
+        assert_eq!(
+            builder.select_by_id("order", 3),
+            "SELECT * FROM `order` WHERE id = 3;"
+        );
+        assert_eq!(
+            builder.select_by_field("order", "key"),
+            "SELECT * FROM `order` WHERE `key` = ?;"
+        );
+        assert_eq!(
+            builder.select_by_field("users", "email"),
+            "SELECT * FROM `users` WHERE `email` = ? AND deleted_at IS NULL;"
+        );
+        assert_eq!(
+            builder.select_where("group", &[], false, "id ASC", 5, 0),
+            "SELECT * FROM `group` ORDER BY id ASC LIMIT 5 OFFSET 0;"
+        );
+        assert_eq!(
+            builder.delete("order", 3),
+            "DELETE FROM `order` WHERE id = 3;"
+        );
+    }
+
+    #[test]
+    fn update_if_version_binds_values_and_version() {
+        let builder = MySQLQueryBuilder;
+        assert_eq!(
+            builder.update_if_version("users", 7, &["username", "email"]),
+            "UPDATE `users` SET `username` = ?, `email` = ?, version = version + 1 WHERE id = 7 AND version = ?;"
+        );
+    }
+
+    #[test]
+    fn keyset_uses_row_comparison() {
+        let builder = MySQLQueryBuilder;
+        let sort = Sort {
+            column: "created_at",
+            descending: true,
+        };
+        assert_eq!(
+            builder.select_keyset("users", &[], false, &sort, true, 21),
+            "SELECT * FROM `users` WHERE (`created_at`, id) < (?, ?) AND deleted_at IS NULL ORDER BY `created_at` DESC, id DESC LIMIT 21;"
+        );
+        let sort = Sort {
+            column: "id",
+            descending: false,
+        };
+        assert_eq!(
+            builder.select_keyset("users", &[], true, &sort, false, 21),
+            "SELECT * FROM `users` ORDER BY `id` ASC, id ASC LIMIT 21;"
+        );
+    }
+
+    #[test]
+    fn search_adds_trigram_conditions_per_column() {
+        let builder = MySQLQueryBuilder;
+        let search = builder.select_search("users", &["username", "email"], "phone_digits", 2, 20);
+        assert_eq!(
+            search.sql,
+            "SELECT * FROM `users` WHERE (MATCH(`username`, `email`) AGAINST (? IN BOOLEAN MODE) OR `username` LIKE ? OR `email` LIKE ? OR `username` LIKE ? OR `email` LIKE ? OR `username` LIKE ? OR `email` LIKE ? OR `phone_digits` LIKE ?) AND deleted_at IS NULL ORDER BY MATCH(`username`, `email`) AGAINST (? IN BOOLEAN MODE) DESC, id LIMIT 20;"
+        );
+        assert_eq!(
+            search.params,
+            vec![
+                SearchParam::FullText,
+                SearchParam::TextPrefix,
+                SearchParam::TextPrefix,
+                SearchParam::Trigram(0),
+                SearchParam::Trigram(0),
+                SearchParam::Trigram(1),
+                SearchParam::Trigram(1),
+                SearchParam::Digits,
+                SearchParam::FullText,
+            ]
+        );
     }
 }
@@ -0,0 +1,271 @@
+use std::sync::{Arc, LazyLock};
+use std::time::Duration;
+
+use tokio::sync::Semaphore;
+
+use crate::utils::{
+    metrics::metrics,
+    password_hasher::{
+        new_argon2id_hasher, new_bcrypt_hasher, new_password_hasher, PasswordHasher,
+    },
+};
+
+// Hashing password dijalankan di blocking pool agar worker tokio tidak tertahan
+struct HashingPool {
+    permits: Semaphore,
+    queue_timeout: Duration,
+    // Dipakai untuk hash baru
+    hasher: Arc<dyn PasswordHasher>,
+    // Semua algoritma yang dikenali saat verifikasi hash lama
+    known_hashers: Vec<Arc<dyn PasswordHasher>>,
+}
+
+// Err bila konfigurasi hasher tidak valid, dicek saat startup lewat check_password_hashing
+static HASHING_POOL: LazyLock<Result<HashingPool, String>> = LazyLock::new(|| {
+    dotenv::dotenv().ok();
+    // 0 akan membuat setiap hash menunggu sampai timeout
+    let max_concurrency = std::env::var("HASH_MAX_CONCURRENCY")
+        .ok()
+        .and_then(|v| v.parse().ok())
+        .unwrap_or_else(|| {
+            std::thread::available_parallelism()
+                .map(|n| n.get())
+                .unwrap_or(4)
+        })
+        .max(1);
+    let queue_timeout_ms = std::env::var("HASH_QUEUE_TIMEOUT_MS")
+        .ok()
+        .and_then(|v| v.parse().ok())
+        .unwrap_or(5000);
+
+    Ok(HashingPool {
+        permits: Semaphore::new(max_concurrency),
+        queue_timeout: Duration::from_millis(queue_timeout_ms),
+        hasher: new_password_hasher()?,
+        known_hashers: vec![new_argon2id_hasher()?, new_bcrypt_hasher()?],
+    })
+});
+
+fn hashing_pool() -> Result<&'static HashingPool, String> {
+    HASHING_POOL.as_ref().map_err(Clone::clone)
+}
+
+// Dipanggil saat startup agar parameter yang salah tidak baru ketahuan saat request pertama
+pub fn check_password_hashing() -> Result<(), String> {
+    hashing_pool().map(|_| ())
+}
+
+async fn run_blocking<T, F>(pool: &HashingPool, operation: &'static str, f: F) -> Result<T, String>
+where
+    T: Send + 'static,
+    F: FnOnce() -> Result<T, String> + Send + 'static,
+{
+    let _permit = tokio::time::timeout(pool.queue_timeout, pool.permits.acquire())
+        .await
+        .map_err(|_| "Password hashing is busy, please try again later".to_string())?
+        .map_err(|err| err.to_string())?;
+
+    let span = tracing::info_span!("password_hash", operation);
+    tokio::task::spawn_blocking(move || {
+        span.in_scope(|| {
+            let timer = metrics()
+                .password_hash_duration_seconds
+                .with_label_values(&[operation])
+                .start_timer();
+            let res = f();
+            timer.observe_duration();
+            res
+        })
+    })
+    .await
+    .map_err(|err| err.to_string())?
+}
+
+pub async fn hash_password(password: &str) -> Result<String, String> {
+    let pool = hashing_pool()?;
+    let password = password.to_string();
+    let hasher = pool.hasher.clone();
+    run_blocking(pool, "hash", move || hasher.hash(&password)).await
+}
+
+pub async fn verify_password(password: &str, hash: &str) -> Result<bool, String> {
+    let pool = hashing_pool()?;
+    let hasher = pool
+        .known_hashers
+        .iter()
+        .find(|hasher| hasher.matches(hash))
+        .cloned()
+        .ok_or_else(|| "Unsupported password hash".to_string())?;
+    let password = password.to_string();
+    let hash = hash.to_string();
+    run_blocking(pool, "verify", move || hasher.verify(&password, &hash)).await
+}
+
+// Hash lama (algoritma atau parameter berbeda) di-upgrade saat login berhasil
+pub fn needs_rehash(hash: &str) -> bool {
+    match hashing_pool() {
+        Ok(pool) => !pool.hasher.matches(hash) || !pool.hasher.is_current(hash),
+        Err(_) => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+    use std::sync::Arc;
+    use std::time::Instant;
+
+    use axum::{body::Body, http::Request, routing::get, Router};
+    use tower::ServiceExt;
+
+    use super::*;
+    use crate::controller::healthcheck_controller::healthcheck_controller;
+
+    const HASHING_TASKS: usize = 8;
+    const PROBES: usize = 100;
+
+    fn p99(mut samples: Vec<Duration>) -> Duration {
+        samples.sort();
+        samples[(samples.len() * 99 / 100).min(samples.len() - 1)]
+    }
+
+    // Latency of GET /api/healthcheck while signups keep hashing passwords
+    async fn healthcheck_p99_under_load(offload: bool) -> Duration {
+        let router = Router::new().route("/api/healthcheck", get(healthcheck_controller));
+        let stop = Arc::new(AtomicBool::new(false));
+
+        let load: Vec<_> = (0..HASHING_TASKS)
+            .map(|_| {
+                let stop = stop.clone();
+                tokio::spawn(async move {
+                    while !stop.load(Ordering::Relaxed) {
+                        if offload {
+                            let _ = hash_password("Password123!").await;
+                        } else {
+                            let _ = hashing_pool().unwrap().hasher.hash("Password123!");
+                            tokio::task::yield_now().await;
+                        }
+                    }
+                })
+            })
+            .collect();
+
+        let mut samples = Vec::with_capacity(PROBES);
+        for _ in 0..PROBES {
+            tokio::time::sleep(Duration::from_millis(5)).await;
+            let router = router.clone();
+            let start = Instant::now();
+            tokio::spawn(async move {
+                let req = Request::get("/api/healthcheck")
+                    .body(Body::empty())
+                    .unwrap();
+                router.oneshot(req).await.unwrap();
+            })
+            .await
+            .unwrap();
+            samples.push(start.elapsed());
+        }
+
+        stop.store(true, Ordering::Relaxed);
+        for task in load {
+            task.await.unwrap();
+        }
+        p99(samples)
+    }
+
+    #[tokio::test]
+    async fn hash_and_verify_round_trip() {
+        let hash = hash_password("Password123!").await.unwrap();
+
+        assert!(verify_password("Password123!", &hash).await.unwrap());
+        assert!(!verify_password("Password124!", &hash).await.unwrap());
+        assert!(!verify_password("", &hash).await.unwrap());
+        assert!(!needs_rehash(&hash));
+    }
+
+    #[tokio::test]
+    async fn bcrypt_hashes_are_verified_and_upgraded() {
+        let hash = bcrypt::hash("Password123!", 4).unwrap();
+
+        assert!(verify_password("Password123!", &hash).await.unwrap());
+        assert!(!verify_password("salah", &hash).await.unwrap());
+        assert!(needs_rehash(&hash));
+        assert!(verify_password("Password123!", "plaintext").await.is_err());
+    }
+
+    fn pool(permits: usize, queue_timeout: Duration) -> HashingPool {
+        HashingPool {
+            permits: Semaphore::new(permits),
+            queue_timeout,
+            hasher: new_bcrypt_hasher().unwrap(),
+            known_hashers: Vec::new(),
+        }
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+    async fn concurrency_is_bounded_by_semaphore() {
+        let pool = Arc::new(pool(2, Duration::from_secs(5)));
+        let running = Arc::new(AtomicUsize::new(0));
+        let peak = Arc::new(AtomicUsize::new(0));
+
+        let tasks: Vec<_> = (0..8)
+            .map(|_| {
+                let (pool, running, peak) = (pool.clone(), running.clone(), peak.clone());
+                tokio::spawn(async move {
+                    run_blocking(&pool, "hash", move || {
+                        let now = running.fetch_add(1, Ordering::SeqCst) + 1;
+                        peak.fetch_max(now, Ordering::SeqCst);
+                        std::thread::sleep(Duration::from_millis(20));
+                        running.fetch_sub(1, Ordering::SeqCst);
+                        Ok(())
+                    })
+                    .await
+                })
+            })
+            .collect();
+        for task in tasks {
+            task.await.unwrap().unwrap();
+        }
+
+        let peak = peak.load(Ordering::SeqCst);
+        assert!((1..=2).contains(&peak), "{} hashes ran at once", peak);
+    }
+
+    #[tokio::test]
+    async fn queue_timeout_rejects_when_busy() {
+        let pool = Arc::new(pool(1, Duration::from_millis(10)));
+        let slow = {
+            let pool = pool.clone();
+            tokio::spawn(async move {
+                run_blocking(&pool, "hash", || {
+                    std::thread::sleep(Duration::from_millis(200));
+                    Ok(())
+                })
+                .await
+            })
+        };
+        tokio::time::sleep(Duration::from_millis(50)).await;
+
+        let busy = run_blocking(&pool, "hash", || Ok(())).await;
+        assert_eq!(
+            busy,
+            Err("Password hashing is busy, please try again later".to_string())
+        );
+        assert!(slow.await.unwrap().is_ok());
+    }
+
+    // cargo test --release bench_ -- --ignored
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    #[ignore = "benchmark"]
+    async fn bench_unrelated_endpoint_p99_under_hashing_load() {
+        let inline = healthcheck_p99_under_load(false).await;
+        let offloaded = healthcheck_p99_under_load(true).await;
+
+        assert!(
+            offloaded < inline,
+            "healthcheck p99 with hashing on the blocking pool ({:?}) should be lower than on the async runtime ({:?})",
+            offloaded,
+            inline
+        );
+    }
+}
@@ -0,0 +1,566 @@
+use std::{collections::HashMap, future::Future, sync::Mutex};
+
+use axum::{
+    async_trait,
+    http::{HeaderMap, StatusCode},
+};
+use chrono::{DateTime, Duration, Utc};
+use serde::Serialize;
+use sqlx::mysql::MySqlPool;
+use tracing::Instrument;
+
+use crate::{
+    model::model::IdempotencyKeyModel,
+    utils::{adapter::sql_span, auth::jwt_secret, context::RequestContext, token::keyed_hash},
+};
+
+pub const IDEMPOTENCY_KEY: &str = "idempotency-key";
+pub const IDEMPOTENT_REPLAYED: &str = "idempotent-replayed";
+
+const MAX_KEY_LENGTH: usize = 255;
+
+enum Begin {
+    // Key baru, request boleh dijalankan
+    New,
+    // Response tersimpan dari request pertama
+    Replay(StatusCode, serde_json::Value),
+    // Request pertama dengan key yang sama belum selesai
+    InProgress,
+    // Key sudah dipakai untuk body yang berbeda
+    Mismatch,
+}
+
+pub enum Run<T, E> {
+    // Handler dijalankan, T hanya untuk response request ini (mis. ETag)
+    Done(StatusCode, serde_json::Value, T),
+    // Response tersimpan dari request pertama, tanpa menjalankan handler
+    Replay(StatusCode, serde_json::Value),
+    // Handler gagal, key dilepas sehingga boleh dicoba lagi
+    Failed(E),
+    InProgress,
+    Mismatch,
+}
+
+fn env_or(key: &str, default: i64) -> i64 {
+    std::env::var(key)
+        .ok()
+        .and_then(|v| v.parse().ok())
+        .unwrap_or(default)
+}
+
+fn ttl() -> Duration {
+    Duration::hours(env_or("IDEMPOTENCY_TTL_HOURS", 24))
+}
+
+// Selama lease, retry dengan key yang sama mendapat InProgress; setelahnya key boleh
+// diambil alih, mis. bila proses mati sebelum complete/release
+fn lease() -> Duration {
+    Duration::seconds(env_or("IDEMPOTENCY_LEASE_SECONDS", 60))
+}
+
+// Key hanya berlaku untuk pemanggil yang sama, mis. "POST /api/user|user:7"; request
+// anonim hanya dibedakan oleh route, karena IP bisa berubah di antara retry
+fn client_scope(route: &str) -> String {
+    let context = RequestContext::current().unwrap_or_default();
+    match context.actor {
+        Some(_) => format!("{}|{}", route, context.client_id()),
+        None => route.to_string(),
+    }
+}
+
+// Body bisa berisi password, jadi yang disimpan hanya HMAC dengan secret server
+fn fingerprint<B: Serialize>(body: &B) -> String {
+    let body = serde_json::to_string(body).unwrap_or_default();
+    keyed_hash(jwt_secret().as_bytes(), &body)
+}
+
+// None bila header tidak dikirim
+pub fn idempotency_key(headers: &HeaderMap) -> Result<Option<String>, String> {
+    let value = match headers.get(IDEMPOTENCY_KEY) {
+        Some(value) => value,
+        None => return Ok(None),
+    };
+    let key = value
+        .to_str()
+        .map(str::trim)
+        .map_err(|_| "Idempotency-Key must be a visible ASCII string".to_string())?;
+    if key.is_empty() || key.len() > MAX_KEY_LENGTH {
+        return Err(format!(
+            "Idempotency-Key must be between 1 and {} characters",
+            MAX_KEY_LENGTH
+        ));
+    }
+
+    Ok(Some(key.to_string()))
+}
+
+// Mencadangkan key sebelum request dijalankan agar retry yang bersamaan tidak ikut jalan
+async fn begin(
+    store: &dyn IdempotencyStore,
+    scope: &str,
+    key: &str,
+    fingerprint: &str,
+) -> Result<Begin, String> {
+    let now = Utc::now();
+    store.purge_expired(now).await?;
+
+    let inserted = store
+        .insert(scope, key, fingerprint, now + lease(), now + ttl())
+        .await?;
+    if inserted {
+        return Ok(Begin::New);
+    }
+
+    let row = match store.find(scope, key).await? {
+        Some(row) => row,
+        // Terhapus di antara insert dan select, anggap masih diproses
+        None => return Ok(Begin::InProgress),
+    };
+    if row.fingerprint != fingerprint {
+        return Ok(Begin::Mismatch);
+    }
+
+    match (row.status_code, row.response) {
+        (Some(status), Some(response)) => {
+            let status = StatusCode::from_u16(status as u16).map_err(|err| err.to_string())?;
+            Ok(Begin::Replay(status, response.0))
+        }
+        _ if row.locked_until.is_some_and(|until| until > now) => Ok(Begin::InProgress),
+        // Lease request pertama sudah habis tanpa response; hanya satu retry yang berhasil mengambil alih
+        _ if store.take_over(row.id, now, now + lease()).await? => Ok(Begin::New),
+        _ => Ok(Begin::InProgress),
+    }
+}
+
+async fn complete(
+    store: &dyn IdempotencyStore,
+    scope: &str,
+    key: &str,
+    status: StatusCode,
+    response: &serde_json::Value,
+) -> Result<(), String> {
+    store.complete(scope, key, status, response).await
+}
+
+// Request gagal tidak disimpan, sehingga key yang sama boleh dicoba lagi
+async fn release(store: &dyn IdempotencyStore, scope: &str, key: &str) -> Result<(), String> {
+    store.release(scope, key).await
+}
+
+// Tanpa key handler langsung dijalankan. Dengan key: retry mendapat response request
+// pertama, response sukses disimpan dan key dari request gagal dilepas
+pub async fn run<B, T, E, F, Fut>(
+    store: &dyn IdempotencyStore,
+    route: &str,
+    key: Option<&str>,
+    body: &B,
+    handler: F,
+) -> Result<Run<T, E>, String>
+where
+    B: Serialize,
+    F: FnOnce() -> Fut,
+    Fut: Future<Output = Result<(StatusCode, serde_json::Value, T), E>>,
+{
+    let Some(key) = key else {
+        return Ok(match handler().await {
+            Ok((status, response, extra)) => Run::Done(status, response, extra),
+            Err(err) => Run::Failed(err),
+        });
+    };
+
+    let scope = client_scope(route);
+    let fingerprint = fingerprint(body);
+    match begin(store, &scope, key, &fingerprint).await? {
+        Begin::New => {}
+        Begin::Replay(status, response) => return Ok(Run::Replay(status, response)),
+        Begin::InProgress => return Ok(Run::InProgress),
+        Begin::Mismatch => return Ok(Run::Mismatch),
+    }
+
+    match handler().await {
+        Ok((status, response, extra)) => {
+            if let Err(err) = complete(store, &scope, key, status, &response).await {
+                tracing::error!("Failed to store idempotent response: {}", err);
+            }
+            Ok(Run::Done(status, response, extra))
+        }
+        Err(err) => {
+            if let Err(err) = release(store, &scope, key).await {
+                tracing::error!("Failed to release idempotency key: {}", err);
+            }
+            Ok(Run::Failed(err))
+        }
+    }
+}
+
+// Penyimpanan key, mode --simulate memakai MemoryIdempotencyStore
+#[async_trait]
+pub trait IdempotencyStore: Send + Sync {
+    async fn purge_expired(&self, now: DateTime<Utc>) -> Result<(), String>;
+    // false bila key sudah ada
+    async fn insert(
+        &self,
+        scope: &str,
+        key: &str,
+        fingerprint: &str,
+        locked_until: DateTime<Utc>,
+        expires_at: DateTime<Utc>,
+    ) -> Result<bool, String>;
+    async fn find(&self, scope: &str, key: &str) -> Result<Option<IdempotencyKeyModel>, String>;
+    // true bila key belum punya response dan lease-nya sudah habis pada now
+    async fn take_over(
+        &self,
+        id: i64,
+        now: DateTime<Utc>,
+        locked_until: DateTime<Utc>,
+    ) -> Result<bool, String>;
+    async fn complete(
+        &self,
+        scope: &str,
+        key: &str,
+        status: StatusCode,
+        response: &serde_json::Value,
+    ) -> Result<(), String>;
+    async fn release(&self, scope: &str, key: &str) -> Result<(), String>;
+}
+
+pub struct MySqlIdempotencyStore {
+    db: MySqlPool,
+}
+
+impl MySqlIdempotencyStore {
+    pub fn new(db: MySqlPool) -> Self {
+        MySqlIdempotencyStore { db }
+    }
+}
+
+#[async_trait]
+impl IdempotencyStore for MySqlIdempotencyStore {
+    async fn purge_expired(&self, now: DateTime<Utc>) -> Result<(), String> {
+        let query = "DELETE FROM idempotency_keys WHERE expires_at < ?;";
+        sqlx::query(query)
+            .bind(now)
+            .execute(&self.db)
+            .instrument(sql_span(query))
+            .await
+            .map_err(|err: sqlx::Error| err.to_string())?;
+
+        Ok(())
+    }
+
+    async fn insert(
+        &self,
+        scope: &str,
+        key: &str,
+        fingerprint: &str,
+        locked_until: DateTime<Utc>,
+        expires_at: DateTime<Utc>,
+    ) -> Result<bool, String> {
+        let query = "INSERT INTO idempotency_keys (scope, idempotency_key, fingerprint, locked_until, expires_at) VALUES (?, ?, ?, ?, ?);";
+        let inserted = sqlx::query(query)
+            .bind(scope)
+            .bind(key)
+            .bind(fingerprint)
+            .bind(locked_until)
+            .bind(expires_at)
+            .execute(&self.db)
+            .instrument(sql_span(query))
+            .await;
+        match inserted {
+            Ok(_) => Ok(true),
+            Err(sqlx::Error::Database(err)) if err.is_unique_violation() => Ok(false),
+            Err(err) => Err(err.to_string()),
+        }
+    }
+
+    async fn find(&self, scope: &str, key: &str) -> Result<Option<IdempotencyKeyModel>, String> {
+        let query = "SELECT * FROM idempotency_keys WHERE scope = ? AND idempotency_key = ?;";
+        sqlx::query_as::<_, IdempotencyKeyModel>(query)
+            .bind(scope)
+            .bind(key)
+            .fetch_optional(&self.db)
+            .instrument(sql_span(query))
+            .await
+            .map_err(|err: sqlx::Error| err.to_string())
+    }
+
+    async fn take_over(
+        &self,
+        id: i64,
+        now: DateTime<Utc>,
+        locked_until: DateTime<Utc>,
+    ) -> Result<bool, String> {
+        let query = "UPDATE idempotency_keys SET locked_until = ? WHERE id = ? AND status_code IS NULL AND (locked_until IS NULL OR locked_until <= ?);";
+        let res = sqlx::query(query)
+            .bind(locked_until)
+            .bind(id)
+            .bind(now)
+            .execute(&self.db)
+            .instrument(sql_span(query))
+            .await
+            .map_err(|err: sqlx::Error| err.to_string())?;
+
+        Ok(res.rows_affected() == 1)
+    }
+
+    async fn complete(
+        &self,
+        scope: &str,
+        key: &str,
+        status: StatusCode,
+        response: &serde_json::Value,
+    ) -> Result<(), String> {
+        let query = "UPDATE idempotency_keys SET status_code = ?, response = ?, locked_until = NULL WHERE scope = ? AND idempotency_key = ?;";
+        sqlx::query(query)
+            .bind(status.as_u16() as i16)
+            .bind(sqlx::types::Json(response))
+            .bind(scope)
+            .bind(key)
+            .execute(&self.db)
+            .instrument(sql_span(query))
+            .await
+            .map_err(|err: sqlx::Error| err.to_string())?;
+
+        Ok(())
+    }
+
+    async fn release(&self, scope: &str, key: &str) -> Result<(), String> {
+        let query = "DELETE FROM idempotency_keys WHERE scope = ? AND idempotency_key = ?;";
+        sqlx::query(query)
+            .bind(scope)
+            .bind(key)
+            .execute(&self.db)
+            .instrument(sql_span(query))
+            .await
+            .map_err(|err: sqlx::Error| err.to_string())?;
+
+        Ok(())
+    }
+}
+
+// Hilang saat restart, key kedaluwarsa dibuang setiap begin
+#[derive(Default)]
+pub struct MemoryIdempotencyStore {
+    rows: Mutex<HashMap<(String, String), IdempotencyKeyModel>>,
+}
+
+#[async_trait]
+impl IdempotencyStore for MemoryIdempotencyStore {
+    async fn purge_expired(&self, now: DateTime<Utc>) -> Result<(), String> {
+        self.rows
+            .lock()
+            .unwrap()
+            .retain(|_, row| row.expires_at >= now);
+        Ok(())
+    }
+
+    async fn insert(
+        &self,
+        scope: &str,
+        key: &str,
+        fingerprint: &str,
+        locked_until: DateTime<Utc>,
+        expires_at: DateTime<Utc>,
+    ) -> Result<bool, String> {
+        let mut rows = self.rows.lock().unwrap();
+        if rows.contains_key(&(scope.to_string(), key.to_string())) {
+            return Ok(false);
+        }
+        let id = rows.values().map(|row| row.id).max().unwrap_or(0) + 1;
+        rows.insert(
+            (scope.to_string(), key.to_string()),
+            IdempotencyKeyModel {
+                id,
+                scope: scope.to_string(),
+                idempotency_key: key.to_string(),
+                fingerprint: fingerprint.to_string(),
+                status_code: None,
+                response: None,
+                locked_until: Some(locked_until),
+                expires_at,
+                created_at: Some(Utc::now()),
+            },
+        );
+        Ok(true)
+    }
+
+    async fn find(&self, scope: &str, key: &str) -> Result<Option<IdempotencyKeyModel>, String> {
+        let rows = self.rows.lock().unwrap();
+        Ok(rows.get(&(scope.to_string(), key.to_string())).cloned())
+    }
+
+    async fn take_over(
+        &self,
+        id: i64,
+        now: DateTime<Utc>,
+        locked_until: DateTime<Utc>,
+    ) -> Result<bool, String> {
+        let mut rows = self.rows.lock().unwrap();
+        let row = rows.values_mut().find(|row| {
+            row.id == id
+                && row.status_code.is_none()
+                && row.locked_until.is_none_or(|until| until <= now)
+        });
+        Ok(match row {
+            Some(row) => {
+                row.locked_until = Some(locked_until);
+                true
+            }
+            None => false,
+        })
+    }
+
+    async fn complete(
+        &self,
+        scope: &str,
+        key: &str,
+        status: StatusCode,
+        response: &serde_json::Value,
+    ) -> Result<(), String> {
+        let mut rows = self.rows.lock().unwrap();
+        if let Some(row) = rows.get_mut(&(scope.to_string(), key.to_string())) {
+            row.status_code = Some(status.as_u16() as i16);
+            row.response = Some(sqlx::types::Json(response.clone()));
+            row.locked_until = None;
+        }
+        Ok(())
+    }
+
+    async fn release(&self, scope: &str, key: &str) -> Result<(), String> {
+        self.rows
+            .lock()
+            .unwrap()
+            .remove(&(scope.to_string(), key.to_string()));
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn headers(value: &[u8]) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        headers.insert(
+            IDEMPOTENCY_KEY,
+            axum::http::HeaderValue::from_bytes(value).unwrap(),
+        );
+        headers
+    }
+
+    #[test]
+    fn idempotency_key_header() {
+        assert_eq!(idempotency_key(&HeaderMap::new()), Ok(None));
+        assert_eq!(
+            idempotency_key(&headers(b" order-42 ")),
+            Ok(Some("order-42".to_string()))
+        );
+        assert!(idempotency_key(&headers(b"  ")).is_err());
+        assert!(idempotency_key(&headers("kunci-é".as_bytes())).is_err());
+
+        let longest = "k".repeat(MAX_KEY_LENGTH);
+        assert_eq!(
+            idempotency_key(&headers(longest.as_bytes())),
+            Ok(Some(longest.clone()))
+        );
+        assert!(idempotency_key(&headers(format!("{}k", longest).as_bytes())).is_err());
+    }
+
+    #[tokio::test]
+    async fn begin_reserves_then_replays() {
+        let store = MemoryIdempotencyStore::default();
+        let response = serde_json::json!({ "status": "ok" });
+
+        assert!(matches!(begin(&store, "s", "k", "a").await, Ok(Begin::New)));
+        assert!(matches!(
+            begin(&store, "s", "k", "a").await,
+            Ok(Begin::InProgress)
+        ));
+        assert!(matches!(
+            begin(&store, "s", "k", "b").await,
+            Ok(Begin::Mismatch)
+        ));
+        // Scope lain berarti pemanggil lain
+        assert!(matches!(begin(&store, "t", "k", "a").await, Ok(Begin::New)));
+
+        complete(&store, "s", "k", StatusCode::CREATED, &response)
+            .await
+            .unwrap();
+        match begin(&store, "s", "k", "a").await {
+            Ok(Begin::Replay(status, body)) => {
+                assert_eq!(status, StatusCode::CREATED);
+                assert_eq!(body, response);
+            }
+            _ => panic!("expected a replay"),
+        }
+
+        release(&store, "t", "k").await.unwrap();
+        assert!(matches!(begin(&store, "t", "k", "a").await, Ok(Begin::New)));
+    }
+
+    #[tokio::test]
+    async fn run_stores_success_and_releases_failure() {
+        crate::utils::simulation::enable_simulation();
+        let store = MemoryIdempotencyStore::default();
+        let ok = || async { Ok::<_, String>((StatusCode::CREATED, serde_json::json!(1), ())) };
+
+        let first = run(&store, "POST /x", Some("k"), &"a", ok).await;
+        assert!(matches!(first, Ok(Run::Done(StatusCode::CREATED, _, ()))));
+        let retry = run(&store, "POST /x", Some("k"), &"a", ok).await;
+        assert!(matches!(retry, Ok(Run::Replay(StatusCode::CREATED, _))));
+        let other_body = run(&store, "POST /x", Some("k"), &"b", ok).await;
+        assert!(matches!(other_body, Ok(Run::Mismatch)));
+
+        let failed = run(&store, "POST /x", Some("j"), &"a", || async {
+            Err::<(StatusCode, serde_json::Value, ()), _>("gagal".to_string())
+        })
+        .await;
+        assert!(matches!(failed, Ok(Run::Failed(_))));
+        // Key dari request gagal boleh dipakai lagi
+        let again = run(&store, "POST /x", Some("j"), &"a", ok).await;
+        assert!(matches!(again, Ok(Run::Done(..))));
+    }
+
+    #[tokio::test]
+    async fn scope_is_per_client() {
+        assert_eq!(client_scope("POST /api/user"), "POST /api/user");
+
+        let anonymous = RequestContext {
+            ip: Some("10.0.0.1".to_string()),
+            ..Default::default()
+        };
+        let scope = anonymous
+            .scope(async { client_scope("POST /api/user") })
+            .await;
+        assert_eq!(scope, "POST /api/user");
+
+        let user = RequestContext {
+            ip: Some("10.0.0.1".to_string()),
+            actor: Some(crate::utils::auth::AuthUser {
+                id: 7,
+                username: "budi".to_string(),
+                role: "user".to_string(),
+                token_version: 0,
+                email_verified: true,
+                api_key_id: None,
+                scopes: None,
+            }),
+            ..Default::default()
+        };
+        let scope = user.scope(async { client_scope("POST /api/user") }).await;
+        assert_eq!(scope, "POST /api/user|user:7");
+    }
+
+    #[test]
+    fn fingerprint_is_keyed() {
+        crate::utils::simulation::enable_simulation();
+        let body = serde_json::json!({ "password": "rahasia" });
+        let serialized = serde_json::to_string(&body).unwrap();
+
+        assert_eq!(fingerprint(&body), fingerprint(&body));
+        assert_ne!(
+            fingerprint(&body),
+            crate::utils::token::hash_token(&serialized)
+        );
+    }
+}
@@ -0,0 +1,309 @@
+use std::{collections::HashMap, sync::Mutex};
+
+use axum::async_trait;
+use chrono::{DateTime, Duration, Utc};
+use sqlx::mysql::MySqlPool;
+use tracing::Instrument;
+
+use crate::{model::model::LoginThrottleModel, utils::adapter::sql_span};
+
+// Penghitung gagal login, dipisah per akun, per IP dan untuk langkah 2FA
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ThrottleScope {
+    Account,
+    Ip,
+    // Langkah kedua login, dihitung terpisah dari password
+    Mfa,
+}
+
+impl ThrottleScope {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ThrottleScope::Account => "account",
+            ThrottleScope::Ip => "ip",
+            ThrottleScope::Mfa => "mfa",
+        }
+    }
+
+    // (mulai diperlambat setelah, dikunci setelah) sekian kali gagal
+    fn thresholds(&self) -> (i32, i32) {
+        match self {
+            ThrottleScope::Account => (
+                env_or("LOGIN_DELAY_AFTER_FAILURES", 3),
+                env_or("LOGIN_LOCKOUT_THRESHOLD", 10),
+            ),
+            ThrottleScope::Ip => (
+                env_or("LOGIN_IP_DELAY_AFTER_FAILURES", 10),
+                env_or("LOGIN_IP_LOCKOUT_THRESHOLD", 50),
+            ),
+            ThrottleScope::Mfa => (
+                env_or("MFA_DELAY_AFTER_FAILURES", 3),
+                env_or("MFA_LOCKOUT_THRESHOLD", 5),
+            ),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct ThrottleKey {
+    pub scope: ThrottleScope,
+    pub key: String,
+}
+
+impl ThrottleKey {
+    // Username yang tidak terdaftar tetap dihitung agar perilakunya tidak membocorkan akun
+    pub fn account(user_id: Option<i32>, identifier: &str) -> Self {
+        let key = match user_id {
+            Some(id) => format!("user:{}", id),
+            None => format!("name:{}", identifier.trim().to_lowercase()),
+        };
+        ThrottleKey {
+            scope: ThrottleScope::Account,
+            key,
+        }
+    }
+
+    pub fn mfa(user_id: i32) -> Self {
+        ThrottleKey {
+            scope: ThrottleScope::Mfa,
+            key: format!("user:{}", user_id),
+        }
+    }
+
+    pub fn ip(ip: &str) -> Self {
+        ThrottleKey {
+            scope: ThrottleScope::Ip,
+            key: ip.to_string(),
+        }
+    }
+}
+
+fn env_or<T: std::str::FromStr>(key: &str, default: T) -> T {
+    std::env::var(key)
+        .ok()
+        .and_then(|v| v.parse().ok())
+        .unwrap_or(default)
+}
+
+fn failure_window() -> Duration {
+    Duration::minutes(env_or("LOGIN_FAILURE_WINDOW_MINUTES", 60))
+}
+
+// Jeda bertambah dua kali lipat setiap gagal, dibatasi LOGIN_MAX_DELAY_SECONDS
+fn delay(scope: ThrottleScope, failures: i32) -> Duration {
+    let (delay_after, _) = scope.thresholds();
+    if failures < delay_after {
+        return Duration::zero();
+    }
+    let max_delay: i64 = env_or("LOGIN_MAX_DELAY_SECONDS", 60);
+    let exponent = (failures - delay_after).min(16) as u32;
+    Duration::seconds(2i64.pow(exponent).min(max_delay))
+}
+
+fn retry_at(
+    scope: ThrottleScope,
+    row: &LoginThrottleModel,
+    now: DateTime<Utc>,
+) -> Option<DateTime<Utc>> {
+    let locked_until = row.locked_until.filter(|until| *until > now);
+    let delayed_until = row
+        .last_failure_at
+        .filter(|at| *at + failure_window() > now)
+        .map(|at| at + delay(scope, row.failures))
+        .filter(|until| *until > now);
+
+    locked_until.into_iter().chain(delayed_until).max()
+}
+
+// Detik sampai login boleh dicoba lagi, None bila tidak ditahan
+pub async fn retry_after(
+    store: &dyn ThrottleStore,
+    keys: &[ThrottleKey],
+) -> Result<Option<i64>, String> {
+    let now = Utc::now();
+    let mut latest: Option<DateTime<Utc>> = None;
+    for key in keys {
+        if let Some(row) = store.find(key).await? {
+            latest = latest.max(retry_at(key.scope, &row, now));
+        }
+    }
+
+    Ok(latest.map(|at| (at - now).num_seconds().max(1)))
+}
+
+// Mengembalikan true bila kegagalan ini membuat key terkunci
+pub async fn record_failure(store: &dyn ThrottleStore, key: &ThrottleKey) -> Result<bool, String> {
+    let now = Utc::now();
+    let row = store.add_failure(key, now, now - failure_window()).await?;
+    let (_, lockout_threshold) = key.scope.thresholds();
+    let already_locked = row.locked_until.is_some_and(|until| until > now);
+    if row.failures < lockout_threshold || already_locked {
+        return Ok(false);
+    }
+
+    let lockout = Duration::minutes(env_or("LOGIN_LOCKOUT_MINUTES", 15));
+    store.lock(key, now + lockout).await?;
+
+    Ok(true)
+}
+
+// Login berhasil atau dibuka oleh admin
+pub async fn reset(store: &dyn ThrottleStore, key: &ThrottleKey) -> Result<bool, String> {
+    store.delete(key).await
+}
+
+// Penyimpanan penghitung, mode --simulate memakai MemoryThrottleStore
+#[async_trait]
+pub trait ThrottleStore: Send + Sync {
+    async fn find(&self, key: &ThrottleKey) -> Result<Option<LoginThrottleModel>, String>;
+    // failures dimulai ulang bila kegagalan terakhir sebelum window_start
+    async fn add_failure(
+        &self,
+        key: &ThrottleKey,
+        now: DateTime<Utc>,
+        window_start: DateTime<Utc>,
+    ) -> Result<LoginThrottleModel, String>;
+    async fn lock(&self, key: &ThrottleKey, until: DateTime<Utc>) -> Result<(), String>;
+    // true bila key memang tersimpan
+    async fn delete(&self, key: &ThrottleKey) -> Result<bool, String>;
+}
+
+// Disimpan di tabel login_throttles agar tidak hilang saat restart
+pub struct MySqlThrottleStore {
+    db: MySqlPool,
+}
+
+impl MySqlThrottleStore {
+    pub fn new(db: MySqlPool) -> Self {
+        MySqlThrottleStore { db }
+    }
+}
+
+#[async_trait]
+impl ThrottleStore for MySqlThrottleStore {
+    async fn find(&self, key: &ThrottleKey) -> Result<Option<LoginThrottleModel>, String> {
+        let query = "SELECT * FROM login_throttles WHERE scope = ? AND throttle_key = ?;";
+        sqlx::query_as::<_, LoginThrottleModel>(query)
+            .bind(key.scope.as_str())
+            .bind(&key.key)
+            .fetch_optional(&self.db)
+            .instrument(sql_span(query))
+            .await
+            .map_err(|err: sqlx::Error| err.to_string())
+    }
+
+    async fn add_failure(
+        &self,
+        key: &ThrottleKey,
+        now: DateTime<Utc>,
+        window_start: DateTime<Utc>,
+    ) -> Result<LoginThrottleModel, String> {
+        let upsert_query = "INSERT INTO login_throttles (scope, throttle_key, failures, last_failure_at) VALUES (?, ?, 1, ?) \
+            ON DUPLICATE KEY UPDATE failures = IF(last_failure_at IS NULL OR last_failure_at < ?, 1, failures + 1), last_failure_at = VALUES(last_failure_at);";
+        sqlx::query(upsert_query)
+            .bind(key.scope.as_str())
+            .bind(&key.key)
+            .bind(now)
+            .bind(window_start)
+            .execute(&self.db)
+            .instrument(sql_span(upsert_query))
+            .await
+            .map_err(|err: sqlx::Error| err.to_string())?;
+
+        self.find(key)
+            .await?
+            .ok_or_else(|| format!("Login throttle {} was not stored", key.key))
+    }
+
+    async fn lock(&self, key: &ThrottleKey, until: DateTime<Utc>) -> Result<(), String> {
+        let query =
+            "UPDATE login_throttles SET locked_until = ? WHERE scope = ? AND throttle_key = ?;";
+        sqlx::query(query)
+            .bind(until)
+            .bind(key.scope.as_str())
+            .bind(&key.key)
+            .execute(&self.db)
+            .instrument(sql_span(query))
+            .await
+            .map_err(|err: sqlx::Error| err.to_string())?;
+
+        Ok(())
+    }
+
+    async fn delete(&self, key: &ThrottleKey) -> Result<bool, String> {
+        let query = "DELETE FROM login_throttles WHERE scope = ? AND throttle_key = ?;";
+        let res = sqlx::query(query)
+            .bind(key.scope.as_str())
+            .bind(&key.key)
+            .execute(&self.db)
+            .instrument(sql_span(query))
+            .await
+            .map_err(|err: sqlx::Error| err.to_string())?;
+
+        Ok(res.rows_affected() > 0)
+    }
+}
+
+// Hilang saat restart; baris yang sudah di luar jendela waktu dan tidak terkunci
+// dibuang setiap ada kegagalan baru
+#[derive(Default)]
+pub struct MemoryThrottleStore {
+    rows: Mutex<HashMap<(&'static str, String), LoginThrottleModel>>,
+}
+
+#[async_trait]
+impl ThrottleStore for MemoryThrottleStore {
+    async fn find(&self, key: &ThrottleKey) -> Result<Option<LoginThrottleModel>, String> {
+        let rows = self.rows.lock().unwrap();
+        Ok(rows.get(&(key.scope.as_str(), key.key.clone())).cloned())
+    }
+
+    async fn add_failure(
+        &self,
+        key: &ThrottleKey,
+        now: DateTime<Utc>,
+        window_start: DateTime<Utc>,
+    ) -> Result<LoginThrottleModel, String> {
+        let mut rows = self.rows.lock().unwrap();
+        rows.retain(|_, row| {
+            row.last_failure_at.is_some_and(|at| at >= window_start)
+                || row.locked_until.is_some_and(|until| until > now)
+        });
+
+        let next_id = rows.values().map(|row| row.id).max().unwrap_or(0) + 1;
+        let row = rows
+            .entry((key.scope.as_str(), key.key.clone()))
+            .or_insert_with(|| LoginThrottleModel {
+                id: next_id,
+                scope: key.scope.as_str().to_string(),
+                throttle_key: key.key.clone(),
+                failures: 0,
+                last_failure_at: None,
+                locked_until: None,
+                updated_at: None,
+            });
+        if row.last_failure_at.is_none_or(|at| at < window_start) {
+            row.failures = 0;
+        }
+        row.failures += 1;
+        row.last_failure_at = Some(now);
+        row.updated_at = Some(now);
+
+        Ok(row.clone())
+    }
+
+    async fn lock(&self, key: &ThrottleKey, until: DateTime<Utc>) -> Result<(), String> {
+        let mut rows = self.rows.lock().unwrap();
+        if let Some(row) = rows.get_mut(&(key.scope.as_str(), key.key.clone())) {
+            row.locked_until = Some(until);
+        }
+        Ok(())
+    }
+
+    async fn delete(&self, key: &ThrottleKey) -> Result<bool, String> {
+        let mut rows = self.rows.lock().unwrap();
+        Ok(rows
+            .remove(&(key.scope.as_str(), key.key.clone()))
+            .is_some())
+    }
+}
@@ -0,0 +1,93 @@
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use chrono::Utc;
+
+#[derive(Debug, Clone)]
+pub struct Mail {
+    pub to: String,
+    pub subject: String,
+    pub body: String,
+}
+
+pub trait Mailer: Send + Sync {
+    fn send(&self, mail: &Mail) -> Result<(), String>;
+}
+
+// Hanya menulis ke log, untuk development. Isi email berisi token reset dan verifikasi,
+// jadi hanya ditulis bila log_body (LOG_MESSAGE_BODY=true)
+pub struct LogMailer {
+    log_body: bool,
+}
+
+impl Mailer for LogMailer {
+    fn send(&self, mail: &Mail) -> Result<(), String> {
+        if self.log_body {
+            tracing::info!(to = %mail.to, subject = %mail.subject, "📧 {}", mail.body);
+        } else {
+            tracing::info!(to = %mail.to, subject = %mail.subject, "📧 Mail sent");
+        }
+        Ok(())
+    }
+}
+
+// Hanya untuk development, jangan aktifkan di lingkungan yang log-nya dikirim keluar
+pub fn log_message_body() -> bool {
+    std::env::var("LOG_MESSAGE_BODY").is_ok_and(|value| value == "true")
+}
+
+// Menulis setiap email sebagai file .eml dalam format maildir (tmp/ lalu new/)
+pub struct FileMailer {
+    dir: PathBuf,
+    from: String,
+}
+
+impl Mailer for FileMailer {
+    fn send(&self, mail: &Mail) -> Result<(), String> {
+        let tmp_dir = self.dir.join("tmp");
+        let new_dir = self.dir.join("new");
+        for dir in [&tmp_dir, &new_dir, &self.dir.join("cur")] {
+            std::fs::create_dir_all(dir).map_err(|err| err.to_string())?;
+        }
+
+        let now = Utc::now();
+        let file_name = format!(
+            "{}.{}.{}.eml",
+            now.timestamp_micros(),
+            std::process::id(),
+            rand::random::<u32>()
+        );
+        let content = format!(
+            "From: {}\r\nTo: {}\r\nSubject: {}\r\nDate: {}\r\nContent-Type: text/plain; charset=utf-8\r\n\r\n{}\r\n",
+            self.from,
+            mail.to,
+            mail.subject,
+            now.to_rfc2822(),
+            mail.body
+        );
+
+        let tmp_path = tmp_dir.join(&file_name);
+        std::fs::write(&tmp_path, content).map_err(|err| err.to_string())?;
+        std::fs::rename(&tmp_path, new_dir.join(&file_name)).map_err(|err| err.to_string())
+    }
+}
+
+// URL dasar untuk tautan di dalam email
+pub fn app_url() -> String {
+    std::env::var("APP_URL").unwrap_or_else(|_| "http://localhost:3245".to_string())
+}
+
+// MAILER=log|file, MAIL_DIR untuk sink file
+pub fn new_mailer() -> Arc<dyn Mailer> {
+    dotenv::dotenv().ok();
+    match std::env::var("MAILER").as_deref() {
+        Ok("file") => Arc::new(FileMailer {
+            dir: PathBuf::from(std::env::var("MAIL_DIR").unwrap_or_else(|_| "maildir".to_string())),
+            from: std::env::var("MAIL_FROM")
+                .unwrap_or_else(|_| "no-reply@api-simu.local".to_string()),
+        }),
+        _ => Arc::new(LogMailer {
+            log_body: log_message_body(),
+        }),
+    }
+}
@@ -0,0 +1,170 @@
+use std::sync::LazyLock;
+use std::time::Instant;
+
+use axum::{
+    extract::{MatchedPath, Request},
+    middleware::Next,
+    response::Response,
+};
+use prometheus::{
+    Encoder, HistogramOpts, HistogramVec, IntCounterVec, IntGaugeVec, Opts, Registry, TextEncoder,
+};
+use sqlx::mysql::MySqlPool;
+
+use crate::utils::observer::{Event, Observer};
+
+pub struct Metrics {
+    registry: Registry,
+    pub http_requests_total: IntCounterVec,
+    pub http_request_duration_seconds: HistogramVec,
+    pub db_pool_connections: IntGaugeVec,
+    pub password_hash_duration_seconds: HistogramVec,
+    pub validation_failures_total: IntCounterVec,
+    pub user_events_total: IntCounterVec,
+}
+
+static METRICS: LazyLock<Metrics> = LazyLock::new(Metrics::new);
+
+// Satu registry untuk seluruh aplikasi
+pub fn metrics() -> &'static Metrics {
+    &METRICS
+}
+
+impl Metrics {
+    fn new() -> Self {
+        let registry = Registry::new();
+
+        let http_requests_total = IntCounterVec::new(
+            Opts::new("http_requests_total", "Total HTTP requests"),
+            &["method", "route", "status"],
+        )
+        .unwrap();
+        let http_request_duration_seconds = HistogramVec::new(
+            HistogramOpts::new(
+                "http_request_duration_seconds",
+                "HTTP request latency in seconds",
+            ),
+            &["method", "route", "status"],
+        )
+        .unwrap();
+        let db_pool_connections = IntGaugeVec::new(
+            Opts::new("db_pool_connections", "MySQL pool connections by state"),
+            &["state"],
+        )
+        .unwrap();
+        let password_hash_duration_seconds = HistogramVec::new(
+            HistogramOpts::new(
+                "password_hash_duration_seconds",
+                "Password hashing and verification duration in seconds",
+            )
+            .buckets(vec![0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5]),
+            &["operation"],
+        )
+        .unwrap();
+        let validation_failures_total = IntCounterVec::new(
+            Opts::new("validation_failures_total", "Validation failures per rule"),
+            &["rule"],
+        )
+        .unwrap();
+        let user_events_total = IntCounterVec::new(
+            Opts::new("user_events_total", "User lifecycle events by topic"),
+            &["topic"],
+        )
+        .unwrap();
+
+        registry
+            .register(Box::new(http_requests_total.clone()))
+            .unwrap();
+        registry
+            .register(Box::new(http_request_duration_seconds.clone()))
+            .unwrap();
+        registry
+            .register(Box::new(db_pool_connections.clone()))
+            .unwrap();
+        registry
+            .register(Box::new(password_hash_duration_seconds.clone()))
+            .unwrap();
+        registry
+            .register(Box::new(validation_failures_total.clone()))
+            .unwrap();
+        registry
+            .register(Box::new(user_events_total.clone()))
+            .unwrap();
+
+        Metrics {
+            registry,
+            http_requests_total,
+            http_request_duration_seconds,
+            db_pool_connections,
+            password_hash_duration_seconds,
+            validation_failures_total,
+            user_events_total,
+        }
+    }
+
+    // Hanya membaca status pool, scrape tidak boleh mengambil koneksi dari request
+    pub fn observe_pool(&self, pool: &MySqlPool) {
+        let size = pool.size() as i64;
+        let idle = pool.num_idle() as i64;
+        let max = pool.options().get_max_connections() as i64;
+        self.db_pool_connections
+            .with_label_values(&["size"])
+            .set(size);
+        self.db_pool_connections
+            .with_label_values(&["idle"])
+            .set(idle);
+        self.db_pool_connections
+            .with_label_values(&["in_use"])
+            .set(size - idle);
+        self.db_pool_connections
+            .with_label_values(&["max"])
+            .set(max);
+    }
+
+    pub fn render(&self) -> Result<String, String> {
+        let mut buffer = Vec::new();
+        TextEncoder::new()
+            .encode(&self.registry.gather(), &mut buffer)
+            .map_err(|err| err.to_string())?;
+        String::from_utf8(buffer).map_err(|err| err.to_string())
+    }
+}
+
+pub async fn track_metrics(req: Request, next: Next) -> Response {
+    let method = req.method().to_string();
+    let route = req
+        .extensions()
+        .get::<MatchedPath>()
+        .map(|path| path.as_str().to_string())
+        .unwrap_or_else(|| "unmatched".to_string());
+
+    let start = Instant::now();
+    let response = next.run(req).await;
+    let status = response.status().as_u16().to_string();
+    let labels = [method.as_str(), route.as_str(), status.as_str()];
+
+    metrics()
+        .http_requests_total
+        .with_label_values(&labels)
+        .inc();
+    metrics()
+        .http_request_duration_seconds
+        .with_label_values(&labels)
+        .observe(start.elapsed().as_secs_f64());
+
+    response
+}
+
+// Menghitung event siklus hidup pengguna dari Observable
+pub struct MetricsObserver;
+
+impl Observer for MetricsObserver {
+    fn update(&self, _message: &str) {}
+
+    fn on_event(&self, event: &Event) {
+        metrics()
+            .user_events_total
+            .with_label_values(&[&event.topic])
+            .inc();
+    }
+}
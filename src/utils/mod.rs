@@ -1,5 +1,27 @@
 pub mod adapter;
+pub mod audit;
+pub mod auth;
+pub mod context;
+pub mod crypto;
+pub mod cursor;
+pub mod etag;
+pub mod event_hub;
+pub mod idempotency;
+pub mod lockout;
+pub mod logging;
+pub mod mailer;
+pub mod metrics;
 pub mod observer;
+pub mod password_hasher;
+pub mod presence;
+pub mod rate_limit;
+pub mod recording;
+pub mod simulation;
 pub mod singleton;
+pub mod sms;
+pub mod token;
+pub mod totp;
 pub mod validation_chain;
+pub mod versioning;
 pub mod factory;
+pub mod hashing;
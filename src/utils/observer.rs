@@ -1,16 +1,77 @@
 use std::sync::{Arc, Mutex};
 
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+
+use crate::utils::context::RequestContext;
+
+// Event terstruktur yang dikirim ke observer, mis. "user.created"
+#[derive(Debug, Clone, Serialize)]
+pub struct Event {
+    pub topic: String,
+    pub user_id: Option<i32>,
+    pub data: serde_json::Value,
+    pub at: DateTime<Utc>,
+    // Diff before/after, hanya untuk observer internal (audit)
+    #[serde(skip)]
+    pub changes: Option<serde_json::Value>,
+    #[serde(skip)]
+    pub context: Option<RequestContext>,
+}
+
+impl Event {
+    pub fn new(topic: &str, user_id: Option<i32>, data: serde_json::Value) -> Self {
+        Event {
+            topic: topic.to_string(),
+            user_id,
+            data,
+            at: Utc::now(),
+            changes: None,
+            context: RequestContext::current(),
+        }
+    }
+
+    pub fn with_changes(mut self, changes: serde_json::Value) -> Self {
+        self.changes = Some(changes);
+        self
+    }
+
+    pub fn message(&self) -> String {
+        match self.user_id {
+            Some(id) => format!("{} event for user {}", self.topic, id),
+            None => format!("{} event", self.topic),
+        }
+    }
+}
+
 // Define trait Observer
 pub trait Observer {
     fn update(&self, message: &str);
+
+    // Default: event diteruskan sebagai pesan biasa
+    fn on_event(&self, event: &Event) {
+        self.update(&event.message());
+    }
 }
 
-// Implementasi Observer untuk LoggerObserver
+// Implementasi Observer untuk LoggerObserver, ditulis lewat tracing
 pub struct LoggerObserver;
 
 impl Observer for LoggerObserver {
     fn update(&self, message: &str) {
-        println!("Logging: {}", message);
+        let request_id = RequestContext::current().and_then(|ctx| ctx.request_id);
+        tracing::info!(request_id = request_id.as_deref(), "{}", message);
+    }
+
+    fn on_event(&self, event: &Event) {
+        let request_id = event.context.as_ref().and_then(|ctx| ctx.request_id.as_deref());
+        tracing::info!(
+            request_id,
+            topic = %event.topic,
+            user_id = event.user_id,
+            "{}",
+            event.message()
+        );
     }
 }
 
@@ -42,4 +103,11 @@ impl Observable {
             observer.update(message);
         }
     }
-}
\ No newline at end of file
+
+    // Method untuk mengirim event terstruktur
+    pub fn notify_event(&self, event: Event) {
+        for observer in self.observers.lock().unwrap().iter() {
+            observer.on_event(&event);
+        }
+    }
+}
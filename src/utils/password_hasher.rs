@@ -0,0 +1,188 @@
+use std::sync::Arc;
+
+use argon2::{
+    password_hash::{
+        rand_core::OsRng, PasswordHash, PasswordHasher as _, PasswordVerifier, SaltString,
+    },
+    Algorithm, Argon2, Params, Version,
+};
+
+pub trait PasswordHasher: Send + Sync {
+    // Whether the stored hash was produced by this algorithm
+    fn matches(&self, hash: &str) -> bool;
+    // Whether the stored hash uses the parameters configured for this hasher
+    fn is_current(&self, hash: &str) -> bool;
+    fn hash(&self, password: &str) -> Result<String, String>;
+    fn verify(&self, password: &str, hash: &str) -> Result<bool, String>;
+}
+
+pub struct BcryptHasher {
+    cost: u32,
+}
+
+impl PasswordHasher for BcryptHasher {
+    fn matches(&self, hash: &str) -> bool {
+        ["$2a$", "$2b$", "$2x$", "$2y$"]
+            .iter()
+            .any(|prefix| hash.starts_with(prefix))
+    }
+
+    fn is_current(&self, hash: &str) -> bool {
+        // $2b$10$...
+        hash.get(4..6).and_then(|cost| cost.parse::<u32>().ok()) == Some(self.cost)
+    }
+
+    fn hash(&self, password: &str) -> Result<String, String> {
+        bcrypt::hash(password, self.cost).map_err(|err| err.to_string())
+    }
+
+    fn verify(&self, password: &str, hash: &str) -> Result<bool, String> {
+        bcrypt::verify(password, hash).map_err(|err| err.to_string())
+    }
+}
+
+pub struct Argon2idHasher {
+    params: Params,
+}
+
+impl Argon2idHasher {
+    fn argon2(&self) -> Argon2<'static> {
+        Argon2::new(Algorithm::Argon2id, Version::V0x13, self.params.clone())
+    }
+}
+
+impl PasswordHasher for Argon2idHasher {
+    fn matches(&self, hash: &str) -> bool {
+        hash.starts_with("$argon2id$")
+    }
+
+    fn is_current(&self, hash: &str) -> bool {
+        let parsed = match PasswordHash::new(hash) {
+            Ok(parsed) => parsed,
+            Err(_) => return false,
+        };
+        let params = match Params::try_from(&parsed) {
+            Ok(params) => params,
+            Err(_) => return false,
+        };
+
+        parsed.version == Some(Version::V0x13.into())
+            && params.m_cost() == self.params.m_cost()
+            && params.t_cost() == self.params.t_cost()
+            && params.p_cost() == self.params.p_cost()
+    }
+
+    fn hash(&self, password: &str) -> Result<String, String> {
+        let salt = SaltString::generate(&mut OsRng);
+        self.argon2()
+            .hash_password(password.as_bytes(), &salt)
+            .map(|hash| hash.to_string())
+            .map_err(|err| err.to_string())
+    }
+
+    fn verify(&self, password: &str, hash: &str) -> Result<bool, String> {
+        let parsed = PasswordHash::new(hash).map_err(|err| err.to_string())?;
+        match self.argon2().verify_password(password.as_bytes(), &parsed) {
+            Ok(()) => Ok(true),
+            Err(argon2::password_hash::Error::Password) => Ok(false),
+            Err(err) => Err(err.to_string()),
+        }
+    }
+}
+
+fn env_or<T: std::str::FromStr>(key: &str, default: T) -> T {
+    std::env::var(key)
+        .ok()
+        .and_then(|v| v.parse().ok())
+        .unwrap_or(default)
+}
+
+// Rentang cost yang diterima crate bcrypt
+const BCRYPT_COSTS: std::ops::RangeInclusive<u32> = 4..=31;
+
+pub fn new_bcrypt_hasher() -> Result<Arc<dyn PasswordHasher>, String> {
+    let cost = env_or("BCRYPT_COST", 10);
+    if !BCRYPT_COSTS.contains(&cost) {
+        return Err(format!(
+            "Invalid BCRYPT_COST {}, expected {}-{}",
+            cost,
+            BCRYPT_COSTS.start(),
+            BCRYPT_COSTS.end()
+        ));
+    }
+    Ok(Arc::new(BcryptHasher { cost }))
+}
+
+fn argon2id_params(memory_kib: u32, iterations: u32, parallelism: u32) -> Result<Params, String> {
+    Params::new(memory_kib, iterations, parallelism, None)
+        .map_err(|err| format!("Invalid ARGON2_* parameters: {}", err))
+}
+
+pub fn new_argon2id_hasher() -> Result<Arc<dyn PasswordHasher>, String> {
+    let params = argon2id_params(
+        env_or("ARGON2_MEMORY_KIB", 19456),
+        env_or("ARGON2_ITERATIONS", 2),
+        env_or("ARGON2_PARALLELISM", 1),
+    )?;
+    Ok(Arc::new(Argon2idHasher { params }))
+}
+
+// PASSWORD_HASHER=argon2id|bcrypt, dipakai untuk hash baru
+pub fn new_password_hasher() -> Result<Arc<dyn PasswordHasher>, String> {
+    dotenv::dotenv().ok();
+    match std::env::var("PASSWORD_HASHER").as_deref() {
+        Ok("bcrypt") => new_bcrypt_hasher(),
+        _ => new_argon2id_hasher(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Parameter kecil agar test cepat
+    fn argon2id(iterations: u32) -> Argon2idHasher {
+        Argon2idHasher {
+            params: argon2id_params(8, iterations, 1).unwrap(),
+        }
+    }
+
+    #[test]
+    fn argon2id_matches_and_is_current() {
+        let hasher = argon2id(1);
+        let hash = hasher.hash("Password123!").unwrap();
+
+        assert!(hasher.matches(&hash));
+        assert!(hasher.is_current(&hash));
+        assert!(!argon2id(2).is_current(&hash));
+        assert!(!hasher.matches("$2b$04$abcdefghijklmnopqrstuu"));
+        assert!(!hasher.matches("$argon2i$v=19$m=8,t=1,p=1$c2FsdA$aGFzaA"));
+        assert!(!hasher.is_current("bukan hash"));
+        assert!(hasher.verify("Password123!", &hash).unwrap());
+        assert!(!hasher.verify("salah", &hash).unwrap());
+    }
+
+    #[test]
+    fn bcrypt_matches_and_is_current() {
+        let hasher = BcryptHasher { cost: 4 };
+        let hash = hasher.hash("Password123!").unwrap();
+
+        assert!(hasher.matches(&hash));
+        assert!(hasher.is_current(&hash));
+        assert!(!BcryptHasher { cost: 5 }.is_current(&hash));
+        for prefix in ["$2a$", "$2x$", "$2y$"] {
+            assert!(hasher.matches(&format!("{}04$abc", prefix)));
+        }
+        assert!(!hasher.matches(&argon2id(1).hash("Password123!").unwrap()));
+        assert!(hasher.verify("Password123!", &hash).unwrap());
+        assert!(!hasher.verify("salah", &hash).unwrap());
+    }
+
+    #[test]
+    fn invalid_argon2_parameters_are_errors() {
+        assert!(argon2id_params(19456, 2, 1).is_ok());
+        assert!(argon2id_params(1, 2, 1).is_err());
+        assert!(argon2id_params(19456, 0, 1).is_err());
+        assert!(argon2id_params(19456, 2, 0).is_err());
+    }
+}
@@ -0,0 +1,94 @@
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct PresenceStatus {
+    pub user_id: i32,
+    pub online: bool,
+    pub connections: usize,
+    pub connected_at: Option<DateTime<Utc>>,
+    pub last_seen: Option<DateTime<Utc>>,
+}
+
+struct PresenceEntry {
+    connections: usize,
+    connected_at: Option<DateTime<Utc>>,
+    last_seen: DateTime<Utc>,
+}
+
+// Melacak pengguna terautentikasi yang sedang terhubung lewat WebSocket
+pub struct Presence {
+    entries: Mutex<HashMap<i32, PresenceEntry>>,
+}
+
+impl Presence {
+    pub fn new() -> Self {
+        Presence {
+            entries: Mutex::new(HashMap::new()),
+        }
+    }
+
+    // Returns true when this is the first open connection of the user
+    pub fn connect(&self, user_id: i32) -> bool {
+        let now = Utc::now();
+        let mut entries = self.entries.lock().unwrap();
+        let entry = entries.entry(user_id).or_insert(PresenceEntry {
+            connections: 0,
+            connected_at: None,
+            last_seen: now,
+        });
+        entry.connections += 1;
+        entry.last_seen = now;
+        if entry.connections == 1 {
+            entry.connected_at = Some(now);
+            return true;
+        }
+        false
+    }
+
+    // Returns true when the last open connection of the user was closed
+    pub fn disconnect(&self, user_id: i32) -> bool {
+        let mut entries = self.entries.lock().unwrap();
+        match entries.get_mut(&user_id) {
+            Some(entry) if entry.connections > 0 => {
+                entry.connections -= 1;
+                entry.last_seen = Utc::now();
+                if entry.connections == 0 {
+                    entry.connected_at = None;
+                    return true;
+                }
+                false
+            }
+            _ => false,
+        }
+    }
+
+    pub fn touch(&self, user_id: i32) {
+        if let Some(entry) = self.entries.lock().unwrap().get_mut(&user_id) {
+            entry.last_seen = Utc::now();
+        }
+    }
+
+    pub fn status(&self, user_id: i32) -> PresenceStatus {
+        let entries = self.entries.lock().unwrap();
+        match entries.get(&user_id) {
+            Some(entry) => PresenceStatus {
+                user_id,
+                online: entry.connections > 0,
+                connections: entry.connections,
+                connected_at: entry.connected_at,
+                last_seen: Some(entry.last_seen),
+            },
+            None => PresenceStatus {
+                user_id,
+                online: false,
+                connections: 0,
+                connected_at: None,
+                last_seen: None,
+            },
+        }
+    }
+}
@@ -0,0 +1,431 @@
+use std::collections::HashMap;
+use std::str::FromStr;
+use std::sync::{Arc, LazyLock, Mutex};
+use std::time::{Duration, Instant};
+
+use axum::{
+    async_trait,
+    extract::{MatchedPath, Request},
+    http::{header::RETRY_AFTER, HeaderMap, HeaderValue, StatusCode},
+    middleware::Next,
+    response::{IntoResponse, Response},
+    Json,
+};
+
+use crate::utils::{
+    auth::{api_key, api_key_prefix, authenticate_api_key, bearer_token},
+    context::RequestContext,
+    versioning::unversioned_route,
+};
+
+// "10/60" berarti 10 request per 60 detik, dengan burst sampai 10
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Limit {
+    pub capacity: u32,
+    pub period: Duration,
+}
+
+impl Limit {
+    fn refill_per_second(&self) -> f64 {
+        self.capacity as f64 / self.period.as_secs_f64()
+    }
+}
+
+impl FromStr for Limit {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        let (capacity, seconds) = value
+            .trim()
+            .split_once('/')
+            .ok_or_else(|| format!("Invalid rate limit '{}', expected N/SECONDS", value))?;
+        let capacity: u32 = capacity
+            .trim()
+            .parse()
+            .map_err(|_| format!("Invalid rate limit '{}'", value))?;
+        let seconds: u64 = seconds
+            .trim()
+            .parse()
+            .map_err(|_| format!("Invalid rate limit '{}'", value))?;
+        if capacity == 0 || seconds == 0 {
+            return Err(format!("Invalid rate limit '{}'", value));
+        }
+
+        Ok(Limit {
+            capacity,
+            period: Duration::from_secs(seconds),
+        })
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct Decision {
+    pub allowed: bool,
+    pub limit: u32,
+    pub remaining: u32,
+    // Sampai bucket penuh kembali
+    pub reset_after: Duration,
+    // Sampai satu token tersedia, hanya bila ditolak
+    pub retry_after: Option<Duration>,
+}
+
+// Penyimpanan bucket, bisa diganti mis. dengan Redis agar berlaku lintas instance
+#[async_trait]
+pub trait RateLimitStore: Send + Sync {
+    async fn acquire(&self, key: &str, limit: Limit) -> Decision;
+}
+
+struct Bucket {
+    tokens: f64,
+    capacity: f64,
+    refill_per_second: f64,
+    updated: Instant,
+}
+
+impl Bucket {
+    fn refill(&mut self, now: Instant) {
+        let elapsed = now.duration_since(self.updated).as_secs_f64();
+        self.tokens = (self.tokens + elapsed * self.refill_per_second).min(self.capacity);
+        self.updated = now;
+    }
+
+    // Bucket yang sudah penuh lagi sama dengan bucket baru
+    fn is_full_at(&self, now: Instant) -> bool {
+        let elapsed = now.duration_since(self.updated).as_secs_f64();
+        self.tokens + elapsed * self.refill_per_second >= self.capacity
+    }
+}
+
+// Batas jumlah key yang disimpan
+const MEMORY_STORE_MAX_KEYS: usize = 10_000;
+
+pub struct MemoryStore {
+    max_keys: usize,
+    buckets: Mutex<HashMap<String, Bucket>>,
+}
+
+impl Default for MemoryStore {
+    fn default() -> Self {
+        MemoryStore::with_max_keys(MEMORY_STORE_MAX_KEYS)
+    }
+}
+
+// Dipanggil saat penuh: bucket yang sudah penuh lagi dibuang lebih dulu karena membuangnya
+// tidak mengubah hasil; bila semua masih dipakai, sepersepuluh yang paling lama tidak
+// dipakai (LRU) ikut dibuang
+fn evict(buckets: &mut HashMap<String, Bucket>, max_keys: usize, now: Instant) {
+    buckets.retain(|_, bucket| !bucket.is_full_at(now));
+    if buckets.len() < max_keys {
+        return;
+    }
+
+    let count = (max_keys / 10).clamp(1, buckets.len());
+    let mut used: Vec<(Instant, &String)> = buckets
+        .iter()
+        .map(|(key, bucket)| (bucket.updated, key))
+        .collect();
+    used.select_nth_unstable_by_key(count - 1, |(updated, _)| *updated);
+    let oldest: Vec<String> = used[..count]
+        .iter()
+        .map(|(_, key)| key.to_string())
+        .collect();
+    for key in oldest {
+        buckets.remove(&key);
+    }
+}
+
+impl MemoryStore {
+    pub fn with_max_keys(max_keys: usize) -> Self {
+        MemoryStore {
+            max_keys,
+            buckets: Mutex::new(HashMap::new()),
+        }
+    }
+
+    fn acquire_at(&self, key: &str, limit: Limit, now: Instant) -> Decision {
+        let mut buckets = self.buckets.lock().unwrap();
+        if !buckets.contains_key(key) && buckets.len() >= self.max_keys {
+            evict(&mut buckets, self.max_keys, now);
+        }
+
+        let bucket = buckets.entry(key.to_string()).or_insert_with(|| Bucket {
+            tokens: limit.capacity as f64,
+            capacity: limit.capacity as f64,
+            refill_per_second: limit.refill_per_second(),
+            updated: now,
+        });
+        bucket.refill(now);
+
+        let allowed = bucket.tokens >= 1.0;
+        if allowed {
+            bucket.tokens -= 1.0;
+        }
+        let missing = bucket.capacity - bucket.tokens;
+
+        Decision {
+            allowed,
+            limit: limit.capacity,
+            remaining: bucket.tokens.floor() as u32,
+            reset_after: Duration::from_secs_f64(missing / bucket.refill_per_second),
+            retry_after: (!allowed)
+                .then(|| Duration::from_secs_f64((1.0 - bucket.tokens) / bucket.refill_per_second)),
+        }
+    }
+}
+
+#[async_trait]
+impl RateLimitStore for MemoryStore {
+    async fn acquire(&self, key: &str, limit: Limit) -> Decision {
+        self.acquire_at(key, limit, Instant::now())
+    }
+}
+
+pub struct RateLimiter {
+    store: Arc<dyn RateLimitStore>,
+    default: Option<Limit>,
+    // "METHOD /route" -> limit
+    routes: HashMap<String, Limit>,
+}
+
+// Pendaftaran dan login menjalankan hashing password, jadi dibatasi lebih ketat
+const DEFAULT_ROUTE_LIMITS: &str = "POST /api/user=10/60,POST /api/auth/login=20/60";
+
+fn parse_routes(value: &str) -> HashMap<String, Limit> {
+    value
+        .split(',')
+        .filter(|entry| !entry.trim().is_empty())
+        .filter_map(|entry| {
+            let (route, limit) = entry.rsplit_once('=')?;
+            match limit.parse::<Limit>() {
+                Ok(limit) => Some((route.trim().to_string(), limit)),
+                Err(err) => {
+                    tracing::warn!("Ignoring RATE_LIMIT_ROUTES entry: {}", err);
+                    None
+                }
+            }
+        })
+        .collect()
+}
+
+// RATE_LIMIT_DEFAULT=120/60 (atau "off"), RATE_LIMIT_ROUTES="POST /api/user=10/60,..."
+static RATE_LIMITER: LazyLock<RateLimiter> = LazyLock::new(|| {
+    dotenv::dotenv().ok();
+    let default = match std::env::var("RATE_LIMIT_DEFAULT").as_deref() {
+        Ok("off") => None,
+        Ok(value) => value.parse().ok(),
+        Err(_) => Some(Limit {
+            capacity: 120,
+            period: Duration::from_secs(60),
+        }),
+    };
+    let routes =
+        std::env::var("RATE_LIMIT_ROUTES").unwrap_or_else(|_| DEFAULT_ROUTE_LIMITS.to_string());
+
+    RateLimiter {
+        store: Arc::new(MemoryStore::default()),
+        default,
+        routes: parse_routes(&routes),
+    }
+});
+
+// Bucket ditentukan sebelum API key diverifikasi agar request yang ditolak tidak sampai ke
+// database: pengguna dari bearer token, prefix API key, lalu IP
+fn bucket_client(ctx: &RequestContext, headers: &HeaderMap) -> String {
+    if ctx.actor.is_none() && bearer_token(headers).is_none() {
+        if let Some(prefix) = api_key(headers).and_then(api_key_prefix) {
+            return format!("prefix:{}", prefix);
+        }
+    }
+    ctx.client_id()
+}
+
+// Hanya untuk request yang lolos limit; key yang tidak valid diperlakukan seperti tanpa key
+async fn verified_context(mut ctx: RequestContext, headers: &HeaderMap) -> RequestContext {
+    if ctx.actor.is_none() && bearer_token(headers).is_none() {
+        if let Some(key) = api_key(headers) {
+            ctx.actor = authenticate_api_key(key).await.ok();
+        }
+    }
+    ctx
+}
+
+fn insert_header(headers: &mut HeaderMap, name: &'static str, value: u64) {
+    headers.insert(name, HeaderValue::from(value));
+}
+
+fn ceil_secs(duration: Duration) -> u64 {
+    duration.as_secs_f64().ceil() as u64
+}
+
+fn rate_limit_headers(headers: &mut HeaderMap, decision: &Decision) {
+    insert_header(headers, "ratelimit-limit", decision.limit as u64);
+    insert_header(headers, "ratelimit-remaining", decision.remaining as u64);
+    insert_header(headers, "ratelimit-reset", ceil_secs(decision.reset_after));
+    if let Some(retry_after) = decision.retry_after {
+        headers.insert(
+            RETRY_AFTER,
+            HeaderValue::from(ceil_secs(retry_after).max(1)),
+        );
+    }
+}
+
+pub async fn rate_limit(req: Request, next: Next) -> Response {
+    let limiter = &*RATE_LIMITER;
+    let route = req
+        .extensions()
+        .get::<MatchedPath>()
+        .map(|path| format!("{} {}", req.method(), unversioned_route(path.as_str())));
+    let ctx = RequestContext::current().unwrap_or_default();
+    let client = bucket_client(&ctx, req.headers());
+
+    let mut checks = Vec::new();
+    if let Some(limit) = limiter.default {
+        checks.push((format!("*|{}", client), limit));
+    }
+    if let Some(route) = &route {
+        if let Some(limit) = limiter.routes.get(route) {
+            checks.push((format!("{}|{}", route, client), *limit));
+        }
+    }
+
+    // Header mengikuti batas yang paling ketat
+    let mut tightest: Option<Decision> = None;
+    for (key, limit) in checks {
+        let decision = limiter.store.acquire(&key, limit).await;
+        let tighter = tightest.is_none_or(|current| {
+            !decision.allowed || (current.allowed && decision.remaining < current.remaining)
+        });
+        if tighter {
+            tightest = Some(decision);
+        }
+        if !decision.allowed {
+            break;
+        }
+    }
+
+    let decision = match tightest {
+        Some(decision) => decision,
+        None => {
+            let ctx = verified_context(ctx, req.headers()).await;
+            return ctx.scope(next.run(req)).await;
+        }
+    };
+
+    if !decision.allowed {
+        tracing::warn!(client = %client, route = ?route, "Rate limit exceeded");
+        let json_response = serde_json::json!({
+            "status": "error",
+            "message": "Too many requests, please try again later"
+        });
+        let mut response = (StatusCode::TOO_MANY_REQUESTS, Json(json_response)).into_response();
+        rate_limit_headers(response.headers_mut(), &decision);
+        return response;
+    }
+
+    // API key yang sudah diverifikasi ikut dicatat sebagai actor
+    let ctx = verified_context(ctx, req.headers()).await;
+    let mut response = ctx.scope(next.run(req)).await;
+    rate_limit_headers(response.headers_mut(), &decision);
+    response
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn limit(capacity: u32, seconds: u64) -> Limit {
+        Limit {
+            capacity,
+            period: Duration::from_secs(seconds),
+        }
+    }
+
+    #[test]
+    fn limit_parsing() {
+        assert_eq!(" 10 / 60 ".parse::<Limit>(), Ok(limit(10, 60)));
+        assert!("10".parse::<Limit>().is_err());
+        assert!("0/60".parse::<Limit>().is_err());
+        assert!("10/0".parse::<Limit>().is_err());
+        assert!("ten/60".parse::<Limit>().is_err());
+
+        let routes = parse_routes("POST /api/user=10/60, GET /api/user=bad,");
+        assert_eq!(routes.len(), 1);
+        assert_eq!(routes.get("POST /api/user"), Some(&limit(10, 60)));
+    }
+
+    #[test]
+    fn memory_store_refills_over_time() {
+        let store = MemoryStore::default();
+        let limit = limit(2, 10);
+        let start = Instant::now();
+
+        assert!(store.acquire_at("a", limit, start).allowed);
+        assert!(store.acquire_at("a", limit, start).allowed);
+        let denied = store.acquire_at("a", limit, start);
+        assert!(!denied.allowed);
+        assert_eq!(denied.retry_after, Some(Duration::from_secs(5)));
+        // Key lain punya bucket sendiri
+        assert!(store.acquire_at("b", limit, start).allowed);
+
+        let later = start + Duration::from_secs(5);
+        let decision = store.acquire_at("a", limit, later);
+        assert!(decision.allowed);
+        assert_eq!(decision.remaining, 0);
+        assert!(!store.acquire_at("a", limit, later).allowed);
+    }
+
+    #[test]
+    fn memory_store_evicts_idle_then_least_recently_used() {
+        let limit = limit(1, 60);
+        let now = Instant::now();
+
+        // Bucket yang sudah penuh lagi dibuang lebih dulu
+        let store = MemoryStore::with_max_keys(2);
+        store.acquire_at("a", limit, now);
+        store.acquire_at("b", limit, now);
+        store.acquire_at("c", limit, now + Duration::from_secs(60));
+        assert_eq!(store.buckets.lock().unwrap().len(), 1);
+
+        // Semua masih dipakai: yang paling lama tidak dipakai yang dibuang
+        let store = MemoryStore::with_max_keys(2);
+        store.acquire_at("a", limit, now);
+        store.acquire_at("b", limit, now + Duration::from_secs(1));
+        assert!(
+            !store
+                .acquire_at("a", limit, now + Duration::from_secs(2))
+                .allowed
+        );
+        store.acquire_at("c", limit, now + Duration::from_secs(3));
+
+        let buckets = store.buckets.lock().unwrap();
+        assert_eq!(buckets.len(), 2);
+        assert!(!buckets.contains_key("b"));
+        drop(buckets);
+        // Bucket yang masih aktif tidak ikut dibuang
+        assert!(
+            !store
+                .acquire_at("a", limit, now + Duration::from_secs(3))
+                .allowed
+        );
+    }
+
+    #[test]
+    fn bucket_uses_key_prefix_before_verification() {
+        let ctx = RequestContext {
+            ip: Some("10.0.0.1".to_string()),
+            ..Default::default()
+        };
+        let headers = |key: &str| {
+            let mut headers = HeaderMap::new();
+            headers.insert("x-api-key", HeaderValue::from_str(key).unwrap());
+            headers
+        };
+
+        assert_eq!(
+            bucket_client(&ctx, &headers("simu_0a1b2c3d_rahasia")),
+            "prefix:0a1b2c3d"
+        );
+        assert_eq!(bucket_client(&ctx, &headers("bukan-key")), "ip:10.0.0.1");
+        assert_eq!(bucket_client(&ctx, &HeaderMap::new()), "ip:10.0.0.1");
+    }
+}
@@ -0,0 +1,558 @@
+use axum::{
+    body::{to_bytes, Body, Bytes, HttpBody},
+    extract::{Query, Request},
+    http::{header::CONTENT_TYPE, HeaderMap, HeaderName, HeaderValue, StatusCode, Uri},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::{
+    collections::{BTreeMap, HashMap},
+    io::Write,
+    path::Path,
+    sync::{LazyLock, Mutex},
+};
+
+// Pengganti nilai sensitif; saat replay nilai ini cocok dengan nilai apa pun
+pub const REDACTED: &str = "[REDACTED]";
+
+const SENSITIVE_FIELDS: [&str; 8] = [
+    "password",
+    "token",
+    "secret",
+    "key",
+    "code",
+    "otpauth_uri",
+    "recovery_code",
+    "recovery_codes",
+];
+const SENSITIVE_SUFFIXES: [&str; 4] = ["_password", "_token", "_secret", "_code"];
+const SENSITIVE_HEADERS: [&str; 4] = ["authorization", "x-api-key", "cookie", "set-cookie"];
+
+// Hanya header yang relevan dan stabil yang disimpan, agar rekaman tidak berubah
+// setiap kali direkam ulang (x-request-id, date, ratelimit-remaining, dsb.)
+const REQUEST_HEADERS: [&str; 8] = [
+    "accept",
+    "authorization",
+    "content-type",
+    "cookie",
+    "idempotency-key",
+    "if-match",
+    "if-none-match",
+    "x-api-key",
+];
+const RESPONSE_HEADERS: [&str; 10] = [
+    "content-disposition",
+    "content-type",
+    "deprecation",
+    "etag",
+    "idempotent-replayed",
+    "link",
+    "location",
+    "retry-after",
+    "sunset",
+    "x-simulated-fault",
+];
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RecordedRequest {
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub headers: BTreeMap<String, String>,
+    #[serde(default, skip_serializing_if = "Value::is_null")]
+    pub body: Value,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RecordedResponse {
+    pub status: u16,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub headers: BTreeMap<String, String>,
+    #[serde(default, skip_serializing_if = "Value::is_null")]
+    pub body: Value,
+}
+
+// Satu baris NDJSON. Urutan field tetap dan key object diurutkan, jadi merekam ulang
+// alur yang sama menghasilkan file yang sama.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Recording {
+    // "METHOD /path #N", N = pemanggilan ke-N untuk method dan path itu
+    pub id: String,
+    pub method: String,
+    pub path: String,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub query: BTreeMap<String, String>,
+    pub request: RecordedRequest,
+    pub response: RecordedResponse,
+}
+
+fn env_or<T: std::str::FromStr>(name: &str, default: T) -> T {
+    std::env::var(name)
+        .ok()
+        .and_then(|v| v.parse().ok())
+        .unwrap_or(default)
+}
+
+fn max_body_bytes() -> usize {
+    env_or("RECORD_MAX_BODY_BYTES", 1024 * 1024)
+}
+
+// RECORD_REDACT menambah nama field yang disamarkan, mis. "nik,birth_date"
+static EXTRA_SENSITIVE_FIELDS: LazyLock<Vec<String>> = LazyLock::new(|| {
+    std::env::var("RECORD_REDACT")
+        .unwrap_or_default()
+        .split(',')
+        .map(|field| field.trim().to_ascii_lowercase())
+        .filter(|field| !field.is_empty())
+        .collect()
+});
+
+fn is_sensitive(name: &str) -> bool {
+    let name = name.to_ascii_lowercase();
+    SENSITIVE_FIELDS.contains(&name.as_str())
+        || SENSITIVE_SUFFIXES
+            .iter()
+            .any(|suffix| name.ends_with(suffix))
+        || EXTRA_SENSITIVE_FIELDS.contains(&name)
+}
+
+pub fn redact(value: &mut Value) {
+    match value {
+        Value::Object(map) => {
+            for (key, value) in map.iter_mut() {
+                if is_sensitive(key) && !value.is_null() {
+                    *value = Value::String(REDACTED.to_string());
+                } else {
+                    redact(value);
+                }
+            }
+        }
+        Value::Array(items) => items.iter_mut().for_each(redact),
+        _ => {}
+    }
+}
+
+fn recorded_headers(headers: &HeaderMap, names: &[&str]) -> BTreeMap<String, String> {
+    names
+        .iter()
+        .filter_map(|name| {
+            let value = headers.get(*name)?.to_str().ok()?;
+            let value = if SENSITIVE_HEADERS.contains(name) {
+                REDACTED
+            } else {
+                value
+            };
+            Some((name.to_string(), value.to_string()))
+        })
+        .collect()
+}
+
+pub fn query_map(uri: &Uri) -> BTreeMap<String, String> {
+    Query::<Vec<(String, String)>>::try_from_uri(uri)
+        .map(|Query(pairs)| pairs.into_iter().collect())
+        .unwrap_or_default()
+}
+
+// JSON disimpan sebagai object, teks sebagai string, selain itu hanya ukurannya
+pub fn body_value(headers: &HeaderMap, bytes: &Bytes) -> Value {
+    if bytes.is_empty() {
+        return Value::Null;
+    }
+    let content_type = headers
+        .get(CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok())
+        .unwrap_or_default();
+    if content_type.contains("json") {
+        if let Ok(value) = serde_json::from_slice(bytes) {
+            return value;
+        }
+    }
+    match std::str::from_utf8(bytes) {
+        Ok(text) if content_type.starts_with("text/") => Value::String(text.to_string()),
+        _ => Value::String(format!("[{} bytes]", bytes.len())),
+    }
+}
+
+struct Recorder {
+    file: std::fs::File,
+    calls: HashMap<String, u64>,
+}
+
+// RECORD_FILE mengaktifkan perekaman; file dikosongkan saat startup
+static RECORDER: LazyLock<Option<Mutex<Recorder>>> = LazyLock::new(|| {
+    dotenv::dotenv().ok();
+    let path = std::env::var("RECORD_FILE").ok()?;
+    let path = Path::new(path.trim());
+    let file = path
+        .parent()
+        .filter(|dir| !dir.as_os_str().is_empty())
+        .map_or(Ok(()), std::fs::create_dir_all)
+        .and_then(|_| std::fs::File::create(path));
+    match file {
+        Ok(file) => Some(Mutex::new(Recorder {
+            file,
+            calls: HashMap::new(),
+        })),
+        Err(err) => {
+            tracing::error!("❌ Failed to open {}: {}", path.display(), err);
+            std::process::exit(1);
+        }
+    }
+});
+
+pub fn recording_enabled() -> bool {
+    RECORDER.is_some()
+}
+
+fn write_recording(mut recording: Recording) {
+    let Some(recorder) = RECORDER.as_ref() else {
+        return;
+    };
+    let mut recorder = recorder.lock().unwrap();
+    let route = format!("{} {}", recording.method, recording.path);
+    let call = recorder.calls.entry(route.clone()).or_default();
+    *call += 1;
+    recording.id = format!("{} #{}", route, call);
+
+    let line = match serde_json::to_string(&recording) {
+        Ok(line) => line,
+        Err(err) => {
+            tracing::error!("Failed to serialize recording: {}", err);
+            return;
+        }
+    };
+    if let Err(err) = writeln!(recorder.file, "{}", line) {
+        tracing::error!("Failed to write recording: {}", err);
+    }
+}
+
+fn fits(body: &impl HttpBody, limit: usize) -> bool {
+    body.size_hint()
+        .upper()
+        .is_some_and(|size| size as usize <= limit)
+}
+
+// Merekam pasangan request/response ke RECORD_FILE. Body streaming (export, SSE),
+// upgrade WebSocket dan body di atas RECORD_MAX_BODY_BYTES dilewati.
+pub async fn record_traffic(req: Request, next: Next) -> Response {
+    let limit = max_body_bytes();
+    if !fits(req.body(), limit) {
+        return next.run(req).await;
+    }
+
+    let (parts, body) = req.into_parts();
+    let bytes = match to_bytes(body, limit).await {
+        Ok(bytes) => bytes,
+        Err(err) => return (StatusCode::BAD_REQUEST, err.to_string()).into_response(),
+    };
+    let method = parts.method.to_string();
+    let path = parts.uri.path().to_string();
+    let mut query = query_map(&parts.uri);
+    query
+        .iter_mut()
+        .filter(|(key, _)| is_sensitive(key))
+        .for_each(|(_, value)| *value = REDACTED.to_string());
+    let mut request_body = body_value(&parts.headers, &bytes);
+    redact(&mut request_body);
+    let request = RecordedRequest {
+        headers: recorded_headers(&parts.headers, &REQUEST_HEADERS),
+        body: request_body,
+    };
+
+    let response = next
+        .run(Request::from_parts(parts, Body::from(bytes)))
+        .await;
+    if response.status() == StatusCode::SWITCHING_PROTOCOLS || !fits(response.body(), limit) {
+        return response;
+    }
+    let (parts, body) = response.into_parts();
+    let bytes = match to_bytes(body, limit).await {
+        Ok(bytes) => bytes,
+        Err(err) => {
+            tracing::error!("Failed to buffer response for recording: {}", err);
+            return StatusCode::INTERNAL_SERVER_ERROR.into_response();
+        }
+    };
+    let mut response_body = body_value(&parts.headers, &bytes);
+    redact(&mut response_body);
+    let recording = Recording {
+        id: String::new(),
+        method,
+        path,
+        query,
+        request,
+        response: RecordedResponse {
+            status: parts.status.as_u16(),
+            headers: recorded_headers(&parts.headers, &RESPONSE_HEADERS),
+            body: response_body,
+        },
+    };
+    write_recording(recording);
+
+    Response::from_parts(parts, Body::from(bytes))
+}
+
+// Seberapa ketat request dicocokkan dengan rekaman (REPLAY_MATCH)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum MatchMode {
+    // method, path, query dan body
+    Strict,
+    // method, path dan query
+    Query,
+    // method dan path
+    Path,
+}
+
+impl std::str::FromStr for MatchMode {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "strict" => Ok(MatchMode::Strict),
+            "query" => Ok(MatchMode::Query),
+            "path" => Ok(MatchMode::Path),
+            _ => Err(format!(
+                "Invalid REPLAY_MATCH '{}', expected strict, query or path",
+                value
+            )),
+        }
+    }
+}
+
+// Nilai yang disamarkan saat merekam cocok dengan nilai apa pun
+fn matches_value(recorded: &Value, actual: &Value) -> bool {
+    match (recorded, actual) {
+        (Value::String(value), _) if value == REDACTED => true,
+        (Value::Object(recorded), Value::Object(actual)) => {
+            recorded.len() == actual.len()
+                && recorded.iter().all(|(key, value)| {
+                    actual
+                        .get(key)
+                        .is_some_and(|actual| matches_value(value, actual))
+                })
+        }
+        (Value::Array(recorded), Value::Array(actual)) => {
+            recorded.len() == actual.len()
+                && recorded
+                    .iter()
+                    .zip(actual)
+                    .all(|(recorded, actual)| matches_value(recorded, actual))
+        }
+        _ => recorded == actual,
+    }
+}
+
+fn matches_query(recorded: &BTreeMap<String, String>, actual: &BTreeMap<String, String>) -> bool {
+    recorded.len() == actual.len()
+        && recorded.iter().all(|(key, value)| {
+            actual
+                .get(key)
+                .is_some_and(|actual| value == REDACTED || value == actual)
+        })
+}
+
+pub struct Replay {
+    pub mode: MatchMode,
+    recordings: Vec<Recording>,
+    // Rekaman yang sudah pernah diputar
+    served: Mutex<Vec<bool>>,
+}
+
+pub fn load_recordings(path: &str) -> Result<Vec<Recording>, String> {
+    let content = std::fs::read_to_string(path).map_err(|err| format!("{}: {}", path, err))?;
+    content
+        .lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty())
+        .map(|(i, line)| {
+            serde_json::from_str(line).map_err(|err| format!("{}:{}: {}", path, i + 1, err))
+        })
+        .collect()
+}
+
+impl Replay {
+    // REPLAY_FILE dan REPLAY_MATCH
+    pub fn from_env() -> Result<Self, String> {
+        dotenv::dotenv().ok();
+        let path = std::env::var("REPLAY_FILE")
+            .map_err(|_| "REPLAY_FILE must be set in replay mode".to_string())?;
+        let mode = match std::env::var("REPLAY_MATCH") {
+            Ok(value) => value.parse()?,
+            Err(_) => MatchMode::Strict,
+        };
+        let recordings = load_recordings(path.trim())?;
+
+        Ok(Replay {
+            mode,
+            served: Mutex::new(vec![false; recordings.len()]),
+            recordings,
+        })
+    }
+
+    pub fn recordings(&self) -> &[Recording] {
+        &self.recordings
+    }
+
+    // Rekaman yang cocok diputar sesuai urutan rekam; setelah habis rekaman
+    // terakhir diulang, jadi GET sebelum dan sesudah PUT tetap berbeda.
+    pub fn find(
+        &self,
+        method: &str,
+        path: &str,
+        query: &BTreeMap<String, String>,
+        body: &Value,
+    ) -> Option<&Recording> {
+        let candidates: Vec<usize> = self
+            .recordings
+            .iter()
+            .enumerate()
+            .filter(|(_, recording)| {
+                recording.method == method
+                    && recording.path == path
+                    && (self.mode == MatchMode::Path || matches_query(&recording.query, query))
+                    && (self.mode != MatchMode::Strict
+                        || matches_value(&recording.request.body, body))
+            })
+            .map(|(i, _)| i)
+            .collect();
+
+        let mut served = self.served.lock().unwrap();
+        let index = candidates
+            .iter()
+            .copied()
+            .find(|i| !served[*i])
+            .or_else(|| candidates.last().copied())?;
+        served[index] = true;
+        Some(&self.recordings[index])
+    }
+}
+
+static REPLAY: LazyLock<Replay> = LazyLock::new(|| match Replay::from_env() {
+    Ok(replay) => replay,
+    Err(err) => {
+        tracing::error!("❌ Invalid replay config: {}", err);
+        std::process::exit(1);
+    }
+});
+
+pub fn replay() -> &'static Replay {
+    &REPLAY
+}
+
+pub fn replay_response(recording: &Recording) -> Response {
+    let status = StatusCode::from_u16(recording.response.status).unwrap_or(StatusCode::OK);
+    let is_json = recording
+        .response
+        .headers
+        .get("content-type")
+        .is_some_and(|content_type| content_type.contains("json"));
+    let body = match &recording.response.body {
+        Value::Null => Vec::new(),
+        Value::String(text) if !is_json => text.clone().into_bytes(),
+        value => value.to_string().into_bytes(),
+    };
+
+    let mut response = (status, body).into_response();
+    let headers = response.headers_mut();
+    headers.remove(CONTENT_TYPE);
+    for (name, value) in &recording.response.headers {
+        if let (Ok(name), Ok(value)) = (
+            HeaderName::try_from(name.as_str()),
+            HeaderValue::from_str(value),
+        ) {
+            headers.insert(name, value);
+        }
+    }
+    response
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn redact_nested_fields() {
+        let mut body = json!({
+            "username": "budi",
+            "password": "Password123!",
+            "data": {
+                "Access_Token": "abc",
+                "mfa_token": null,
+                "items": [{ "recovery_codes": ["a", "b"], "phone": "+6281" }]
+            }
+        });
+        redact(&mut body);
+
+        assert_eq!(
+            body,
+            json!({
+                "username": "budi",
+                "password": REDACTED,
+                "data": {
+                    "Access_Token": REDACTED,
+                    // null dibiarkan agar rekaman tetap menunjukkan field kosong
+                    "mfa_token": null,
+                    "items": [{ "recovery_codes": REDACTED, "phone": "+6281" }]
+                }
+            })
+        );
+    }
+
+    #[test]
+    fn sensitive_headers_are_redacted() {
+        let mut headers = HeaderMap::new();
+        headers.insert("authorization", "Bearer abc".parse().unwrap());
+        headers.insert("content-type", "application/json".parse().unwrap());
+        headers.insert("x-request-id", "42".parse().unwrap());
+
+        let recorded = recorded_headers(&headers, &REQUEST_HEADERS);
+        assert_eq!(recorded.len(), 2);
+        assert_eq!(recorded["authorization"], REDACTED);
+        assert_eq!(recorded["content-type"], "application/json");
+    }
+
+    #[test]
+    fn redacted_values_match_anything() {
+        let recorded = json!({ "username": "budi", "password": REDACTED, "tags": [1, REDACTED] });
+
+        assert!(matches_value(
+            &recorded,
+            &json!({ "username": "budi", "password": "rahasia", "tags": [1, { "a": 2 }] })
+        ));
+        assert!(!matches_value(
+            &recorded,
+            &json!({ "username": "siti", "password": "rahasia", "tags": [1, 2] })
+        ));
+        // Field tambahan atau item yang kurang tidak cocok
+        assert!(!matches_value(
+            &recorded,
+            &json!({ "username": "budi", "password": "x", "tags": [1, 2], "role": "admin" })
+        ));
+        assert!(!matches_value(
+            &recorded,
+            &json!({ "username": "budi", "password": "x", "tags": [1] })
+        ));
+        assert!(!matches_value(&recorded, &json!({ "username": "budi" })));
+    }
+
+    #[test]
+    fn query_and_match_mode() {
+        let recorded = BTreeMap::from([
+            ("token".to_string(), REDACTED.to_string()),
+            ("page".to_string(), "2".to_string()),
+        ]);
+        let actual = |page: &str| {
+            BTreeMap::from([
+                ("token".to_string(), "abc".to_string()),
+                ("page".to_string(), page.to_string()),
+            ])
+        };
+        assert!(matches_query(&recorded, &actual("2")));
+        assert!(!matches_query(&recorded, &actual("3")));
+
+        assert_eq!(" Query ".parse::<MatchMode>(), Ok(MatchMode::Query));
+        assert!("fuzzy".parse::<MatchMode>().is_err());
+    }
+}
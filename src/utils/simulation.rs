@@ -0,0 +1,277 @@
+use axum::{
+    extract::{MatchedPath, Request},
+    http::{HeaderName, HeaderValue, StatusCode},
+    middleware::Next,
+    response::{IntoResponse, Response},
+    Json,
+};
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::HashMap,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        LazyLock, Mutex,
+    },
+    time::Duration,
+};
+
+use crate::utils::versioning::unversioned_route;
+
+// Penanda response yang kegagalannya dibuat oleh simulator
+const X_SIMULATED_FAULT: HeaderName = HeaderName::from_static("x-simulated-fault");
+
+// Route admin simulator tidak pernah diberi latensi atau error buatan
+const SIMULATION_ROUTES: &str = "/api/simulation";
+
+const DEFAULT_JWT_SECRET: &str = "api-simu-simulation";
+
+// Satu aturan di file skenario, mis. 500 pada pemanggilan ke-3 PUT /api/user/:id
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ScenarioRule {
+    // "METHOD /path" sesuai pola route, atau "*" untuk semua route
+    pub route: String,
+    // Pemanggilan ke-N (mulai dari 1) sejak start atau reset; kosong berarti setiap pemanggilan
+    #[serde(default)]
+    pub call: Option<u64>,
+    #[serde(default = "default_fault_status")]
+    pub status: u16,
+    #[serde(default)]
+    pub body: Option<serde_json::Value>,
+    #[serde(default)]
+    pub delay_ms: Option<u64>,
+}
+
+fn default_fault_status() -> u16 {
+    500
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct ScenarioFile {
+    #[serde(default)]
+    rules: Vec<ScenarioRule>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SimulationConfig {
+    pub seed: u64,
+    pub users: usize,
+    // Rentang latensi buatan (min, max) dalam milidetik
+    pub latency_ms: (u64, u64),
+    // Peluang 0..1 response 500 per route, "*" untuk route lain
+    pub error_rates: HashMap<String, f64>,
+    pub scenario: Vec<ScenarioRule>,
+    // JWT_SECRET bila diisi, selain itu secret tetap agar --simulate jalan tanpa .env
+    #[serde(skip)]
+    pub jwt_secret: String,
+}
+
+fn env_or<T: std::str::FromStr>(name: &str, default: T) -> T {
+    std::env::var(name)
+        .ok()
+        .and_then(|v| v.parse().ok())
+        .unwrap_or(default)
+}
+
+// "100" atau "50-200"
+fn parse_latency(value: &str) -> Result<(u64, u64), String> {
+    let invalid = || format!("Invalid SIMULATE_LATENCY_MS '{}'", value);
+    let (min, max) = value.split_once('-').unwrap_or((value, value));
+    let min: u64 = min.trim().parse().map_err(|_| invalid())?;
+    let max: u64 = max.trim().parse().map_err(|_| invalid())?;
+    if min > max {
+        return Err(invalid());
+    }
+    Ok((min, max))
+}
+
+// "*=0.01,PUT /api/user/:id=0.2"
+fn parse_error_rates(value: &str) -> Result<HashMap<String, f64>, String> {
+    value
+        .split(',')
+        .filter(|entry| !entry.trim().is_empty())
+        .map(|entry| {
+            let invalid = || format!("Invalid SIMULATE_ERROR_RATES entry '{}'", entry);
+            let (route, rate) = entry.rsplit_once('=').ok_or_else(invalid)?;
+            let rate: f64 = rate.trim().parse().map_err(|_| invalid())?;
+            if !(0.0..=1.0).contains(&rate) {
+                return Err(invalid());
+            }
+            Ok((route.trim().to_string(), rate))
+        })
+        .collect()
+}
+
+fn load_scenario(path: &str) -> Result<Vec<ScenarioRule>, String> {
+    let content = std::fs::read_to_string(path).map_err(|err| format!("{}: {}", path, err))?;
+    let scenario: ScenarioFile = if path.ends_with(".json") {
+        serde_json::from_str(&content).map_err(|err| format!("{}: {}", path, err))?
+    } else {
+        serde_yaml::from_str(&content).map_err(|err| format!("{}: {}", path, err))?
+    };
+    if let Some(rule) = scenario
+        .rules
+        .iter()
+        .find(|rule| StatusCode::from_u16(rule.status).is_err())
+    {
+        return Err(format!("{}: invalid status {}", path, rule.status));
+    }
+    Ok(scenario.rules)
+}
+
+impl SimulationConfig {
+    // SIMULATE_SEED, SIMULATE_USERS, SIMULATE_LATENCY_MS, SIMULATE_ERROR_RATES, SIMULATE_SCENARIO,
+    // JWT_SECRET
+    pub fn from_env() -> Result<Self, String> {
+        dotenv::dotenv().ok();
+        let latency_ms = match std::env::var("SIMULATE_LATENCY_MS") {
+            Ok(value) => parse_latency(&value)?,
+            Err(_) => (0, 0),
+        };
+        let error_rates = match std::env::var("SIMULATE_ERROR_RATES") {
+            Ok(value) => parse_error_rates(&value)?,
+            Err(_) => HashMap::new(),
+        };
+        let scenario = match std::env::var("SIMULATE_SCENARIO") {
+            Ok(path) if !path.trim().is_empty() => load_scenario(path.trim())?,
+            _ => Vec::new(),
+        };
+
+        Ok(SimulationConfig {
+            seed: env_or("SIMULATE_SEED", 42),
+            users: env_or("SIMULATE_USERS", 25),
+            latency_ms,
+            error_rates,
+            scenario,
+            jwt_secret: std::env::var("JWT_SECRET")
+                .unwrap_or_else(|_| DEFAULT_JWT_SECRET.to_string()),
+        })
+    }
+
+    fn error_rate(&self, route: &str) -> f64 {
+        self.error_rates
+            .get(route)
+            .or_else(|| self.error_rates.get("*"))
+            .copied()
+            .unwrap_or(0.0)
+    }
+
+    fn scenario_rule(&self, route: &str, call: u64) -> Option<&ScenarioRule> {
+        self.scenario.iter().find(|rule| {
+            (rule.route == route || rule.route == "*") && rule.call.is_none_or(|n| n == call)
+        })
+    }
+}
+
+struct Simulation {
+    config: SimulationConfig,
+    // Jumlah pemanggilan per route, dipakai oleh aturan skenario "call"
+    calls: Mutex<HashMap<String, u64>>,
+}
+
+static SIMULATION: LazyLock<Simulation> = LazyLock::new(|| {
+    let config = match SimulationConfig::from_env() {
+        Ok(config) => config,
+        Err(err) => {
+            tracing::error!("❌ Invalid simulation config: {}", err);
+            std::process::exit(1);
+        }
+    };
+
+    Simulation {
+        config,
+        calls: Mutex::new(HashMap::new()),
+    }
+});
+
+static ENABLED: AtomicBool = AtomicBool::new(false);
+
+// Dinyalakan saat state mode --simulate dipasang
+pub fn enable_simulation() {
+    ENABLED.store(true, Ordering::Relaxed);
+}
+
+pub fn simulation_enabled() -> bool {
+    ENABLED.load(Ordering::Relaxed)
+}
+
+pub fn simulation_config() -> &'static SimulationConfig {
+    &SIMULATION.config
+}
+
+pub fn simulation_calls() -> HashMap<String, u64> {
+    SIMULATION.calls.lock().unwrap().clone()
+}
+
+pub fn reset_simulation_calls() {
+    SIMULATION.calls.lock().unwrap().clear();
+}
+
+fn fault_response(
+    status: StatusCode,
+    body: Option<serde_json::Value>,
+    kind: &'static str,
+) -> Response {
+    let body = body.unwrap_or_else(|| {
+        serde_json::json!({
+            "status": "error",
+            "message": "Simulated failure"
+        })
+    });
+    let mut response = (status, Json(body)).into_response();
+    response
+        .headers_mut()
+        .insert(X_SIMULATED_FAULT, HeaderValue::from_static(kind));
+    response
+}
+
+// Latensi, aturan skenario lalu error acak, dalam urutan itu
+pub async fn simulate_faults(req: Request, next: Next) -> Response {
+    let path = req
+        .extensions()
+        .get::<MatchedPath>()
+        .map(|path| unversioned_route(path.as_str()))
+        .unwrap_or_else(|| unversioned_route(req.uri().path()));
+    if path.starts_with(SIMULATION_ROUTES) {
+        return next.run(req).await;
+    }
+
+    let simulation = &*SIMULATION;
+    let route = format!("{} {}", req.method(), path);
+    let call = {
+        let mut calls = simulation.calls.lock().unwrap();
+        let count = calls.entry(route.clone()).or_default();
+        *count += 1;
+        *count
+    };
+
+    let config = &simulation.config;
+    let (min, max) = config.latency_ms;
+    let (latency, fail) = {
+        let mut rng = rand::thread_rng();
+        (
+            rng.gen_range(min..=max),
+            rng.gen_bool(config.error_rate(&route)),
+        )
+    };
+    if latency > 0 {
+        tokio::time::sleep(Duration::from_millis(latency)).await;
+    }
+
+    if let Some(rule) = config.scenario_rule(&route, call) {
+        tracing::info!(route = %route, call, status = rule.status, "Scenario rule applied");
+        if let Some(delay) = rule.delay_ms {
+            tokio::time::sleep(Duration::from_millis(delay)).await;
+        }
+        let status = StatusCode::from_u16(rule.status).unwrap_or(StatusCode::INTERNAL_SERVER_ERROR);
+        return fault_response(status, rule.body.clone(), "scenario");
+    }
+    if fail {
+        tracing::info!(route = %route, call, "Injected random error");
+        return fault_response(StatusCode::INTERNAL_SERVER_ERROR, None, "error-rate");
+    }
+
+    next.run(req).await
+}
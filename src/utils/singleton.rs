@@ -1,33 +1,103 @@
 use std::sync::Arc;
 use sqlx::mysql::MySqlPool;
+use tokio::sync::OnceCell;
+use crate::service::user::user_repository::{MySqlUserRepository, UserRepository};
 use crate::utils::{
     adapter::connection,
+    audit::AuditObserver,
+    event_hub::EventHub,
     factory::new_mysql_query_builder,
+    idempotency::{IdempotencyStore, MySqlIdempotencyStore},
+    lockout::{MySqlThrottleStore, ThrottleStore},
+    mailer::{new_mailer, Mailer},
+    metrics::MetricsObserver,
     observer::{LoggerObserver, Observable},
     factory::QueryBuilder,
+    presence::Presence,
+    sms::{new_sms_sender, SmsSender},
 };
 
 pub struct AppState {
     pub db: MySqlPool,
     pub observable: Observable,
     pub query_builder: Arc<dyn QueryBuilder>,
+    pub event_hub: Arc<EventHub>,
+    pub presence: Presence,
+    pub mailer: Arc<dyn Mailer>,
+    pub sms: Arc<dyn SmsSender>,
+    pub users: Arc<dyn UserRepository>,
+    pub throttles: Arc<dyn ThrottleStore>,
+    pub idempotency: Arc<dyn IdempotencyStore>,
 }
 
+// Penyimpanan yang bisa diganti, mis. dengan versi di memori untuk mode --simulate
+pub struct Stores {
+    pub users: Arc<dyn UserRepository>,
+    pub throttles: Arc<dyn ThrottleStore>,
+    pub idempotency: Arc<dyn IdempotencyStore>,
+}
+
+// Satu instance AppState untuk seluruh aplikasi
+static INSTANCE: OnceCell<Arc<AppState>> = OnceCell::const_new();
+
 pub async fn init_app_state() -> Arc<AppState> {
-    get_app_state().await.unwrap()
+    INSTANCE
+        .get_or_init(|| async { get_app_state().await.unwrap() })
+        .await
+        .clone()
 }
 
-pub async fn get_app_state() -> Result<Arc<AppState>, sqlx::Error> {
+// Mode --simulate memasang state-nya sendiri sebelum request pertama; bila sudah ada
+// state yang terpasang, state itu yang dipakai
+pub fn install_app_state(app_state: impl FnOnce() -> AppState) -> Arc<AppState> {
+    if INSTANCE.get().is_none() {
+        let _ = INSTANCE.set(Arc::new(app_state()));
+    }
+    INSTANCE.get().unwrap().clone()
+}
+
+pub fn new_app_state(
+    db: MySqlPool,
+    query_builder: Arc<dyn QueryBuilder>,
+    stores: Stores,
+) -> AppState {
     let observable = Observable::new();
     let logger_observer = Arc::new(LoggerObserver);
     observable.add_observer(logger_observer.clone());
 
-    let pool = connection().await.unwrap();
+    let event_hub = Arc::new(EventHub::new(256));
+    observable.add_observer(event_hub.clone());
+    observable.add_observer(Arc::new(MetricsObserver));
 
-    let app_state = Arc::new(AppState {
-        db: pool,
+    AppState {
+        db,
         observable,
-        query_builder: new_mysql_query_builder(), 
-    });
-    Ok(app_state)
+        query_builder,
+        event_hub,
+        presence: Presence::new(),
+        mailer: new_mailer(),
+        sms: new_sms_sender(),
+        users: stores.users,
+        throttles: stores.throttles,
+        idempotency: stores.idempotency,
+    }
+}
+
+pub async fn get_app_state() -> Result<Arc<AppState>, sqlx::Error> {
+    let pool = connection().await.unwrap();
+    let query_builder = new_mysql_query_builder();
+    let stores = Stores {
+        users: Arc::new(MySqlUserRepository::new(
+            pool.clone(),
+            query_builder.clone(),
+        )),
+        throttles: Arc::new(MySqlThrottleStore::new(pool.clone())),
+        idempotency: Arc::new(MySqlIdempotencyStore::new(pool.clone())),
+    };
+
+    let app_state = new_app_state(pool.clone(), query_builder, stores);
+    app_state
+        .observable
+        .add_observer(Arc::new(AuditObserver::new(pool)));
+    Ok(Arc::new(app_state))
 }
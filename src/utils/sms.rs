@@ -0,0 +1,38 @@
+use std::sync::Arc;
+
+use crate::utils::mailer::log_message_body;
+
+#[derive(Debug, Clone)]
+pub struct Sms {
+    pub to: String,
+    pub body: String,
+}
+
+pub trait SmsSender: Send + Sync {
+    fn send(&self, sms: &Sms) -> Result<(), String>;
+}
+
+// Hanya menulis ke log, untuk development. Isi pesan berisi kode verifikasi,
+// jadi hanya ditulis bila log_body (LOG_MESSAGE_BODY=true)
+pub struct LogSmsSender {
+    log_body: bool,
+}
+
+impl SmsSender for LogSmsSender {
+    fn send(&self, sms: &Sms) -> Result<(), String> {
+        if self.log_body {
+            tracing::info!(to = %sms.to, "📱 {}", sms.body);
+        } else {
+            tracing::info!(to = %sms.to, "📱 SMS sent");
+        }
+        Ok(())
+    }
+}
+
+// Belum ada gateway SMS, semua pesan ditulis ke log
+pub fn new_sms_sender() -> Arc<dyn SmsSender> {
+    dotenv::dotenv().ok();
+    Arc::new(LogSmsSender {
+        log_body: log_message_body(),
+    })
+}
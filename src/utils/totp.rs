@@ -0,0 +1,56 @@
+use rand::RngCore;
+use totp_rs::{Algorithm, Secret, TOTP};
+
+const DIGITS: usize = 6;
+const STEP: u64 = 30;
+// Toleransi satu langkah sebelum/sesudah untuk jam yang tidak sinkron
+const SKEW: i64 = 1;
+
+fn issuer() -> String {
+    std::env::var("TOTP_ISSUER").unwrap_or_else(|_| "API-SIMU".to_string())
+}
+
+fn totp(secret: &str, account: &str) -> Result<TOTP, String> {
+    let secret = Secret::Encoded(secret.to_string())
+        .to_bytes()
+        .map_err(|err| err.to_string())?;
+    TOTP::new(
+        Algorithm::SHA1,
+        DIGITS,
+        SKEW as u8,
+        STEP,
+        secret,
+        Some(issuer()),
+        account.to_string(),
+    )
+    .map_err(|err| err.to_string())
+}
+
+// Secret 160 bit dalam base32, format yang dipakai aplikasi authenticator
+pub fn generate_secret() -> String {
+    let mut bytes = [0u8; 20];
+    rand::thread_rng().fill_bytes(&mut bytes);
+    Secret::Raw(bytes.to_vec()).to_encoded().to_string()
+}
+
+pub fn otpauth_uri(secret: &str, account: &str) -> Result<String, String> {
+    Ok(totp(secret, account)?.get_url())
+}
+
+// Mengembalikan time step yang cocok; step <= last_step ditolak agar kode tidak bisa dipakai ulang
+pub fn verify_code(
+    secret: &str,
+    code: &str,
+    last_step: Option<i64>,
+) -> Result<Option<i64>, String> {
+    let totp = totp(secret, "")?;
+    let now = chrono::Utc::now().timestamp();
+    let current = now / STEP as i64;
+
+    let step = (current - SKEW..=current + SKEW).find(|step| {
+        last_step.is_none_or(|last| *step > last)
+            && totp.generate((*step as u64) * STEP) == code.trim()
+    });
+
+    Ok(step)
+}
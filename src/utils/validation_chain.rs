@@ -1,9 +1,12 @@
 use regex::Regex;
-use tokio::sync::OnceCell;
+use serde_json::{Map, Value};
+use sqlx::Row;
 use std::collections::HashMap;
-use std::sync::Arc;
+use std::sync::{Arc, LazyLock};
+use tokio::sync::OnceCell;
+use tracing::Instrument;
 
-use crate::utils::singleton::AppState;
+use crate::utils::{adapter::sql_span, metrics::metrics, singleton::AppState};
 
 use super::singleton::init_app_state;
 
@@ -13,6 +16,45 @@ pub struct ValidationChain {
 
 static APP_STATE: OnceCell<Arc<AppState>> = OnceCell::const_new();
 
+static EMAIL_REGEX: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"^[a-zA-Z0-9._%+-]+@[a-zA-Z0-9.-]+\.[a-zA-Z]{2,}$").unwrap());
+// Simple phone number regex
+static PHONE_REGEX: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"^\+?[1-9]\d{1,14}$").unwrap());
+// Rule "contains": setiap kelas karakter ASCII harus muncul minimal sekali. Validasi
+// dan pattern JSON Schema sama-sama dibentuk dari daftar ini
+const CONTAINS_CLASSES: [(&str, &str); 4] = [
+    ("an uppercase letter", "A-Z"),
+    ("a lowercase letter", "a-z"),
+    ("a number", "0-9"),
+    // Tanda baca ASCII, huruf non-ASCII seperti "é" tidak dihitung
+    ("a special character", r"!-/:-@\[-`{-~"),
+];
+static CONTAINS_REGEXES: LazyLock<Vec<(&str, Regex)>> = LazyLock::new(|| {
+    CONTAINS_CLASSES
+        .iter()
+        .map(|(name, class)| (*name, Regex::new(&format!("[{}]", class)).unwrap()))
+        .collect()
+});
+
+// ECMA-262, boleh lookahead
+fn contains_pattern() -> String {
+    let lookaheads: String = CONTAINS_CLASSES
+        .iter()
+        .map(|(_, class)| format!("(?=.*[{}])", class))
+        .collect();
+    format!("^{}.*$", lookaheads)
+}
+
+fn check_contains(field: &str, value: &str) -> Result<(), String> {
+    match CONTAINS_REGEXES
+        .iter()
+        .find(|(_, regex)| !regex.is_match(value))
+    {
+        Some((name, _)) => Err(format!("{} must contain {}", field, name)),
+        None => Ok(()),
+    }
+}
+
 impl ValidationChain {
     pub fn new() -> Self {
         ValidationChain {
@@ -28,7 +70,7 @@ impl ValidationChain {
 
         self.rules
             .entry(field.to_string())
-            .or_insert_with(Vec::new)
+            .or_default()
             .push(rule_string);
 
         self
@@ -38,8 +80,20 @@ impl ValidationChain {
         self.rules.get(field)
     }
 
+    // Rule milik field yang dapat dinyatakan sebagai JSON Schema, untuk dokumen OpenAPI
+    pub fn json_schema(&self, field: &str) -> Map<String, Value> {
+        let mut schema = Map::new();
+        for rule in self.get_rules(field).into_iter().flatten() {
+            let (rule_name, rule_value) = match rule.split_once(':') {
+                Some((name, value)) => (name, Some(value)),
+                None => (rule.as_str(), None),
+            };
+            rule_json_schema(&mut schema, rule_name, rule_value);
+        }
+        schema
+    }
+
     pub async fn validate(&self, fields_values: &[(&str, &str)], table: &str) -> Result<(), String> {
-        let app_state: Arc<AppState> = APP_STATE.get_or_init(init_app_state).await.clone();
         for (field, value) in fields_values {
             let rules = match self.get_rules(field) {
                 Some(rules) => rules,
@@ -49,97 +103,19 @@ impl ValidationChain {
             for rule in rules {
                 let parts: Vec<&str> = rule.split(':').collect();
                 let rule_name = parts[0];
-                let rule_value = parts.get(1).map(|v| *v);
-
-                match rule_name {
-                    "required" => {
-                        if value.is_empty() {
-                            return Err(format!("{} is required", field));
-                        }
-                    }
-                    "min_length" => {
-                        if let Some(min_length_str) = rule_value {
-                            let min_length = min_length_str
-                                .parse::<usize>()
-                                .map_err(|_| format!("Invalid min_length value for {}", field))?;
-                            if value.len() < min_length {
-                                return Err(format!(
-                                    "{} must be at least {} characters long",
-                                    field, min_length
-                                ));
-                            }
-                        }
-                    }
-                    "max_length" => {
-                        if let Some(max_length_str) = rule_value {
-                            let max_length = max_length_str
-                                .parse::<usize>()
-                                .map_err(|_| format!("Invalid max_length value for {}", field))?;
-                            if value.len() > max_length {
-                                return Err(format!(
-                                    "{} must be at most {} characters long",
-                                    field, max_length
-                                ));
-                            }
-                        }
-                    }
-                    "email" => {
-                        let re =
-                            Regex::new(r"^[a-zA-Z0-9._%+-]+@[a-zA-Z0-9.-]+\.[a-zA-Z]{2,}$").unwrap();
-                        if !re.is_match(value) {
-                            return Err(format!("{} is not a valid email", field));
-                        }
-                    }
-                    "contains" => {
-                        let mut has_uppercase = false;
-                        let mut has_lowercase = false;
-                        let mut has_number = false;
-                        let mut has_special_char = false;
-                        for c in value.chars() {
-                            if c.is_ascii_uppercase() {
-                                has_uppercase = true;
-                            } else if c.is_ascii_lowercase() {
-                                has_lowercase = true;
-                            } else if c.is_ascii_digit() {
-                                has_number = true;
-                            } else if !c.is_ascii_alphanumeric() {
-                                has_special_char = true;
-                            }
-                        }
-                        if !has_uppercase {
-                            return Err(format!("{} must contain an uppercase letter", field));
-                        }
-                        if !has_lowercase {
-                            return Err(format!("{} must contain a lowercase letter", field));
-                        }
-                        if !has_number {
-                            return Err(format!("{} must contain a number", field));
-                        }
-                        if !has_special_char {
-                            return Err(format!("{} must contain a special character", field));
-                        }
-                    }
-                    "unique" => {
-                        let count: (i64,) = sqlx::query_as(&format!(
-                            "SELECT COUNT(*) FROM {} WHERE {} = ?",
-                            table, field
-                        ))
-                        .bind(value)
-                        .fetch_one(&app_state.db)
-                        .await
-                        .map_err(|_| format!("Failed to check uniqueness of {}", field))?;
-
-                        if count.0 > 0 {
-                            return Err(format!("{} is already taken", field));
-                        }
-                    }
-                    "phone" => {
-                        let re = Regex::new(r"^\+?[1-9]\d{1,14}$").unwrap(); // Simple phone number regex
-                        if !re.is_match(value) {
-                            return Err(format!("{} is not a valid phone number", field));
-                        }
-                    }
-                    _ => {}
+                let rule_value = parts.get(1).copied();
+
+                let span =
+                    tracing::info_span!("validation_rule", field = %field, rule = %rule_name);
+                if let Err(err) = check_rule(field, value, rule_name, rule_value, table)
+                    .instrument(span)
+                    .await
+                {
+                    metrics()
+                        .validation_failures_total
+                        .with_label_values(&[rule_name])
+                        .inc();
+                    return Err(err);
                 }
             }
         }
@@ -175,3 +151,146 @@ impl ValidationChain {
         }
     }
 }
+
+// id baris aktif yang memakai value; pengguna lewat AppState.users agar juga berlaku
+// di mode --simulate
+async fn find_existing(table: &str, field: &str, value: &str) -> Result<Option<i64>, String> {
+    let app_state: Arc<AppState> = APP_STATE.get_or_init(init_app_state).await.clone();
+    if table == "users" {
+        let user = app_state.users.find_by_field(field, value).await?;
+        return Ok(user.map(|user| user.id as i64));
+    }
+
+    // Baris yang sudah di-soft delete tidak dihitung
+    let query = app_state.query_builder.select_by_field(table, field);
+    let row = sqlx::query(&query)
+        .bind(value)
+        .fetch_optional(&app_state.db)
+        .instrument(sql_span(&query))
+        .await
+        .map_err(|err: sqlx::Error| err.to_string())?;
+    row.map(|row| row.try_get::<i64, _>("id"))
+        .transpose()
+        .map_err(|err| err.to_string())
+}
+
+async fn check_rule(
+    field: &str,
+    value: &str,
+    rule_name: &str,
+    rule_value: Option<&str>,
+    table: &str,
+) -> Result<(), String> {
+    match rule_name {
+        "required" if value.is_empty() => {
+            return Err(format!("{} is required", field));
+        }
+        "min_length" => {
+            if let Some(min_length_str) = rule_value {
+                let min_length = min_length_str
+                    .parse::<usize>()
+                    .map_err(|_| format!("Invalid min_length value for {}", field))?;
+                if value.len() < min_length {
+                    return Err(format!(
+                        "{} must be at least {} characters long",
+                        field, min_length
+                    ));
+                }
+            }
+        }
+        "max_length" => {
+            if let Some(max_length_str) = rule_value {
+                let max_length = max_length_str
+                    .parse::<usize>()
+                    .map_err(|_| format!("Invalid max_length value for {}", field))?;
+                if value.len() > max_length {
+                    return Err(format!(
+                        "{} must be at most {} characters long",
+                        field, max_length
+                    ));
+                }
+            }
+        }
+        "email" if !EMAIL_REGEX.is_match(value) => {
+            return Err(format!("{} is not a valid email", field));
+        }
+        "contains" => check_contains(field, value)?,
+        // "unique:7": baris dengan id 7 (yang sedang diubah) tidak dihitung
+        "unique" => {
+            let except = rule_value.and_then(|id| id.parse::<i64>().ok());
+            if find_existing(table, field, value)
+                .await
+                .map_err(|_| format!("Failed to check uniqueness of {}", field))?
+                .is_some_and(|id| Some(id) != except)
+            {
+                return Err(format!("{} is already taken", field));
+            }
+        }
+        "phone" if !PHONE_REGEX.is_match(value) => {
+            return Err(format!("{} is not a valid phone number", field));
+        }
+        _ => {}
+    }
+    Ok(())
+}
+
+// minLength, maxLength, format dan pattern; rule lain (required, unique) tidak punya padanan
+pub fn rule_json_schema(
+    schema: &mut Map<String, Value>,
+    rule_name: &str,
+    rule_value: Option<&str>,
+) {
+    let length = rule_value.and_then(|value| value.parse::<usize>().ok());
+    match (rule_name, length) {
+        ("min_length", Some(length)) => {
+            schema.insert("minLength".to_string(), length.into());
+        }
+        ("max_length", Some(length)) => {
+            schema.insert("maxLength".to_string(), length.into());
+        }
+        ("email", _) => {
+            schema.insert("format".to_string(), "email".into());
+            schema.insert("pattern".to_string(), EMAIL_REGEX.as_str().into());
+        }
+        ("phone", _) => {
+            schema.insert("pattern".to_string(), PHONE_REGEX.as_str().into());
+        }
+        ("contains", _) => {
+            schema.insert("pattern".to_string(), contains_pattern().into());
+        }
+        _ => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn contains_rule_and_pattern_share_classes() {
+        assert_eq!(check_contains("password", "Rahasia1!"), Ok(()));
+        assert_eq!(
+            check_contains("password", "rahasia1!"),
+            Err("password must contain an uppercase letter".to_string())
+        );
+        assert_eq!(
+            check_contains("password", "Rahasia!!"),
+            Err("password must contain a number".to_string())
+        );
+        // Huruf non-ASCII dan spasi bukan karakter khusus
+        for value in ["Rahasia1é", "Rahasia 1"] {
+            assert_eq!(
+                check_contains("password", value),
+                Err("password must contain a special character".to_string())
+            );
+        }
+        for special in "!\"#$%&'()*+,-./:;<=>?@[\\]^_`{|}~".chars() {
+            assert_eq!(check_contains("p", &format!("Ab1{}", special)), Ok(()));
+        }
+
+        assert_eq!(
+            contains_pattern(),
+            r"^(?=.*[A-Z])(?=.*[a-z])(?=.*[0-9])(?=.*[!-/:-@\[-`{-~]).*$"
+        );
+    }
+}
@@ -0,0 +1,107 @@
+use std::sync::LazyLock;
+
+use axum::{
+    extract::{OriginalUri, Request},
+    http::{header::LINK, HeaderName, HeaderValue},
+    middleware::Next,
+    response::Response,
+    Router,
+};
+use chrono::{NaiveDate, NaiveTime};
+
+pub const API_PREFIX: &str = "/api";
+pub const API_V1: &str = "/api/v1";
+pub const API_V2: &str = "/api/v2";
+
+pub const DEPRECATION: HeaderName = HeaderName::from_static("deprecation");
+pub const SUNSET: HeaderName = HeaderName::from_static("sunset");
+
+const DEFAULT_DEPRECATED_AT: &str = "2026-10-19";
+const DEFAULT_SUNSET_AT: &str = "2027-04-19";
+
+// v1 di /api/v1 dan di /api (alias lama, deprecated), v2 di /api/v2
+pub fn mount_versions<S>(v1: Router<S>, v2: Router<S>) -> Router<S>
+where
+    S: Clone + Send + Sync + 'static,
+{
+    Router::new()
+        .nest(API_V1, v1.clone())
+        .nest(API_V2, v2)
+        .nest(
+            API_PREFIX,
+            v1.layer(axum::middleware::from_fn(deprecated_alias)),
+        )
+}
+
+// "/api/v1/user/:id" dan "/api/user/:id" menjadi "/api/user/:id", jadi aturan rate limit
+// dan skenario simulasi berlaku untuk semua versi
+pub fn unversioned_route(path: &str) -> String {
+    for prefix in [API_V1, API_V2] {
+        if let Some(rest) = path.strip_prefix(prefix) {
+            if rest.is_empty() || rest.starts_with('/') {
+                return format!("{}{}", API_PREFIX, rest);
+            }
+        }
+    }
+    path.to_string()
+}
+
+struct Deprecation {
+    deprecation: HeaderValue,
+    sunset: HeaderValue,
+}
+
+fn date_env(name: &str, default: &str) -> NaiveDate {
+    std::env::var(name)
+        .ok()
+        .and_then(
+            |value| match NaiveDate::parse_from_str(&value, "%Y-%m-%d") {
+                Ok(date) => Some(date),
+                Err(err) => {
+                    tracing::warn!("Ignoring {}={}: {}", name, value, err);
+                    None
+                }
+            },
+        )
+        .unwrap_or_else(|| NaiveDate::parse_from_str(default, "%Y-%m-%d").unwrap())
+}
+
+// API_DEPRECATED_AT dan API_SUNSET_AT dalam format YYYY-MM-DD
+static DEPRECATION_HEADERS: LazyLock<Deprecation> = LazyLock::new(|| {
+    dotenv::dotenv().ok();
+    let deprecated_at = date_env("API_DEPRECATED_AT", DEFAULT_DEPRECATED_AT);
+    let sunset_at = date_env("API_SUNSET_AT", DEFAULT_SUNSET_AT);
+
+    // RFC 9745: "@<unix timestamp>", RFC 8594: HTTP-date
+    let deprecated_at = deprecated_at.and_time(NaiveTime::MIN).and_utc();
+    let sunset_at = sunset_at.and_time(NaiveTime::MIN).and_utc();
+    Deprecation {
+        deprecation: HeaderValue::from_str(&format!("@{}", deprecated_at.timestamp())).unwrap(),
+        sunset: HeaderValue::from_str(&sunset_at.format("%a, %d %b %Y %H:%M:%S GMT").to_string())
+            .unwrap(),
+    }
+});
+
+// Response dari /api tanpa versi menunjuk ke route yang sama di /api/v1
+pub async fn deprecated_alias(req: Request, next: Next) -> Response {
+    // uri() di dalam router yang di-nest sudah tanpa prefix
+    let path = req
+        .extensions()
+        .get::<OriginalUri>()
+        .map(|OriginalUri(uri)| uri.path().to_string())
+        .unwrap_or_else(|| format!("{}{}", API_PREFIX, req.uri().path()));
+    let successor = path
+        .strip_prefix(API_PREFIX)
+        .map(|rest| format!("<{}{}>; rel=\"successor-version\"", API_V1, rest))
+        .and_then(|link| HeaderValue::from_str(&link).ok());
+
+    let mut response = next.run(req).await;
+    let headers = response.headers_mut();
+    headers.insert(DEPRECATION, DEPRECATION_HEADERS.deprecation.clone());
+    headers.insert(SUNSET, DEPRECATION_HEADERS.sunset.clone());
+    // Link pagination tetap ada
+    if let Some(successor) = successor {
+        headers.append(LINK, successor);
+    }
+    response
+}